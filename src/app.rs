@@ -1,35 +1,189 @@
-use crate::cli::ThemeName;
-use crate::keys::KeyBindings;
-use crate::theme::{themes, Theme};
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use crate::cli::CvdSimulation;
+use crate::color::ColorDepth;
+use crate::keys::{self, KeyAction, KeyBindings, KeyContext, KeySequence, KeySpec, SequenceMatch};
+use crate::theme::Theme;
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// How long a success message stays in the footer.
+const STATUS_TTL: Duration = Duration::from_secs(3);
+
+/// A footer message, e.g. the outcome of a config reload. Errors stay until replaced so a
+/// broken config can't go unnoticed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusMessage {
+    pub text: String,
+    pub is_error: bool,
+    expires_at: Option<Instant>,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DemoPanel {
     Overview,
     List,
+    Keys,
 }
 
 impl DemoPanel {
+    /// Tab order.
+    pub const ALL: [DemoPanel; 3] = [DemoPanel::Overview, DemoPanel::List, DemoPanel::Keys];
+
     fn next(self) -> Self {
-        match self {
-            DemoPanel::Overview => DemoPanel::List,
-            DemoPanel::List => DemoPanel::Overview,
-        }
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
     }
 
     fn prev(self) -> Self {
-        match self {
-            DemoPanel::Overview => DemoPanel::List,
-            DemoPanel::List => DemoPanel::Overview,
-        }
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
     }
 
     pub fn index(self) -> usize {
         match self {
             DemoPanel::Overview => 0,
             DemoPanel::List => 1,
+            DemoPanel::Keys => 2,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            DemoPanel::Overview => "Overview",
+            DemoPanel::List => "List",
+            DemoPanel::Keys => "Keys",
+        }
+    }
+}
+
+/// The Keys panel: pick one binding of an action, type its new keys, save to the config file.
+///
+/// Rows are every action in its home context followed by the `[keys.<context>]` entries. The
+/// selected row lists all of its bindings plus a slot for adding one, so a capture replaces or
+/// adds a single binding and leaves the others alone. Edits go to a draft keymap that is
+/// validated after every change; the live keymap only takes the draft while it is valid, so a
+/// conflicting binding never breaks the running demo.
+#[derive(Debug, Clone)]
+pub struct KeyEditor {
+    selected: usize,
+    /// The selected binding of the selected row; one past its last binding is the add slot.
+    binding: usize,
+    /// Keys typed so far while capturing, and when the capture ends unless another key follows.
+    captured: Option<Vec<KeySpec>>,
+    capture_deadline: Option<Instant>,
+    draft: KeyBindings,
+    /// Rows changed since the last save, in the order they were changed.
+    edited: Vec<(KeyContext, KeyAction)>,
+    error: Option<String>,
+}
+
+impl KeyEditor {
+    fn new(keymap: &KeyBindings) -> Self {
+        Self {
+            selected: 0,
+            binding: 0,
+            captured: None,
+            capture_deadline: None,
+            draft: keymap.clone(),
+            edited: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Each action in its home context, then the `[keys.<context>]` entries of the draft.
+    pub fn rows(&self) -> Vec<(KeyContext, KeyAction)> {
+        KeyAction::ALL
+            .into_iter()
+            .map(|action| (action.home_context(), action))
+            .chain(
+                self.draft
+                    .scoped
+                    .iter()
+                    .map(|binding| (binding.context, binding.action)),
+            )
+            .collect()
+    }
+
+    pub fn selected_row(&self) -> (KeyContext, KeyAction) {
+        self.rows()[self.selected]
+    }
+
+    pub fn selected_action(&self) -> KeyAction {
+        self.selected_row().1
+    }
+
+    /// Index of the selected binding in the selected row; equal to the row's length on the add
+    /// slot.
+    pub fn selected_binding(&self) -> usize {
+        self.binding
+    }
+
+    pub fn is_capturing(&self) -> bool {
+        self.captured.is_some()
+    }
+
+    /// The keys typed so far while capturing.
+    pub fn captured(&self) -> Option<&[KeySpec]> {
+        self.captured.as_deref()
+    }
+
+    pub fn draft(&self) -> &KeyBindings {
+        &self.draft
+    }
+
+    pub fn has_edits(&self) -> bool {
+        !self.edited.is_empty()
+    }
+
+    pub fn is_edited(&self, context: KeyContext, action: KeyAction) -> bool {
+        self.edited.contains(&(context, action))
+    }
+
+    /// Why the draft can't be applied or saved, e.g. a conflict with another binding.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    fn selected_keys_len(&self) -> usize {
+        let (context, action) = self.selected_row();
+        self.draft.context_keys(context, action).len()
+    }
+
+    fn move_up(&mut self) {
+        if self.binding > 0 {
+            self.binding -= 1;
+        } else if self.selected > 0 {
+            self.selected -= 1;
+            self.binding = self.selected_keys_len();
+        }
+    }
+
+    fn move_down(&mut self) {
+        if self.binding < self.selected_keys_len() {
+            self.binding += 1;
+        } else if self.selected + 1 < self.rows().len() {
+            self.selected += 1;
+            self.binding = 0;
+        }
+    }
+
+    /// Keeps the selection on an existing row and slot after the draft changed underneath it.
+    fn clamp_selection(&mut self) {
+        self.selected = self.selected.min(self.rows().len() - 1);
+        self.binding = self.binding.min(self.selected_keys_len());
+    }
+
+    /// Applies `edit` to the selected row's bindings and marks the row as edited.
+    fn edit_selected(&mut self, edit: impl FnOnce(&mut Vec<KeySequence>, usize)) {
+        let row = self.selected_row();
+        edit(self.draft.context_keys_mut(row.0, row.1), self.binding);
+        // A `[keys.<context>]` entry without bindings is gone; saving removes it from the file.
+        self.draft.scoped.retain(|binding| !binding.keys.is_empty());
+        if !self.edited.contains(&row) {
+            self.edited.push(row);
         }
+        self.clamp_selection();
     }
 }
 
@@ -41,64 +195,77 @@ pub struct App {
     pub high_contrast: bool,
     pub reduced_motion: bool,
     pub mouse_enabled: bool,
+    pub color_depth: ColorDepth,
+    pub simulation: Option<CvdSimulation>,
     panel: DemoPanel,
     list_selected: usize,
     pub show_help: bool,
     pub should_quit: bool,
     spinner_index: usize,
+    status: Option<StatusMessage>,
+    /// Keys typed so far of a multi-key binding, and when they stop counting.
+    pending_keys: Vec<KeyEvent>,
+    pending_deadline: Option<Instant>,
+    key_editor: KeyEditor,
+    /// Edited bindings the user asked to write to the config file; the event loop does the IO.
+    save_request: Option<Vec<(KeyContext, KeyAction, Vec<KeySequence>)>>,
 }
 
 impl App {
+    /// `themes` must not be empty; `theme` selects the initial entry by name (falls back to the
+    /// first theme when missing).
     pub fn new(
-        theme: ThemeName,
+        themes: Vec<Theme>,
+        theme: &str,
         no_color: bool,
         high_contrast: bool,
         reduced_motion: bool,
         keymap: KeyBindings,
         panel: DemoPanel,
     ) -> Self {
-        Self::new_with_mouse(
-            theme,
-            no_color,
-            high_contrast,
-            reduced_motion,
-            keymap,
-            false,
-            panel,
-        )
-    }
-
-    pub fn new_with_mouse(
-        theme: ThemeName,
-        no_color: bool,
-        high_contrast: bool,
-        reduced_motion: bool,
-        keymap: KeyBindings,
-        mouse_enabled: bool,
-        panel: DemoPanel,
-    ) -> Self {
-        let list = themes();
-        let theme_index = list
+        let theme_index = themes
             .iter()
-            .position(|item| item.name == theme.as_str())
+            .position(|item| item.name == theme)
             .unwrap_or(0);
 
         Self {
-            themes: list,
+            key_editor: KeyEditor::new(&keymap),
+            themes,
             theme_index,
             keymap,
             no_color,
             high_contrast,
             reduced_motion,
-            mouse_enabled,
+            mouse_enabled: false,
+            color_depth: ColorDepth::Truecolor,
+            simulation: None,
             panel,
             list_selected: 0,
             show_help: false,
             should_quit: false,
             spinner_index: 0,
+            status: None,
+            pending_keys: Vec::new(),
+            pending_deadline: None,
+            save_request: None,
         }
     }
 
+    pub fn with_mouse(mut self, enabled: bool) -> Self {
+        self.mouse_enabled = enabled;
+        self
+    }
+
+    pub fn with_color_depth(mut self, depth: ColorDepth) -> Self {
+        self.color_depth = depth;
+        self
+    }
+
+    pub fn with_simulation(mut self, simulation: Option<CvdSimulation>) -> Self {
+        self.simulation = simulation;
+        self
+    }
+
     pub fn panel(&self) -> DemoPanel {
         self.panel
     }
@@ -115,14 +282,67 @@ impl App {
         self.themes[self.theme_index]
             .clone()
             .with_accessibility(self.high_contrast, self.no_color)
+            // Simulate before downsampling: the simulated RGB still has to fit the terminal.
+            .with_simulation(self.simulation)
+            .with_color_depth(self.color_depth)
     }
 
     pub fn current_theme_name(&self) -> &str {
-        self.themes[self.theme_index].name
+        &self.themes[self.theme_index].name
     }
 
     pub fn current_theme_description(&self) -> &str {
-        self.themes[self.theme_index].description
+        &self.themes[self.theme_index].description
+    }
+
+    /// Swaps in freshly loaded themes and key bindings, keeping the current theme when it still
+    /// exists.
+    pub fn reload(&mut self, themes: Vec<Theme>, keymap: KeyBindings, now: Instant) {
+        let current = self.current_theme_name().to_string();
+        self.theme_index = themes
+            .iter()
+            .position(|item| item.name == current)
+            .unwrap_or(0);
+        self.themes = themes;
+        // The file now holds whatever was saved; unsaved edits are dropped with the old keymap,
+        // but the selected row stays put.
+        let mut key_editor = KeyEditor {
+            selected: self.key_editor.selected,
+            binding: self.key_editor.binding,
+            ..KeyEditor::new(&keymap)
+        };
+        key_editor.clamp_selection();
+        self.key_editor = key_editor;
+        self.keymap = keymap;
+        self.clear_pending_keys();
+        self.status = Some(StatusMessage {
+            text: format!("Config reloaded ({} themes)", self.themes.len()),
+            is_error: false,
+            expires_at: Some(now + STATUS_TTL),
+        });
+    }
+
+    pub fn report_reload_error(&mut self, message: String) {
+        self.status = Some(StatusMessage {
+            text: format!("Config error: {}", message),
+            is_error: true,
+            expires_at: None,
+        });
+    }
+
+    pub fn status(&self) -> Option<&StatusMessage> {
+        self.status.as_ref()
+    }
+
+    pub fn expire_status(&mut self, now: Instant) {
+        if self
+            .status
+            .as_ref()
+            .and_then(|status| status.expires_at)
+            .is_some_and(|expires_at| now >= expires_at)
+        {
+            self.status = None;
+        }
     }
 
     pub fn spinner_frame(&self) -> &str {
@@ -140,51 +360,264 @@ impl App {
         }
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
-        if key.code == KeyCode::Esc
-            || (key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL)
-            || KeyBindings::matches_any(&self.keymap.quit, key)
+    /// The partially typed sequence for the footer, e.g. `g` while waiting for `g g`.
+    pub fn pending_keys_label(&self) -> Option<String> {
+        if self.pending_keys.is_empty() {
+            None
+        } else {
+            Some(keys::key_events_display(&self.pending_keys))
+        }
+    }
+
+    /// Drops a partially typed sequence once `keymap.sequence_timeout` has passed since its last
+    /// key. A Keys panel capture ends the same way, binding the keys typed so far.
+    pub fn expire_pending_keys(&mut self, now: Instant) {
+        if self
+            .pending_deadline
+            .is_some_and(|deadline| now >= deadline)
         {
+            self.clear_pending_keys();
+        }
+        if self
+            .key_editor
+            .capture_deadline
+            .is_some_and(|deadline| now >= deadline)
+        {
+            self.finish_capture();
+        }
+    }
+
+    fn clear_pending_keys(&mut self) {
+        self.pending_keys.clear();
+        self.pending_deadline = None;
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent, now: Instant) {
+        if key.kind == KeyEventKind::Release {
+            // While the Keys panel waits for a new key, the release of the enter that started
+            // the capture (or of any other key) must neither bind nor trigger anything.
+            if !self.key_editor.is_capturing() {
+                self.handle_key_release(key, now);
+            }
+            return;
+        }
+        let spec = KeySpec::from_event(key);
+        if spec.code == KeyCode::Char('c') && spec.modifiers == KeyModifiers::CONTROL {
             self.should_quit = true;
             return;
         }
 
-        if KeyBindings::matches_any(&self.keymap.cycle_theme, key) {
-            self.theme_index = (self.theme_index + 1) % self.themes.len();
+        if self.panel == DemoPanel::Keys && !self.show_help && self.handle_editor_key(key, now) {
             return;
         }
-        if KeyBindings::matches_any(&self.keymap.next_panel, key) {
-            self.panel = self.panel.next();
+
+        self.expire_pending_keys(now);
+        if key.code == KeyCode::Esc {
+            // Esc backs out of a half-typed sequence first; otherwise it quits.
+            if self.pending_keys.is_empty() {
+                self.should_quit = true;
+            } else {
+                self.clear_pending_keys();
+            }
             return;
         }
-        if KeyBindings::matches_any(&self.keymap.prev_panel, key) {
-            self.panel = self.panel.prev();
-            return;
+
+        self.pending_keys.push(key);
+        let contexts = self.key_contexts();
+        let mut matched = self.keymap.match_sequence(&self.pending_keys, &contexts);
+        if matched == SequenceMatch::NoMatch && self.pending_keys.len() > 1 {
+            // A key that breaks a sequence still counts on its own (`g` then `t` runs `t`).
+            self.pending_keys = vec![key];
+            matched = self.keymap.match_sequence(&self.pending_keys, &contexts);
         }
-        if self.panel == DemoPanel::List {
-            if KeyBindings::matches_any(&self.keymap.list_up, key) {
-                self.list_move_up();
-                return;
+
+        match matched {
+            SequenceMatch::Pending => {
+                self.pending_deadline = Some(now + self.keymap.sequence_timeout);
             }
-            if KeyBindings::matches_any(&self.keymap.list_down, key) {
-                self.list_move_down();
-                return;
+            SequenceMatch::Action(action) => {
+                self.clear_pending_keys();
+                self.run_action(action);
             }
+            SequenceMatch::NoMatch => self.clear_pending_keys(),
         }
-        if KeyBindings::matches_any(&self.keymap.toggle_high_contrast, key) {
-            self.high_contrast = !self.high_contrast;
-            return;
+    }
+
+    /// Releases only arrive with the kitty keyboard protocol. They run `release+` bindings and are
+    /// otherwise ignored, so releasing a key never breaks a sequence being typed.
+    fn handle_key_release(&mut self, key: KeyEvent, now: Instant) {
+        self.expire_pending_keys(now);
+        let mut events = self.pending_keys.clone();
+        events.push(key);
+        match self.keymap.match_sequence(&events, &self.key_contexts()) {
+            SequenceMatch::Pending => {
+                self.pending_keys = events;
+                self.pending_deadline = Some(now + self.keymap.sequence_timeout);
+            }
+            SequenceMatch::Action(action) => {
+                self.clear_pending_keys();
+                self.run_action(action);
+            }
+            SequenceMatch::NoMatch => {}
         }
-        if KeyBindings::matches_any(&self.keymap.toggle_color, key) {
-            self.no_color = !self.no_color;
-            return;
+    }
+
+    pub fn key_editor(&self) -> &KeyEditor {
+        &self.key_editor
+    }
+
+    /// The edited bindings to write to the config file, once per ctrl+s.
+    pub fn take_save_request(&mut self) -> Option<Vec<(KeyContext, KeyAction, Vec<KeySequence>)>> {
+        self.save_request.take()
+    }
+
+    pub fn report_saved(&mut self, path: &Path, now: Instant) {
+        self.key_editor.edited.clear();
+        self.status = Some(StatusMessage {
+            text: format!("Saved key bindings to {}", path.display()),
+            is_error: false,
+            expires_at: Some(now + STATUS_TTL),
+        });
+    }
+
+    pub fn report_save_error(&mut self, message: String) {
+        self.status = Some(StatusMessage {
+            text: format!("Save failed: {}", message),
+            is_error: true,
+            expires_at: None,
+        });
+    }
+
+    /// Keys panel controls. These are fixed (not in `[keys]`) so a broken keymap can always be
+    /// repaired: up/down walk the bindings of each action, enter captures new keys for the
+    /// selected one (or adds a binding on the add slot), delete/backspace removes it and ctrl+s
+    /// saves. A capture takes a whole sequence and ends once no key follows within
+    /// `keymap.sequence_timeout`; esc cancels it. Keys a config file has no name for (menu,
+    /// media keys) are left out of the capture with an error. Returns false for keys the panel
+    /// doesn't use, which then go through the keymap.
+    fn handle_editor_key(&mut self, key: KeyEvent, now: Instant) -> bool {
+        let spec = KeySpec::from_event(key);
+        let timeout = self.keymap.sequence_timeout;
+        let editor = &mut self.key_editor;
+        if let Some(captured) = &mut editor.captured {
+            // Holding a key shouldn't bind it again; wait for a fresh press.
+            if key.kind == KeyEventKind::Repeat {
+                return true;
+            }
+            if key.code == KeyCode::Esc {
+                editor.captured = None;
+                editor.capture_deadline = None;
+                self.apply_key_draft();
+            } else if keys::parse_key_spec(&keys::key_spec_display(spec)).ok() != Some(spec) {
+                editor.error = Some(format!(
+                    "{:?} can't be bound: config files have no name for it",
+                    key.code
+                ));
+            } else {
+                captured.push(spec);
+                editor.capture_deadline = Some(now + timeout);
+            }
+            return true;
+        }
+        if !self.pending_keys.is_empty()
+            || !spec.modifiers.difference(KeyModifiers::CONTROL).is_empty()
+        {
+            return false;
         }
-        if KeyBindings::matches_any(&self.keymap.toggle_reduced_motion, key) {
-            self.reduced_motion = !self.reduced_motion;
+
+        match (spec.code, spec.modifiers == KeyModifiers::CONTROL) {
+            (KeyCode::Up, false) => editor.move_up(),
+            (KeyCode::Down, false) => editor.move_down(),
+            (KeyCode::Enter, false) => editor.captured = Some(Vec::new()),
+            (KeyCode::Delete | KeyCode::Backspace, false) => {
+                if editor.binding < editor.selected_keys_len() {
+                    editor.edit_selected(|keys, index| {
+                        keys.remove(index);
+                    });
+                    self.apply_key_draft();
+                }
+            }
+            (KeyCode::Char('s'), true) => {
+                if let Some(error) = &editor.error {
+                    self.status = Some(StatusMessage {
+                        text: format!("Fix the conflict before saving: {}", error),
+                        is_error: true,
+                        expires_at: None,
+                    });
+                } else if !editor.edited.is_empty() {
+                    self.save_request = Some(
+                        editor
+                            .edited
+                            .iter()
+                            .map(|&(context, action)| {
+                                let keys = editor.draft.context_keys(context, action).to_vec();
+                                (context, action, keys)
+                            })
+                            .collect(),
+                    );
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Binds the captured keys to the selected slot: replacing that binding, or adding one on
+    /// the add slot.
+    fn finish_capture(&mut self) {
+        let editor = &mut self.key_editor;
+        editor.capture_deadline = None;
+        let Some(sequence) = editor.captured.take().and_then(KeySequence::from_keys) else {
             return;
+        };
+        editor.edit_selected(|keys, index| match keys.get_mut(index) {
+            Some(existing) => *existing = sequence,
+            None => keys.push(sequence),
+        });
+        self.apply_key_draft();
+    }
+
+    /// Makes a valid draft the live keymap, or records why it can't be.
+    fn apply_key_draft(&mut self) {
+        let editor = &mut self.key_editor;
+        match editor.draft.validate() {
+            Ok(()) => {
+                editor.error = None;
+                self.keymap = editor.draft.clone();
+            }
+            Err(err) => editor.error = Some(err.to_string()),
         }
-        if KeyBindings::matches_any(&self.keymap.toggle_help, key) {
-            self.show_help = !self.show_help;
+    }
+
+    /// Active key contexts, innermost first.
+    fn key_contexts(&self) -> Vec<KeyContext> {
+        let mut contexts = Vec::with_capacity(3);
+        if self.show_help {
+            contexts.push(KeyContext::Help);
+        }
+        contexts.push(match self.panel {
+            DemoPanel::Overview => KeyContext::Overview,
+            DemoPanel::List => KeyContext::List,
+            DemoPanel::Keys => KeyContext::Editor,
+        });
+        contexts.push(KeyContext::Global);
+        contexts
+    }
+
+    fn run_action(&mut self, action: KeyAction) {
+        match action {
+            KeyAction::Quit => self.should_quit = true,
+            KeyAction::CycleTheme => {
+                self.theme_index = (self.theme_index + 1) % self.themes.len();
+            }
+            KeyAction::NextPanel => self.panel = self.panel.next(),
+            KeyAction::PrevPanel => self.panel = self.panel.prev(),
+            KeyAction::ListUp => self.list_move_up(),
+            KeyAction::ListDown => self.list_move_down(),
+            KeyAction::ToggleHighContrast => self.high_contrast = !self.high_contrast,
+            KeyAction::ToggleColor => self.no_color = !self.no_color,
+            KeyAction::ToggleReducedMotion => self.reduced_motion = !self.reduced_motion,
+            KeyAction::ToggleHelp => self.show_help = !self.show_help,
         }
     }
 
@@ -212,12 +645,9 @@ impl App {
             && column >= regions.tabs_x
             && column < regions.tabs_x.saturating_add(regions.tabs_width)
         {
-            let split = regions.tabs_x.saturating_add(regions.tabs_width / 2);
-            self.panel = if column < split {
-                DemoPanel::Overview
-            } else {
-                DemoPanel::List
-            };
+            if let Some(panel) = tab_at(column - regions.tabs_x) {
+                self.panel = panel;
+            }
             return;
         }
 
@@ -249,6 +679,20 @@ impl App {
     }
 }
 
+/// The tab under `offset` columns into the tab bar. Mirrors the `Tabs` widget layout: each title
+/// padded by one space on both sides, with a one-column divider between tabs.
+fn tab_at(offset: u16) -> Option<DemoPanel> {
+    let mut start = 0_u16;
+    for panel in DemoPanel::ALL {
+        let end = start + panel.title().len() as u16 + 2;
+        if offset < end {
+            return Some(panel);
+        }
+        start = end + 1;
+    }
+    None
+}
+
 fn list_demo_len() -> usize {
     40
 }
@@ -361,6 +805,8 @@ impl MouseRegions {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::KeymapPreset;
+    use crate::theme::themes;
     use crossterm::event::KeyModifiers;
 
     fn mouse_event(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
@@ -374,15 +820,16 @@ mod tests {
 
     #[test]
     fn mouse_scroll_moves_list_when_enabled() {
-        let mut app = App::new_with_mouse(
-            ThemeName::Aurora,
+        let mut app = App::new(
+            themes(),
+            "aurora",
             true,
             false,
             true,
             KeyBindings::default(),
-            true,
             DemoPanel::List,
-        );
+        )
+        .with_mouse(true);
         let area = Rect::new(0, 0, 120, 24);
 
         app.handle_mouse(mouse_event(MouseEventKind::ScrollDown, 10, 10), area);
@@ -394,7 +841,8 @@ mod tests {
     #[test]
     fn mouse_scroll_is_ignored_when_disabled() {
         let mut app = App::new(
-            ThemeName::Aurora,
+            themes(),
+            "aurora",
             true,
             false,
             true,
@@ -409,28 +857,40 @@ mod tests {
 
     #[test]
     fn mouse_click_switches_tabs() {
-        let mut app = App::new_with_mouse(
-            ThemeName::Aurora,
+        let mut app = App::new(
+            themes(),
+            "aurora",
             true,
             false,
             true,
             KeyBindings::default(),
-            true,
             DemoPanel::Overview,
-        );
+        )
+        .with_mouse(true);
         let area = Rect::new(0, 0, 120, 24);
         let regions = MouseRegions::for_area(area).expect("regions");
 
+        // " Overview │ List │ Keys "
         app.handle_mouse(
             mouse_event(
                 MouseEventKind::Down(MouseButton::Left),
-                regions.tabs_x + regions.tabs_width.saturating_sub(1),
+                regions.tabs_x + 13,
                 regions.tabs_row,
             ),
             area,
         );
         assert_eq!(app.panel(), DemoPanel::List);
 
+        app.handle_mouse(
+            mouse_event(
+                MouseEventKind::Down(MouseButton::Left),
+                regions.tabs_x + 20,
+                regions.tabs_row,
+            ),
+            area,
+        );
+        assert_eq!(app.panel(), DemoPanel::Keys);
+
         app.handle_mouse(
             mouse_event(
                 MouseEventKind::Down(MouseButton::Left),
@@ -444,15 +904,16 @@ mod tests {
 
     #[test]
     fn mouse_click_selects_visible_list_row() {
-        let mut app = App::new_with_mouse(
-            ThemeName::Aurora,
+        let mut app = App::new(
+            themes(),
+            "aurora",
             true,
             false,
             true,
             KeyBindings::default(),
-            true,
             DemoPanel::List,
-        );
+        )
+        .with_mouse(true);
         app.list_selected = 8;
 
         let area = Rect::new(0, 0, 120, 24);
@@ -475,4 +936,328 @@ mod tests {
             (start + target_offset as usize).min(app.list_len() - 1)
         );
     }
+
+    #[test]
+    fn reload_keeps_current_theme_and_reports_status() {
+        let mut app = App::new(
+            themes(),
+            "solar",
+            false,
+            false,
+            true,
+            KeyBindings::default(),
+            DemoPanel::Overview,
+        );
+        let now = Instant::now();
+
+        let mut reloaded = themes();
+        reloaded.retain(|theme| theme.name != "aurora");
+        app.reload(reloaded.clone(), KeyBindings::default(), now);
+        assert_eq!(app.current_theme_name(), "solar");
+        let status = app.status().expect("status");
+        assert!(!status.is_error);
+        assert!(status.text.contains("Config reloaded"));
+
+        app.expire_status(now + Duration::from_secs(1));
+        assert!(app.status().is_some());
+        app.expire_status(now + STATUS_TTL);
+        assert!(app.status().is_none());
+
+        // A reload that drops the current theme falls back to the first one; the Keys panel
+        // keeps its selected row.
+        app.key_editor.selected = 2;
+        reloaded.retain(|theme| theme.name != "solar");
+        app.reload(reloaded, KeyBindings::default(), now);
+        assert_eq!(app.current_theme_name(), "mono");
+        assert_eq!(app.key_editor().selected_action(), KeyAction::ALL[2]);
+
+        app.report_reload_error("invalid config TOML".to_string());
+        app.expire_status(now + Duration::from_secs(60));
+        assert!(app.status().is_some_and(|status| status.is_error));
+    }
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::empty())
+    }
+
+    #[test]
+    fn key_sequences_wait_cancel_and_time_out() {
+        let keymap = KeyBindings {
+            cycle_theme: vec![keys::parse_key_sequence("g t").unwrap()],
+            toggle_help: vec![keys::parse_key_sequence("ctrl+x ?").unwrap()],
+            ..KeyBindings::default()
+        };
+        let mut app = App::new(
+            themes(),
+            "aurora",
+            false,
+            false,
+            true,
+            keymap,
+            DemoPanel::Overview,
+        );
+        let now = Instant::now();
+
+        app.handle_key(press(KeyCode::Char('g')), now);
+        assert_eq!(app.pending_keys_label().as_deref(), Some("g"));
+        app.handle_key(press(KeyCode::Char('t')), now);
+        assert_eq!(app.current_theme_name(), "mono");
+        assert!(app.pending_keys_label().is_none());
+
+        // Esc cancels the pending prefix instead of quitting.
+        app.handle_key(
+            KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL),
+            now,
+        );
+        app.handle_key(press(KeyCode::Esc), now);
+        assert!(!app.should_quit);
+        assert!(app.pending_keys_label().is_none());
+
+        // A key that breaks the sequence is handled on its own.
+        app.handle_key(press(KeyCode::Char('g')), now);
+        app.handle_key(press(KeyCode::Char('h')), now);
+        assert!(app.high_contrast);
+        assert!(app.pending_keys_label().is_none());
+
+        // The prefix expires after the timeout.
+        app.handle_key(press(KeyCode::Char('g')), now);
+        app.expire_pending_keys(now + app.keymap.sequence_timeout);
+        assert!(app.pending_keys_label().is_none());
+        app.handle_key(press(KeyCode::Char('t')), now + app.keymap.sequence_timeout);
+        assert_eq!(app.current_theme_name(), "mono");
+
+        app.handle_key(press(KeyCode::Esc), now);
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn key_releases_run_release_bindings_without_breaking_sequences() {
+        let keymap = KeyBindings {
+            cycle_theme: vec![keys::parse_key_sequence("g t").unwrap()],
+            toggle_color: vec![keys::parse_key_sequence("release+space").unwrap()],
+            ..KeyBindings::default()
+        };
+        let mut app = App::new(
+            themes(),
+            "aurora",
+            false,
+            false,
+            true,
+            keymap,
+            DemoPanel::Overview,
+        );
+        let now = Instant::now();
+        let release =
+            |code| KeyEvent::new_with_kind(code, KeyModifiers::empty(), KeyEventKind::Release);
+
+        // Kitty reports `g` press, `g` release, `t` press: the release must not reset `g`.
+        app.handle_key(press(KeyCode::Char('g')), now);
+        app.handle_key(release(KeyCode::Char('g')), now);
+        assert_eq!(app.pending_keys_label().as_deref(), Some("g"));
+        app.handle_key(press(KeyCode::Char('t')), now);
+        assert_eq!(app.current_theme_name(), "mono");
+
+        // Releasing esc doesn't quit; releasing space runs its release binding.
+        app.handle_key(release(KeyCode::Esc), now);
+        assert!(!app.should_quit);
+        assert!(!app.no_color);
+        app.handle_key(press(KeyCode::Char(' ')), now);
+        assert!(!app.no_color);
+        app.handle_key(release(KeyCode::Char(' ')), now);
+        assert!(app.no_color);
+    }
+
+    #[test]
+    fn key_editor_capture_ignores_releases() {
+        let keymap = KeyBindings {
+            toggle_color: vec![keys::parse_key_sequence("release+space").unwrap()],
+            ..KeyBindings::default()
+        };
+        let mut app = App::new(
+            themes(),
+            "aurora",
+            false,
+            false,
+            true,
+            keymap,
+            DemoPanel::Keys,
+        );
+        let now = Instant::now();
+        let release =
+            |code| KeyEvent::new_with_kind(code, KeyModifiers::empty(), KeyEventKind::Release);
+
+        // Neither the enter release nor a release binding fires while waiting for the new key.
+        app.handle_key(press(KeyCode::Enter), now);
+        app.handle_key(release(KeyCode::Enter), now);
+        app.handle_key(release(KeyCode::Char(' ')), now);
+        assert!(app.key_editor().is_capturing());
+        assert!(!app.no_color);
+        app.handle_key(press(KeyCode::Char('n')), now);
+        app.expire_pending_keys(now + app.keymap.sequence_timeout);
+        assert_eq!(keys::key_list_display(&app.keymap.cycle_theme), "n");
+    }
+
+    #[test]
+    fn key_editor_rebinds_flags_conflicts_and_requests_save() {
+        let mut app = App::new(
+            themes(),
+            "aurora",
+            false,
+            false,
+            true,
+            KeyBindings::default(),
+            DemoPanel::Keys,
+        );
+        let now = Instant::now();
+        let later = now + app.keymap.sequence_timeout;
+
+        // Rebind cycle_theme (the first action) to `n`; the capture ends after a pause.
+        app.handle_key(press(KeyCode::Enter), now);
+        assert!(app.key_editor().is_capturing());
+        app.handle_key(press(KeyCode::Char('n')), now);
+        assert!(app.key_editor().is_capturing());
+        app.expire_pending_keys(later);
+        assert!(!app.key_editor().is_capturing());
+        assert!(app.key_editor().error().is_none());
+        assert_eq!(keys::key_list_display(&app.keymap.cycle_theme), "n");
+        app.handle_key(press(KeyCode::Char('n')), later);
+        assert_eq!(app.current_theme_name(), "mono");
+
+        // Past cycle_theme's add slot, binding next_panel to `h` conflicts with
+        // toggle_high_contrast: flagged, not applied.
+        app.handle_key(press(KeyCode::Down), later);
+        app.handle_key(press(KeyCode::Down), later);
+        assert_eq!(app.key_editor().selected_action(), KeyAction::NextPanel);
+        app.handle_key(press(KeyCode::Enter), later);
+        app.handle_key(press(KeyCode::Char('h')), later);
+        app.expire_pending_keys(later + app.keymap.sequence_timeout);
+        assert_eq!(
+            app.key_editor().error(),
+            Some("duplicate key binding 'h' used for multiple actions")
+        );
+        assert_eq!(keys::key_list_display(&app.keymap.next_panel), "tab");
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        app.handle_key(ctrl_s, later);
+        assert!(app.take_save_request().is_none());
+        assert!(app.status().is_some_and(|status| status.is_error));
+
+        // Esc cancels a capture without quitting; fixing the conflict allows saving.
+        app.handle_key(press(KeyCode::Enter), later);
+        app.handle_key(press(KeyCode::Esc), later);
+        assert!(!app.should_quit);
+        app.handle_key(press(KeyCode::Enter), later);
+        app.handle_key(press(KeyCode::Char('l')), later);
+        app.expire_pending_keys(later + app.keymap.sequence_timeout);
+        assert!(app.key_editor().error().is_none());
+
+        // A key with no config name is refused at capture time, not when saving.
+        app.handle_key(press(KeyCode::Enter), later);
+        app.handle_key(press(KeyCode::Menu), later);
+        assert!(app.key_editor().is_capturing());
+        assert_eq!(
+            app.key_editor().error(),
+            Some("Menu can't be bound: config files have no name for it")
+        );
+        app.handle_key(press(KeyCode::Esc), later);
+        assert!(app.key_editor().error().is_none());
+        app.handle_key(ctrl_s, later);
+        let edits = app.take_save_request().expect("save request");
+        let edits = edits
+            .iter()
+            .map(|(context, action, keys)| {
+                (context.as_str(), action.as_str(), keys::key_labels(keys))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            edits,
+            [
+                ("global", "cycle_theme", vec!["n".to_string()]),
+                ("global", "next_panel", vec!["l".to_string()])
+            ]
+        );
+    }
+
+    #[test]
+    fn key_editor_edits_single_bindings_sequences_and_context_tables() {
+        let keymap = KeyBindings {
+            scoped: vec![keys::ScopedBinding {
+                context: KeyContext::List,
+                action: KeyAction::CycleTheme,
+                keys: vec![keys::parse_key_sequence("T").unwrap()],
+            }],
+            ..KeyBindings::preset(KeymapPreset::Vim)
+        };
+        let mut app = App::new(
+            themes(),
+            "aurora",
+            false,
+            false,
+            true,
+            keymap,
+            DemoPanel::Keys,
+        );
+        let now = Instant::now();
+        let timeout = app.keymap.sequence_timeout;
+        let capture = |app: &mut App, codes: &[KeyCode]| {
+            app.handle_key(press(KeyCode::Enter), now);
+            for &code in codes {
+                app.handle_key(press(code), now);
+            }
+            app.expire_pending_keys(now + timeout);
+        };
+        let labels = |app: &App, action| keys::key_labels(app.keymap.keys(action));
+
+        // Replace the second of next_panel's bindings with a sequence; `g t` stays.
+        for _ in 0..3 {
+            app.handle_key(press(KeyCode::Down), now);
+        }
+        assert_eq!(app.key_editor().selected_action(), KeyAction::NextPanel);
+        assert_eq!(app.key_editor().selected_binding(), 1);
+        capture(&mut app, &[KeyCode::Char('g'), KeyCode::Char('n')]);
+        assert_eq!(labels(&app, KeyAction::NextPanel), ["g t", "g n"]);
+
+        // The slot after the last binding adds one; delete removes the selected binding.
+        app.handle_key(press(KeyCode::Down), now);
+        capture(&mut app, &[KeyCode::Tab]);
+        assert_eq!(labels(&app, KeyAction::NextPanel), ["g t", "g n", "tab"]);
+        app.handle_key(press(KeyCode::Up), now);
+        app.handle_key(press(KeyCode::Delete), now);
+        assert_eq!(labels(&app, KeyAction::NextPanel), ["g t", "tab"]);
+
+        // `[keys.<context>]` entries are rows of their own, after every action.
+        while app.key_editor().selected_row() != (KeyContext::List, KeyAction::CycleTheme) {
+            app.handle_key(press(KeyCode::Down), now);
+        }
+        capture(&mut app, &[KeyCode::Char('x')]);
+        assert_eq!(
+            keys::key_labels(
+                app.keymap
+                    .context_keys(KeyContext::List, KeyAction::CycleTheme)
+            ),
+            ["x"]
+        );
+
+        app.handle_key(
+            KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL),
+            now,
+        );
+        let edits = app.take_save_request().expect("save request");
+        let edits = edits
+            .iter()
+            .map(|(context, action, keys)| {
+                (context.as_str(), action.as_str(), keys::key_labels(keys))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            edits,
+            [
+                (
+                    "global",
+                    "next_panel",
+                    vec!["g t".to_string(), "tab".to_string()]
+                ),
+                ("list", "cycle_theme", vec!["x".to_string()]),
+            ]
+        );
+    }
 }
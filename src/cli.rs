@@ -1,3 +1,4 @@
+use crate::color::ColorDepth;
 use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
@@ -16,7 +17,7 @@ pub struct Cli {
 pub enum Commands {
     /// Launch the interactive TUI demo.
     Demo(DemoArgs),
-    /// List available themes.
+    /// List available themes (built-in and config-defined).
     Themes(ThemesArgs),
     /// Print key bindings.
     Keys(KeysArgs),
@@ -25,10 +26,10 @@ pub enum Commands {
 }
 
 #[derive(Args, Debug, Clone)]
+#[command(mut_arg("ascii", |arg| arg.requires("no_tty")))]
 pub struct DemoArgs {
-    /// Theme to use (by name).
-    #[arg(long, value_enum)]
-    pub theme: Option<ThemeName>,
+    #[command(flatten)]
+    pub settings: SettingsArgs,
     /// Render a one-frame static preview to stdout and exit (does not require a TTY).
     #[arg(long, action = ArgAction::SetTrue, default_value_t = false)]
     pub no_tty: bool,
@@ -38,8 +39,22 @@ pub struct DemoArgs {
     /// Height for `--no-tty` preview rendering (rows).
     #[arg(long, requires = "no_tty")]
     pub height: Option<u16>,
+    /// Simulate a color-vision deficiency by transforming every palette color before rendering.
+    #[arg(long, value_enum)]
+    pub simulate: Option<CvdSimulation>,
+}
+
+/// Flags that override a `[demo]` or `[keys]` config setting, shared by `demo` and `config show`.
+#[derive(Args, Debug, Clone, Default)]
+pub struct SettingsArgs {
+    /// Theme to use (by name), or `auto` to pick aurora/aurora-light from the terminal background.
+    #[arg(long)]
+    pub theme: Option<String>,
+    /// Terminal background; selects the matching light/dark variant of the theme.
+    #[arg(long, value_enum)]
+    pub background: Option<Background>,
     /// Use ASCII glyphs for `--no-tty` output (avoids box-drawing characters).
-    #[arg(long, requires = "no_tty", action = ArgAction::SetTrue, default_value_t = false)]
+    #[arg(long, action = ArgAction::SetTrue, default_value_t = false)]
     pub ascii: bool,
     /// Disable color output for maximum compatibility.
     #[arg(
@@ -52,6 +67,9 @@ pub struct DemoArgs {
     /// Force-enable color output and override config/environment no-color defaults.
     #[arg(long, action = ArgAction::SetTrue, default_value_t = false)]
     pub color: bool,
+    /// Terminal color depth; theme colors are downsampled to fit (`auto` checks COLORTERM/TERM).
+    #[arg(long, value_enum)]
+    pub color_depth: Option<ColorDepthArg>,
     /// Use a high-contrast palette for better visibility.
     #[arg(
         long,
@@ -85,24 +103,75 @@ pub struct DemoArgs {
     /// Disable mouse input for interactive demo mode.
     #[arg(long, action = ArgAction::SetTrue, default_value_t = false)]
     pub no_mouse: bool,
-    /// Optional path to config file (TOML).
+    /// Config file (TOML) merged over the system, user and project files. Repeat to stack several;
+    /// later files win.
     #[arg(long)]
-    pub config: Option<PathBuf>,
+    pub config: Vec<PathBuf>,
+    /// Start key bindings from this preset instead of `[keys] preset` (entries still override).
+    #[arg(long, value_enum)]
+    pub keymap_preset: Option<KeymapPreset>,
 }
 
 #[derive(Args, Debug, Clone)]
 pub struct KeysArgs {
-    /// Optional path to config file (TOML). When omitted, the default config path is used if it exists.
+    /// Config file (TOML) merged over the system, user and project files. Repeat to stack several;
+    /// later files win.
     #[arg(long)]
-    pub config: Option<PathBuf>,
+    pub config: Vec<PathBuf>,
+
+    /// Start key bindings from this preset instead of `[keys] preset` (entries still override).
+    #[arg(long, value_enum)]
+    pub keymap_preset: Option<KeymapPreset>,
+
+    /// Show where each key comes from: the built-in defaults, the preset, or a config file line.
+    #[arg(long)]
+    pub explain: bool,
+
+    /// Output format. `markdown`, `html`, `man` and `csv` render a key reference card.
+    #[arg(long, value_enum, default_value_t = KeysFormat::Text)]
+    pub format: KeysFormat,
+}
+
+/// `--config`/`--format` here only apply to the list itself; subcommands take their own, so
+/// passing them before a subcommand is rejected rather than silently ignored.
+#[derive(Args, Debug, Clone)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct ThemesArgs {
+    #[command(subcommand)]
+    pub command: Option<ThemesCommands>,
+
+    /// Config file (TOML) merged over the system, user and project files. Repeat to stack several;
+    /// later files win.
+    #[arg(long)]
+    pub config: Vec<PathBuf>,
 
     /// Output format.
     #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
     pub format: OutputFormat,
 }
 
+#[derive(Subcommand, Debug, Clone)]
+pub enum ThemesCommands {
+    /// Audit WCAG 2.x contrast of every foreground slot against the background.
+    Check(ThemesCheckArgs),
+    /// Render a card per theme with palette swatches and sample widgets (ANSI-colored).
+    Preview(ThemesPreviewArgs),
+    /// Convert a theme into another tool's color scheme format.
+    Export(ThemesExportArgs),
+    /// Convert a base16 YAML scheme into a theme.
+    Import(ThemesImportArgs),
+}
+
 #[derive(Args, Debug, Clone)]
-pub struct ThemesArgs {
+pub struct ThemesCheckArgs {
+    /// Only check this theme (by name). Checks every theme when omitted.
+    pub name: Option<String>,
+
+    /// Config file (TOML) merged over the system, user and project files. Repeat to stack several;
+    /// later files win.
+    #[arg(long)]
+    pub config: Vec<PathBuf>,
+
     /// Output format.
     #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
     pub format: OutputFormat,
@@ -120,6 +189,12 @@ pub enum ConfigCommands {
     Init(ConfigInitArgs),
     /// Parse and validate a config file without launching the TUI.
     Validate(ConfigValidateArgs),
+    /// Warn about key bindings that are valid but risky or awkward.
+    Lint(ConfigLintArgs),
+    /// Print the effective settings and key bindings after CLI, environment and config precedence.
+    Show(ConfigShowArgs),
+    /// Upgrade a config file from an older release to the current schema version.
+    Migrate(ConfigMigrateArgs),
 }
 
 #[derive(Args, Debug, Clone)]
@@ -134,15 +209,150 @@ pub struct ConfigInitArgs {
 
 #[derive(Args, Debug, Clone)]
 pub struct ConfigValidateArgs {
-    /// Optional path to config file (TOML). When omitted, the default config path is used if it exists.
+    /// Config file (TOML) merged over the system, user and project files. Repeat to stack several;
+    /// later files win.
     #[arg(long)]
-    pub config: Option<PathBuf>,
+    pub config: Vec<PathBuf>,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ConfigLintArgs {
+    /// Config file (TOML) merged over the system, user and project files. Repeat to stack several;
+    /// later files win.
+    #[arg(long)]
+    pub config: Vec<PathBuf>,
+
+    /// Lint the bindings on top of this preset instead of `[keys] preset`.
+    #[arg(long, value_enum)]
+    pub keymap_preset: Option<KeymapPreset>,
+
+    /// Exit non-zero when there are findings at this severity or above.
+    #[arg(long, value_enum)]
+    pub deny: Option<LintDeny>,
 
     /// Output format.
     #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
     pub format: OutputFormat,
 }
 
+#[derive(Args, Debug, Clone)]
+pub struct ConfigShowArgs {
+    #[command(flatten)]
+    pub settings: SettingsArgs,
+
+    /// Output format. TOML output is a valid config file.
+    #[arg(long, value_enum, default_value_t = ShowFormat::Toml)]
+    pub format: ShowFormat,
+
+    /// Annotate each value with where it came from.
+    #[arg(long)]
+    pub origin: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ConfigMigrateArgs {
+    /// Rewrite the file in place instead of printing the migrated config.
+    #[arg(long, action = ArgAction::SetTrue, default_value_t = false)]
+    pub write: bool,
+
+    /// Config file to migrate. Defaults to the project `.cli-tui-starter.toml` if there is one,
+    /// else the user config file.
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShowFormat {
+    Toml,
+    Json,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintDeny {
+    /// Fail on warnings.
+    Warnings,
+    /// Fail on any finding, informational ones included.
+    Info,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ThemesPreviewArgs {
+    /// Only preview this theme (by name). Previews every theme when omitted.
+    pub name: Option<String>,
+
+    /// Output width (columns); cards are laid out side by side when there is room.
+    #[arg(long, default_value_t = 80)]
+    pub width: u16,
+
+    /// Use ASCII glyphs (avoids box-drawing and block characters).
+    #[arg(long, action = ArgAction::SetTrue, default_value_t = false)]
+    pub ascii: bool,
+
+    /// Print plain text without ANSI color codes (also implied by NO_COLOR/CLICOLOR=0/TERM=dumb).
+    #[arg(long, action = ArgAction::SetTrue, default_value_t = false)]
+    pub no_color: bool,
+
+    /// Color depth for the ANSI output (`auto` checks COLORTERM/TERM).
+    #[arg(long, value_enum)]
+    pub color_depth: Option<ColorDepthArg>,
+
+    /// Config file (TOML) merged over the system, user and project files. Repeat to stack several;
+    /// later files win.
+    #[arg(long)]
+    pub config: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ThemesExportArgs {
+    /// Theme to export (by name).
+    pub name: String,
+
+    /// Target format.
+    #[arg(long, value_enum)]
+    pub to: ExportFormat,
+
+    /// Config file (TOML) merged over the system, user and project files. Repeat to stack several;
+    /// later files win.
+    #[arg(long)]
+    pub config: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ThemesImportArgs {
+    /// Path to a base16 scheme (YAML).
+    pub path: PathBuf,
+
+    /// Theme name to use instead of one derived from the scheme name.
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Save to `themes/<name>.toml` next to the config file instead of printing a `[themes.<name>]` table.
+    #[arg(long, action = ArgAction::SetTrue, default_value_t = false)]
+    pub write: bool,
+
+    /// Overwrite an existing theme file (with `--write`).
+    #[arg(long, requires = "write", action = ArgAction::SetTrue, default_value_t = false)]
+    pub force: bool,
+
+    /// Config file (TOML) merged over the system, user and project files. Repeat to stack several;
+    /// `--write` saves next to the last one, like the Keys panel.
+    #[arg(long)]
+    pub config: Vec<PathBuf>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Alacritty,
+    Kitty,
+    Wezterm,
+    Base16Yaml,
+    VscodeTerminal,
+}
+
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OutputFormat {
     Text,
@@ -150,10 +360,136 @@ pub enum OutputFormat {
 }
 
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeysFormat {
+    Text,
+    Json,
+    Markdown,
+    Html,
+    Man,
+    Csv,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepthArg {
+    Auto,
+    Truecolor,
+    #[value(name = "256")]
+    Ansi256,
+    #[value(name = "16")]
+    Ansi16,
+}
+
+impl ColorDepthArg {
+    /// `None` means "detect from the environment".
+    pub fn depth(self) -> Option<ColorDepth> {
+        match self {
+            ColorDepthArg::Auto => None,
+            ColorDepthArg::Truecolor => Some(ColorDepth::Truecolor),
+            ColorDepthArg::Ansi256 => Some(ColorDepth::Ansi256),
+            ColorDepthArg::Ansi16 => Some(ColorDepth::Ansi16),
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(ColorDepthArg::Auto),
+            "truecolor" | "24bit" => Some(ColorDepthArg::Truecolor),
+            "256" => Some(ColorDepthArg::Ansi256),
+            "16" => Some(ColorDepthArg::Ansi16),
+            _ => None,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CvdSimulation {
+    /// Missing L (red) cones.
+    Protanopia,
+    /// Missing M (green) cones.
+    Deuteranopia,
+    /// Missing S (blue) cones.
+    Tritanopia,
+    /// No color vision (luminance only).
+    Achromatopsia,
+}
+
+impl CvdSimulation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CvdSimulation::Protanopia => "protanopia",
+            CvdSimulation::Deuteranopia => "deuteranopia",
+            CvdSimulation::Tritanopia => "tritanopia",
+            CvdSimulation::Achromatopsia => "achromatopsia",
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Background {
+    Dark,
+    Light,
+}
+
+impl Background {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Background::Dark => "dark",
+            Background::Light => "light",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "dark" => Some(Background::Dark),
+            "light" => Some(Background::Light),
+            _ => None,
+        }
+    }
+}
+
+/// Built-in key binding sets. `[keys]` entries are applied on top of the selected preset.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeymapPreset {
+    Default,
+    Vim,
+    Emacs,
+    ArrowsOnly,
+}
+
+impl KeymapPreset {
+    pub const ALL: [KeymapPreset; 4] = [
+        KeymapPreset::Default,
+        KeymapPreset::Vim,
+        KeymapPreset::Emacs,
+        KeymapPreset::ArrowsOnly,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            KeymapPreset::Default => "default",
+            KeymapPreset::Vim => "vim",
+            KeymapPreset::Emacs => "emacs",
+            KeymapPreset::ArrowsOnly => "arrows-only",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.as_str().eq_ignore_ascii_case(value))
+    }
+}
+
+/// Built-in theme identifiers. User-defined themes are resolved by name at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ThemeName {
     Aurora,
     Mono,
     Solar,
+    AuroraLight,
+    MonoLight,
+    SolarLight,
 }
 
 impl ThemeName {
@@ -162,6 +498,9 @@ impl ThemeName {
             ThemeName::Aurora => "aurora",
             ThemeName::Mono => "mono",
             ThemeName::Solar => "solar",
+            ThemeName::AuroraLight => "aurora-light",
+            ThemeName::MonoLight => "mono-light",
+            ThemeName::SolarLight => "solar-light",
         }
     }
 
@@ -170,6 +509,9 @@ impl ThemeName {
             "aurora" => Some(ThemeName::Aurora),
             "mono" => Some(ThemeName::Mono),
             "solar" => Some(ThemeName::Solar),
+            "aurora-light" => Some(ThemeName::AuroraLight),
+            "mono-light" => Some(ThemeName::MonoLight),
+            "solar-light" => Some(ThemeName::SolarLight),
             _ => None,
         }
     }
@@ -184,6 +526,9 @@ mod tests {
         assert_eq!(ThemeName::Aurora.as_str(), "aurora");
         assert_eq!(ThemeName::Mono.as_str(), "mono");
         assert_eq!(ThemeName::Solar.as_str(), "solar");
+        assert_eq!(ThemeName::AuroraLight.as_str(), "aurora-light");
+        assert_eq!(ThemeName::MonoLight.as_str(), "mono-light");
+        assert_eq!(ThemeName::SolarLight.as_str(), "solar-light");
     }
 
     #[test]
@@ -192,5 +537,18 @@ mod tests {
         assert_eq!(ThemeName::parse("mono"), Some(ThemeName::Mono));
         assert_eq!(ThemeName::parse(" Solar "), Some(ThemeName::Solar));
         assert_eq!(ThemeName::parse("unknown"), None);
+        assert_eq!(ThemeName::parse("Solar-Light"), Some(ThemeName::SolarLight));
+    }
+
+    #[test]
+    fn color_depth_parsing_matches_cli_values() {
+        assert_eq!(ColorDepthArg::parse("Auto"), Some(ColorDepthArg::Auto));
+        assert_eq!(
+            ColorDepthArg::parse("24bit"),
+            Some(ColorDepthArg::Truecolor)
+        );
+        assert_eq!(ColorDepthArg::parse("256"), Some(ColorDepthArg::Ansi256));
+        assert_eq!(ColorDepthArg::parse("16"), Some(ColorDepthArg::Ansi16));
+        assert_eq!(ColorDepthArg::parse("8"), None);
     }
 }
@@ -0,0 +1,338 @@
+use crate::cli::CvdSimulation;
+use ratatui::style::Color;
+
+/// How many colors the output terminal can display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// 24-bit RGB.
+    Truecolor,
+    /// The xterm 256-color palette.
+    Ansi256,
+    /// The 16 named ANSI colors.
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// The `color_depth` config value that selects this depth.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ColorDepth::Truecolor => "truecolor",
+            ColorDepth::Ansi256 => "256",
+            ColorDepth::Ansi16 => "16",
+        }
+    }
+}
+
+/// The 16 named ANSI colors with xterm's default RGB values, in ANSI index order.
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Channel levels of the 6x6x6 color cube (indices 16..=231).
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Resolves a color to RGB assuming xterm's default palette. `Reset` has no fixed value.
+pub fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Reset => None,
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        Color::Indexed(index) => Some(indexed_to_rgb(index)),
+        named => ANSI16
+            .iter()
+            .find(|(candidate, _)| *candidate == named)
+            .map(|(_, rgb)| *rgb),
+    }
+}
+
+fn indexed_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16[index as usize].1,
+        16..=231 => {
+            let i = index - 16;
+            (
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[((i / 6) % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+/// WCAG 2.x relative luminance of an sRGB color.
+pub fn relative_luminance(rgb: (u8, u8, u8)) -> f64 {
+    0.2126 * srgb_to_linear(rgb.0) + 0.7152 * srgb_to_linear(rgb.1) + 0.0722 * srgb_to_linear(rgb.2)
+}
+
+/// WCAG 2.x contrast ratio (1.0 to 21.0). `None` when either color has no fixed RGB value.
+pub fn contrast_ratio(fg: Color, bg: Color) -> Option<f64> {
+    let a = relative_luminance(to_rgb(fg)?);
+    let b = relative_luminance(to_rgb(bg)?);
+    let (lighter, darker) = if a >= b { (a, b) } else { (b, a) };
+    Some((lighter + 0.05) / (darker + 0.05))
+}
+
+pub fn hex(rgb: (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb.0, rgb.1, rgb.2)
+}
+
+/// Simulates how a color appears with the given color-vision deficiency.
+///
+/// Uses the Machado, Oliveira & Fernandes (2009) matrices at full severity, applied in linear
+/// RGB; achromatopsia keeps only relative luminance. `Reset` is returned unchanged.
+pub fn simulate_cvd(color: Color, kind: CvdSimulation) -> Color {
+    const PROTANOPIA: [[f64; 3]; 3] = [
+        [0.152286, 1.052583, -0.204868],
+        [0.114503, 0.786281, 0.099216],
+        [-0.003882, -0.048116, 1.051998],
+    ];
+    const DEUTERANOPIA: [[f64; 3]; 3] = [
+        [0.367322, 0.860646, -0.227968],
+        [0.280085, 0.672501, 0.047413],
+        [-0.011820, 0.042940, 0.968881],
+    ];
+    const TRITANOPIA: [[f64; 3]; 3] = [
+        [1.255528, -0.076749, -0.178779],
+        [-0.078411, 0.930809, 0.147602],
+        [0.004733, 0.691367, 0.303900],
+    ];
+
+    let Some(rgb) = to_rgb(color) else {
+        return color;
+    };
+    let linear = [
+        srgb_to_linear(rgb.0),
+        srgb_to_linear(rgb.1),
+        srgb_to_linear(rgb.2),
+    ];
+
+    let out = match kind {
+        CvdSimulation::Protanopia => apply_matrix(&PROTANOPIA, linear),
+        CvdSimulation::Deuteranopia => apply_matrix(&DEUTERANOPIA, linear),
+        CvdSimulation::Tritanopia => apply_matrix(&TRITANOPIA, linear),
+        CvdSimulation::Achromatopsia => {
+            let y = relative_luminance(rgb);
+            [y, y, y]
+        }
+    };
+
+    Color::Rgb(
+        linear_to_srgb(out[0]),
+        linear_to_srgb(out[1]),
+        linear_to_srgb(out[2]),
+    )
+}
+
+fn apply_matrix(m: &[[f64; 3]; 3], v: [f64; 3]) -> [f64; 3] {
+    [
+        m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+        m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+        m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+    ]
+}
+
+fn srgb_to_linear(value: u8) -> f64 {
+    let c = f64::from(value) / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f64) -> u8 {
+    let c = value.clamp(0.0, 1.0);
+    let s = if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (s * 255.0).round() as u8
+}
+
+/// Maps a color down to what a terminal with the given depth can show. Colors that already fit
+/// are returned unchanged.
+pub fn downsample(color: Color, depth: ColorDepth) -> Color {
+    match (depth, color) {
+        (ColorDepth::Truecolor, _) => color,
+        (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_ansi256((r, g, b))),
+        (ColorDepth::Ansi256, _) => color,
+        (ColorDepth::Ansi16, Color::Indexed(index)) if index < 16 => ANSI16[index as usize].0,
+        (ColorDepth::Ansi16, Color::Rgb(..) | Color::Indexed(_)) => match to_rgb(color) {
+            Some(rgb) => nearest_ansi16(rgb),
+            None => color,
+        },
+        (ColorDepth::Ansi16, _) => color,
+    }
+}
+
+fn nearest_ansi256(rgb: (u8, u8, u8)) -> u8 {
+    // Only consider the cube and grayscale ramp: indices 0..=15 are commonly re-themed by users.
+    let cube_index = |value: u8| -> usize {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, level)| (i32::from(**level) - i32::from(value)).abs())
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (cube_index(rgb.0), cube_index(rgb.1), cube_index(rgb.2));
+    let cube = (16 + 36 * ri + 6 * gi + bi) as u8;
+
+    let average = (u32::from(rgb.0) + u32::from(rgb.1) + u32::from(rgb.2)) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 232 + gray_step;
+
+    if distance(rgb, indexed_to_rgb(gray)) < distance(rgb, indexed_to_rgb(cube)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+fn nearest_ansi16(rgb: (u8, u8, u8)) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, candidate)| distance(rgb, *candidate))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::Reset)
+}
+
+/// Weighted ("redmean") squared distance; cheap and closer to perception than plain RGB.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let rmean = (i32::from(a.0) + i32::from(b.0)) / 2;
+    let dr = i32::from(a.0) - i32::from(b.0);
+    let dg = i32::from(a.1) - i32::from(b.1);
+    let db = i32::from(a.2) - i32::from(b.2);
+    let weighted =
+        (((512 + rmean) * dr * dr) >> 8) + 4 * dg * dg + (((767 - rmean) * db * db) >> 8);
+    weighted as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexed_colors_resolve_to_xterm_values() {
+        assert_eq!(to_rgb(Color::Indexed(1)), Some((205, 0, 0)));
+        assert_eq!(to_rgb(Color::Indexed(16)), Some((0, 0, 0)));
+        assert_eq!(to_rgb(Color::Indexed(208)), Some((255, 135, 0)));
+        assert_eq!(to_rgb(Color::Indexed(232)), Some((8, 8, 8)));
+        assert_eq!(to_rgb(Color::Indexed(255)), Some((238, 238, 238)));
+        assert_eq!(to_rgb(Color::Reset), None);
+    }
+
+    #[test]
+    fn downsample_maps_rgb_to_256_palette() {
+        assert_eq!(
+            downsample(Color::Rgb(255, 135, 0), ColorDepth::Ansi256),
+            Color::Indexed(208)
+        );
+        assert_eq!(
+            downsample(Color::Rgb(128, 128, 128), ColorDepth::Ansi256),
+            Color::Indexed(244)
+        );
+        assert_eq!(
+            downsample(Color::LightBlue, ColorDepth::Ansi256),
+            Color::LightBlue
+        );
+    }
+
+    #[test]
+    fn downsample_maps_to_named_ansi_colors() {
+        assert_eq!(
+            downsample(Color::Rgb(250, 10, 10), ColorDepth::Ansi16),
+            Color::LightRed
+        );
+        assert_eq!(
+            downsample(Color::Indexed(9), ColorDepth::Ansi16),
+            Color::LightRed
+        );
+        assert_eq!(
+            downsample(Color::Indexed(236), ColorDepth::Ansi16),
+            Color::Black
+        );
+        assert_eq!(
+            downsample(Color::Indexed(75), ColorDepth::Ansi16),
+            Color::LightBlue
+        );
+        assert_eq!(downsample(Color::Reset, ColorDepth::Ansi16), Color::Reset);
+    }
+
+    #[test]
+    fn contrast_ratio_matches_wcag_reference_values() {
+        let ratio = contrast_ratio(Color::White, Color::Black).unwrap();
+        assert!((ratio - 21.0).abs() < 1e-9);
+        let ratio = contrast_ratio(Color::Rgb(118, 118, 118), Color::White).unwrap();
+        assert!((ratio - 4.54).abs() < 0.01, "ratio was {ratio}");
+        assert_eq!(
+            contrast_ratio(Color::Black, Color::White),
+            contrast_ratio(Color::White, Color::Black)
+        );
+        assert_eq!(contrast_ratio(Color::Reset, Color::Black), None);
+    }
+
+    #[test]
+    fn cvd_simulation_preserves_neutrals_and_reset() {
+        for kind in [
+            CvdSimulation::Protanopia,
+            CvdSimulation::Deuteranopia,
+            CvdSimulation::Tritanopia,
+            CvdSimulation::Achromatopsia,
+        ] {
+            assert_eq!(simulate_cvd(Color::White, kind), Color::Rgb(255, 255, 255));
+            assert_eq!(simulate_cvd(Color::Black, kind), Color::Rgb(0, 0, 0));
+            assert_eq!(simulate_cvd(Color::Reset, kind), Color::Reset);
+        }
+    }
+
+    #[test]
+    fn cvd_simulation_collapses_red_green_for_protanopia() {
+        // Pure red and green are far apart normally but land close together without L cones.
+        let red = to_rgb(simulate_cvd(
+            Color::Rgb(200, 60, 60),
+            CvdSimulation::Protanopia,
+        ));
+        let green = to_rgb(simulate_cvd(
+            Color::Rgb(90, 140, 60),
+            CvdSimulation::Protanopia,
+        ));
+        let before = distance((200, 60, 60), (90, 140, 60));
+        let after = distance(red.unwrap(), green.unwrap());
+        assert!(after * 4 < before, "before {before}, after {after}");
+    }
+
+    #[test]
+    fn achromatopsia_outputs_grayscale() {
+        let Color::Rgb(r, g, b) = simulate_cvd(Color::LightBlue, CvdSimulation::Achromatopsia)
+        else {
+            panic!("expected rgb");
+        };
+        assert!(r == g && g == b);
+    }
+
+    #[test]
+    fn truecolor_leaves_colors_untouched() {
+        let color = Color::Rgb(1, 2, 3);
+        assert_eq!(downsample(color, ColorDepth::Truecolor), color);
+    }
+}
@@ -1,284 +1,1768 @@
-use crate::cli::{DemoArgs, ThemeName};
-use crate::keys::{parse_key_spec, KeyBindings, KeySpec};
+use crate::cli::{Background, ColorDepthArg, KeymapPreset, SettingsArgs, ThemeName};
+use crate::color::ColorDepth;
+use crate::diagnostics::{
+    deserialize, did_you_mean, parse_document, ConfigErrors, Diagnostic, SourceSpan,
+};
+use crate::keys::{
+    key_sequence_display, parse_key_sequence, suggest_key_spec, KeyAction, KeyBindings, KeyContext,
+    KeySequence, ScopedBinding,
+};
+use crate::terminal;
+use crate::theme::{
+    find_theme, parse_color, select_theme, themes, validate_theme_name, PaletteSlot, StyleOverride,
+    StyleSlot, Theme, ThemePalette, AUTO_THEME,
+};
 use anyhow::{anyhow, bail, Context, Result};
+use ratatui::style::Color;
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::env;
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
 use std::fs;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use toml::Spanned;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DemoSettings {
-    pub theme: ThemeName,
+    pub theme: String,
     pub no_color: bool,
     pub high_contrast: bool,
     pub reduced_motion: bool,
     pub ascii: bool,
     pub mouse: bool,
+    pub color_depth: ColorDepth,
+    /// Explicit background from the CLI or config; `None` means detect it for `theme = "auto"`.
+    /// After [`resolve_demo_runtime`] it also holds the detected background.
+    pub background: Option<Background>,
 }
 
 pub struct DemoRuntime {
     pub settings: DemoSettings,
-    pub keys: KeyBindings,
+    /// Where each setting came from, by `[demo]` key.
+    pub origins: BTreeMap<&'static str, Origin>,
+    pub keys: ResolvedKeys,
+    /// Built-in themes followed by any config-defined themes.
+    pub themes: Vec<Theme>,
+}
+
+/// Where an effective setting came from, for `config show --origin`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// The built-in default.
+    Default,
+    /// A command-line flag.
+    Cli(&'static str),
+    /// A config file line, or a `CLI_TUI_STARTER_*` variable.
+    Config(ConfigLocation),
+    /// Detected from an environment variable such as `NO_COLOR`.
+    Env(&'static str),
+    /// Answered by the terminal (the OSC 11 background query).
+    Terminal,
+    /// The keymap preset, for bindings it changes.
+    Preset(KeymapPreset),
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::Default => write!(f, "default"),
+            Origin::Cli(flag) => write!(f, "{}", flag),
+            Origin::Config(location) => write!(f, "{}", location),
+            Origin::Env(var) => write!(f, "env {}", var),
+            Origin::Terminal => write!(f, "terminal"),
+            Origin::Preset(preset) => write!(f, "preset {}", preset.as_str()),
+        }
+    }
 }
 
-pub fn resolve_demo_runtime(args: &DemoArgs) -> Result<DemoRuntime> {
-    let loaded = load_config_bundle(args.config.as_deref())?;
-    let settings = resolve_with_sources(args, &loaded.demo, env_disables_color_current());
+/// Resolves the demo settings from `args`, the config layers and the environment. Only the
+/// interactive demo passes `query_terminal`; everything else goes straight to COLORFGBG.
+pub fn resolve_demo_runtime(args: &SettingsArgs, query_terminal: bool) -> Result<DemoRuntime> {
+    let loaded = load_config_bundle(&args.config)?;
+    let env = EnvDefaults::current();
+    let (mut settings, mut origins) = resolve_with_sources(args, &loaded.demo, env);
+
+    // Only `auto` pays for detection, since the OSC 11 query needs an interactive terminal.
+    if settings.background.is_none() && settings.theme.eq_ignore_ascii_case(AUTO_THEME) {
+        let queried = if query_terminal {
+            terminal::query_background()
+        } else {
+            None
+        };
+        let detected = match (queried, env.background) {
+            (Some(background), _) => Some((background, Origin::Terminal)),
+            (None, Some(background)) => Some((background, Origin::Env("COLORFGBG"))),
+            (None, None) => None,
+        };
+        if let Some((background, origin)) = detected {
+            settings.background = Some(background);
+            origins.insert("background", origin);
+        }
+    }
+    settings.theme = select_theme(&loaded.themes, &settings.theme, settings.background)?;
     Ok(DemoRuntime {
         settings,
-        keys: loaded.keys,
+        origins,
+        keys: loaded.keys_with_preset(args.keymap_preset)?,
+        themes: loaded.themes,
+    })
+}
+
+/// Key bindings plus where they came from, for `keys` output.
+#[derive(Debug, Clone)]
+pub struct ResolvedKeys {
+    pub bindings: KeyBindings,
+    pub preset: KeymapPreset,
+    /// Where `[keys] preset` was set, when the preset came from a config file.
+    pub preset_location: Option<ConfigLocation>,
+    /// Where `[keys] sequence_timeout_ms` was set, if anywhere.
+    pub sequence_timeout_location: Option<ConfigLocation>,
+    /// Actions set in `[keys]`, replacing the preset's bindings.
+    pub from_config: Vec<KeyAction>,
+    /// The binding entries that won the merge across config layers, flat `[keys]` entries under
+    /// their home context.
+    pub entries: Vec<KeyEntry>,
+}
+
+impl ResolvedKeys {
+    /// The config entry that set `action` in `context`, if any.
+    pub fn entry(&self, context: KeyContext, action: KeyAction) -> Option<&KeyEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.context == context && entry.action == action)
+    }
+}
+
+/// Where a config layer was read from.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LayerSource {
+    File(PathBuf),
+    /// A `CLI_TUI_STARTER_*` variable, read as a one-entry document generated from its value.
+    Env(String),
+}
+
+impl LayerSource {
+    pub fn is_env(&self) -> bool {
+        matches!(self, LayerSource::Env(_))
+    }
+}
+
+impl std::fmt::Display for LayerSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayerSource::File(path) => write!(f, "{}", path.display()),
+            LayerSource::Env(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// A line in a config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigLocation {
+    pub source: LayerSource,
+    pub line: usize,
+    /// The key or value itself, for diagnostics; `None` for environment overrides and whole
+    /// files.
+    pub span: Option<SourceSpan>,
+}
+
+impl ConfigLocation {
+    fn new(source: &LayerSource, contents: &str, span: Range<usize>) -> Self {
+        let offset = span.start.min(contents.len());
+        Self {
+            source: source.clone(),
+            line: contents[..offset].matches('\n').count() + 1,
+            span: (!source.is_env()).then(|| SourceSpan::new(contents, span)),
+        }
+    }
+
+    /// A file as a whole, such as a theme in `themes/`.
+    fn file(path: &Path) -> Self {
+        Self {
+            source: LayerSource::File(path.to_path_buf()),
+            line: 1,
+            span: None,
+        }
+    }
+
+    /// True for a `CLI_TUI_STARTER_*` variable, which is a one-line layer named after itself.
+    pub fn is_env(&self) -> bool {
+        self.source.is_env()
+    }
+}
+
+impl std::fmt::Display for ConfigLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_env() {
+            return write!(f, "{}", self.source);
+        }
+        write!(f, "{}:{}", self.source, self.line)
+    }
+}
+
+/// A binding set in a config file, either in `[keys]` or a `[keys.<context>]` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyEntry {
+    pub context: KeyContext,
+    pub action: KeyAction,
+    /// `keys` or `keys.<context>`.
+    pub table: String,
+    pub location: ConfigLocation,
+}
+
+pub fn resolve_key_bindings(
+    explicit: &[PathBuf],
+    preset: Option<KeymapPreset>,
+) -> Result<ResolvedKeys> {
+    load_config_bundle(explicit)?.keys_with_preset(preset)
+}
+
+pub fn resolve_themes(explicit: &[PathBuf]) -> Result<Vec<Theme>> {
+    Ok(load_config_bundle(explicit)?.themes)
+}
+
+/// The parts of the config the interactive demo swaps in place when files change on disk.
+pub struct ReloadedConfig {
+    pub themes: Vec<Theme>,
+    pub keys: KeyBindings,
+}
+
+pub fn reload_config(explicit: &[PathBuf], preset: Option<KeymapPreset>) -> Result<ReloadedConfig> {
+    let loaded = load_config_bundle(explicit)?;
+    Ok(ReloadedConfig {
+        keys: loaded.keys_with_preset(preset)?.bindings,
+        themes: loaded.themes,
     })
 }
 
-pub fn resolve_key_bindings(path_override: Option<&Path>) -> Result<KeyBindings> {
-    Ok(load_config_bundle(path_override)?.keys)
+/// Detects edits to the config layers and `themes/*.toml` by polling modification times.
+///
+/// Polling keeps this dependency-free and behaves the same on every platform; the demo only
+/// calls it a couple of times per second.
+pub struct ConfigWatcher {
+    explicit: Vec<PathBuf>,
+    snapshot: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl ConfigWatcher {
+    pub fn new(explicit: &[PathBuf]) -> Self {
+        let explicit = explicit.to_vec();
+        let snapshot = watch_snapshot(&explicit);
+        Self { explicit, snapshot }
+    }
+
+    /// True when a watched file was created, removed or modified since the previous call.
+    pub fn poll_changed(&mut self) -> bool {
+        let snapshot = watch_snapshot(&self.explicit);
+        if snapshot == self.snapshot {
+            return false;
+        }
+        self.snapshot = snapshot;
+        true
+    }
+
+    /// Records the demo's own write to `path` (a Keys panel save), so it doesn't trigger a reload
+    /// that would replace the save's status message and reset the panel.
+    pub fn acknowledge_write(&mut self, path: &Path) {
+        let stamp = fs::metadata(path).and_then(|m| m.modified()).ok();
+        if let Some(entry) = self.snapshot.iter_mut().find(|(file, _)| file == path) {
+            entry.1 = stamp;
+        }
+    }
+}
+
+fn watch_snapshot(explicit: &[PathBuf]) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    // Layer discovery runs on every poll, so a project file created mid-session is picked up.
+    let layers = config_layers(explicit);
+    let mut snapshot = layers
+        .iter()
+        .map(|layer| (layer.path.clone(), modified(&layer.path)))
+        .collect::<Vec<_>>();
+    for dir in theme_dirs(&layers) {
+        let mut theme_files = fs::read_dir(&dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|e| e.path()))
+                    .filter(|path| path.extension() == Some(OsStr::new("toml")))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        theme_files.sort();
+        snapshot.extend(theme_files.into_iter().map(|path| {
+            let stamp = modified(&path);
+            (path, stamp)
+        }));
+    }
+    snapshot
 }
 
+/// The config schema this build reads and writes. Files without a `version` key predate it and
+/// are version 1, where the flat `[keys]` table may still bind `list_up`/`list_down`; version 2
+/// moved those into `[keys.list]`. `config migrate` upgrades a file one version at a time.
+pub const CONFIG_VERSION: u32 = 2;
+
 #[derive(Debug, Clone, Default, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[serde(deny_unknown_fields, expecting = "a table")]
 struct FileConfig {
+    version: Option<u32>,
     #[serde(default)]
     demo: DemoDefaultsRaw,
     #[serde(default)]
     keys: KeysOverridesRaw,
+    #[serde(default)]
+    themes: BTreeMap<Spanned<String>, ThemeRaw>,
+}
+
+/// A user-defined theme, either a `[themes.<name>]` table or a standalone `themes/<name>.toml`.
+/// Every palette slot is required unless the theme `extends` another one.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields, expecting = "a table")]
+struct ThemeRaw {
+    extends: Option<Spanned<String>>,
+    description: Option<String>,
+    fg: Option<Spanned<String>>,
+    bg: Option<Spanned<String>>,
+    accent: Option<Spanned<String>>,
+    muted: Option<Spanned<String>>,
+    success: Option<Spanned<String>>,
+    danger: Option<Spanned<String>>,
+    /// `[themes.<name>.styles.<slot>]` overrides for individual UI elements.
+    #[serde(default)]
+    styles: BTreeMap<Spanned<String>, StyleRaw>,
+}
+
+impl ThemeRaw {
+    /// Overlays the slots `later` sets, so a later config layer can adjust one color of a theme.
+    fn merge(&mut self, later: ThemeRaw) {
+        self.extends = later.extends.or(self.extends.take());
+        self.description = later.description.or(self.description.take());
+        self.fg = later.fg.or(self.fg.take());
+        self.bg = later.bg.or(self.bg.take());
+        self.accent = later.accent.or(self.accent.take());
+        self.muted = later.muted.or(self.muted.take());
+        self.success = later.success.or(self.success.take());
+        self.danger = later.danger.or(self.danger.take());
+        for (slot, style) in later.styles {
+            let merged = match self.styles.remove(&slot) {
+                Some(earlier) => StyleRaw {
+                    fg: style.fg.or(earlier.fg),
+                    bg: style.bg.or(earlier.bg),
+                    bold: style.bold.or(earlier.bold),
+                    italic: style.italic.or(earlier.italic),
+                    underline: style.underline.or(earlier.underline),
+                },
+                None => style,
+            };
+            self.styles.insert(slot, merged);
+        }
+    }
+
+    fn palette_slot(&self, slot: PaletteSlot) -> Option<&Spanned<String>> {
+        match slot {
+            PaletteSlot::Fg => self.fg.as_ref(),
+            PaletteSlot::Bg => self.bg.as_ref(),
+            PaletteSlot::Accent => self.accent.as_ref(),
+            PaletteSlot::Muted => self.muted.as_ref(),
+            PaletteSlot::Success => self.success.as_ref(),
+            PaletteSlot::Danger => self.danger.as_ref(),
+        }
+    }
+
+    /// Reports the colors and style slots in one layer's `table` that can't be used, so the
+    /// merged theme only has to apply values already known to be good.
+    fn check(&self, table: &str, source: &LayerSource, contents: &str) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut colors = PaletteSlot::ALL
+            .into_iter()
+            .filter_map(|slot| {
+                Some((
+                    format!("{}.{}", table, slot.as_str()),
+                    self.palette_slot(slot)?,
+                ))
+            })
+            .collect::<Vec<_>>();
+        for (slot, style) in &self.styles {
+            if StyleSlot::parse(slot.get_ref()).is_none() {
+                let slots = StyleSlot::ALL.map(StyleSlot::as_str);
+                diagnostics.push(
+                    Diagnostic::located(
+                        source,
+                        contents,
+                        Some(slot.span()),
+                        format!(
+                            "unknown style slot '{}' in [{}.styles]",
+                            slot.get_ref(),
+                            table
+                        ),
+                    )
+                    .with_suggestion(did_you_mean(slot.get_ref(), slots))
+                    .with_help(format!("valid slots: {}", slots.join(", "))),
+                );
+                continue;
+            }
+            for (field, value) in [("fg", &style.fg), ("bg", &style.bg)] {
+                if let Some(value) = value {
+                    colors.push((
+                        format!("{}.styles.{}.{}", table, slot.get_ref(), field),
+                        value,
+                    ));
+                }
+            }
+        }
+        for (name, value) in colors {
+            if let Err(err) = parse_color(value.get_ref()) {
+                diagnostics.push(Diagnostic::located(
+                    source,
+                    contents,
+                    Some(value.span()),
+                    format!("{} in `{}`", err, name),
+                ));
+            }
+        }
+        diagnostics
+    }
+}
+
+/// `[demo.theme_overrides.<theme>]`: replaces individual slots of an existing theme in place.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields, expecting = "a table")]
+struct ThemeOverrideRaw {
+    fg: Option<Spanned<String>>,
+    bg: Option<Spanned<String>>,
+    accent: Option<Spanned<String>>,
+    muted: Option<Spanned<String>>,
+    success: Option<Spanned<String>>,
+    danger: Option<Spanned<String>>,
+    #[serde(default)]
+    styles: BTreeMap<Spanned<String>, StyleRaw>,
+}
+
+impl From<ThemeOverrideRaw> for ThemeRaw {
+    /// An override is a theme without `extends`/`description`, applied over an existing one.
+    fn from(overrides: ThemeOverrideRaw) -> Self {
+        Self {
+            extends: None,
+            description: None,
+            fg: overrides.fg,
+            bg: overrides.bg,
+            accent: overrides.accent,
+            muted: overrides.muted,
+            success: overrides.success,
+            danger: overrides.danger,
+            styles: overrides.styles,
+        }
+    }
+}
+
+/// A theme definition awaiting inheritance resolution, with where it came from.
+#[derive(Debug, Clone)]
+struct ThemeEntry {
+    name: String,
+    raw: ThemeRaw,
+    /// The `[themes.<name>]` key of the layer that set it last, or its file in `themes/`.
+    location: ConfigLocation,
+    /// Where the `extends` in effect was set.
+    extends_location: Option<ConfigLocation>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields, expecting = "a table")]
+struct StyleRaw {
+    fg: Option<Spanned<String>>,
+    bg: Option<Spanned<String>>,
+    bold: Option<bool>,
+    italic: Option<bool>,
+    underline: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[serde(deny_unknown_fields, expecting = "a table")]
 struct DemoDefaultsRaw {
-    theme: Option<String>,
-    no_color: Option<bool>,
-    high_contrast: Option<bool>,
-    reduced_motion: Option<bool>,
-    ascii: Option<bool>,
-    mouse: Option<bool>,
+    theme: Option<Spanned<String>>,
+    no_color: Option<Spanned<bool>>,
+    high_contrast: Option<Spanned<bool>>,
+    reduced_motion: Option<Spanned<bool>>,
+    ascii: Option<Spanned<bool>>,
+    mouse: Option<Spanned<bool>>,
+    color_depth: Option<Spanned<String>>,
+    background: Option<Spanned<String>>,
+    #[serde(default)]
+    theme_overrides: BTreeMap<Spanned<String>, ThemeOverrideRaw>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-#[serde(untagged)]
+/// A key binding: one key spec string, or an array of them that each keep their own span.
+#[derive(Debug, Clone)]
 enum OneOrManyStrings {
     One(String),
-    Many(Vec<String>),
+    Many(Vec<Spanned<String>>),
 }
 
 impl OneOrManyStrings {
-    fn into_vec(self) -> Vec<String> {
+    /// Each spec with its span; a lone string spans the whole value, `span`.
+    fn specs(&self, span: Range<usize>) -> Vec<(&str, Range<usize>)> {
         match self {
-            OneOrManyStrings::One(v) => vec![v],
-            OneOrManyStrings::Many(v) => v,
+            OneOrManyStrings::One(v) => vec![(v.as_str(), span)],
+            OneOrManyStrings::Many(v) => {
+                v.iter().map(|s| (s.get_ref().as_str(), s.span())).collect()
+            }
+        }
+    }
+}
+
+// Written out rather than `#[serde(untagged)]`, which buffers the value and so loses the spans
+// of the array elements.
+impl<'de> Deserialize<'de> for OneOrManyStrings {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = OneOrManyStrings;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a key spec string or an array of them")
+            }
+
+            fn visit_str<E: serde::de::Error>(
+                self,
+                value: &str,
+            ) -> std::result::Result<Self::Value, E> {
+                Ok(OneOrManyStrings::One(value.to_string()))
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> std::result::Result<Self::Value, A::Error> {
+                let mut specs = Vec::new();
+                while let Some(spec) = seq.next_element()? {
+                    specs.push(spec);
+                }
+                Ok(OneOrManyStrings::Many(specs))
+            }
         }
+
+        deserializer.deserialize_any(Visitor)
     }
 }
 
+/// `[keys.<context>]`: action name -> bindings active only in that context.
+type ContextTable = BTreeMap<Spanned<String>, Spanned<OneOrManyStrings>>;
+
 #[derive(Debug, Clone, Default, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[serde(deny_unknown_fields, expecting = "a table")]
 struct KeysOverridesRaw {
-    cycle_theme: Option<OneOrManyStrings>,
-    next_panel: Option<OneOrManyStrings>,
-    prev_panel: Option<OneOrManyStrings>,
-    list_up: Option<OneOrManyStrings>,
-    list_down: Option<OneOrManyStrings>,
-    toggle_high_contrast: Option<OneOrManyStrings>,
-    toggle_color: Option<OneOrManyStrings>,
-    toggle_reduced_motion: Option<OneOrManyStrings>,
-    toggle_help: Option<OneOrManyStrings>,
-    quit: Option<OneOrManyStrings>,
+    cycle_theme: Option<Spanned<OneOrManyStrings>>,
+    next_panel: Option<Spanned<OneOrManyStrings>>,
+    prev_panel: Option<Spanned<OneOrManyStrings>>,
+    list_up: Option<Spanned<OneOrManyStrings>>,
+    list_down: Option<Spanned<OneOrManyStrings>>,
+    toggle_high_contrast: Option<Spanned<OneOrManyStrings>>,
+    toggle_color: Option<Spanned<OneOrManyStrings>>,
+    toggle_reduced_motion: Option<Spanned<OneOrManyStrings>>,
+    toggle_help: Option<Spanned<OneOrManyStrings>>,
+    quit: Option<Spanned<OneOrManyStrings>>,
+    preset: Option<Spanned<String>>,
+    sequence_timeout_ms: Option<Spanned<u64>>,
+    global: Option<ContextTable>,
+    overview: Option<ContextTable>,
+    list: Option<ContextTable>,
+    editor: Option<ContextTable>,
+    help: Option<ContextTable>,
+}
+
+impl KeysOverridesRaw {
+    fn actions(&self) -> [(KeyAction, Option<&Spanned<OneOrManyStrings>>); 10] {
+        [
+            (KeyAction::CycleTheme, self.cycle_theme.as_ref()),
+            (KeyAction::NextPanel, self.next_panel.as_ref()),
+            (KeyAction::PrevPanel, self.prev_panel.as_ref()),
+            (KeyAction::ListUp, self.list_up.as_ref()),
+            (KeyAction::ListDown, self.list_down.as_ref()),
+            (
+                KeyAction::ToggleHighContrast,
+                self.toggle_high_contrast.as_ref(),
+            ),
+            (KeyAction::ToggleColor, self.toggle_color.as_ref()),
+            (
+                KeyAction::ToggleReducedMotion,
+                self.toggle_reduced_motion.as_ref(),
+            ),
+            (KeyAction::ToggleHelp, self.toggle_help.as_ref()),
+            (KeyAction::Quit, self.quit.as_ref()),
+        ]
+    }
+
+    fn contexts(&self) -> [(KeyContext, Option<&ContextTable>); 5] {
+        [
+            (KeyContext::Global, self.global.as_ref()),
+            (KeyContext::Overview, self.overview.as_ref()),
+            (KeyContext::List, self.list.as_ref()),
+            (KeyContext::Editor, self.editor.as_ref()),
+            (KeyContext::Help, self.help.as_ref()),
+        ]
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 struct DemoDefaults {
-    theme: Option<ThemeName>,
+    theme: Option<String>,
     no_color: Option<bool>,
     high_contrast: Option<bool>,
     reduced_motion: Option<bool>,
     ascii: Option<bool>,
     mouse: Option<bool>,
+    color_depth: Option<ColorDepthArg>,
+    background: Option<Background>,
+    /// Where each value above was set, by `[demo]` key.
+    origins: BTreeMap<&'static str, ConfigLocation>,
+}
+
+/// Defaults derived from the process environment rather than config or CLI flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct EnvDefaults {
+    /// Which of `NO_COLOR`, `CLICOLOR=0` or `TERM=dumb` turns color off, if any.
+    no_color: Option<&'static str>,
+    /// Detected from `COLORTERM`/`TERM`; used when the color depth is `auto`.
+    color_depth: ColorDepth,
+    /// Parsed from `COLORFGBG`; the fallback when `theme = "auto"` and OSC 11 gets no answer.
+    background: Option<Background>,
+}
+
+impl EnvDefaults {
+    fn current() -> Self {
+        Self {
+            no_color: env_no_color_source(
+                env::var_os("NO_COLOR").as_deref(),
+                env::var_os("CLICOLOR").as_deref(),
+                env::var_os("TERM").as_deref(),
+            ),
+            color_depth: env_color_depth_current(),
+            background: background_from_colorfgbg(env::var_os("COLORFGBG").as_deref()),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 struct LoadedConfigBundle {
     demo: DemoDefaults,
-    keys: KeyBindings,
+    keys: ResolvedKeys,
+    /// `[keys]` from every layer as written, to rebuild the keymap on another preset.
+    keys_raw: Vec<KeysSource>,
+    themes: Vec<Theme>,
 }
 
-fn load_config_bundle(path_override: Option<&Path>) -> Result<LoadedConfigBundle> {
-    let config_path = match path_override {
-        Some(path) => {
-            if !path.exists() {
-                bail!("config file not found: {}", path.display());
+/// `[keys]` together with the file it came from, so entries can be traced back to their line.
+#[derive(Debug, Clone)]
+struct KeysSource {
+    raw: KeysOverridesRaw,
+    source: LayerSource,
+    contents: String,
+}
+
+impl LoadedConfigBundle {
+    /// The configured keymap, or the same `[keys]` entries on top of `preset` when given.
+    fn keys_with_preset(&self, preset: Option<KeymapPreset>) -> Result<ResolvedKeys> {
+        match preset {
+            None => Ok(self.keys.clone()),
+            Some(preset) => {
+                let mut diagnostics = Vec::new();
+                let keys = resolve_keys(&self.keys_raw, Some(preset), &mut diagnostics);
+                ConfigErrors::check(diagnostics)?;
+                Ok(keys)
             }
-            Some(path.to_path_buf())
         }
-        None => match default_config_path() {
-            Some(path) if path.exists() => Some(path),
-            _ => None,
-        },
-    };
+    }
+}
 
-    let Some(config_path) = config_path else {
-        return Ok(LoadedConfigBundle {
-            demo: DemoDefaults::default(),
-            keys: KeyBindings::default(),
-        });
-    };
+/// The system-wide config file, the lowest-precedence layer.
+pub const SYSTEM_CONFIG_PATH: &str = "/etc/cli-tui-starter/config.toml";
+
+/// The project-local config file, found by walking up from the current directory.
+pub const PROJECT_CONFIG_FILE: &str = ".cli-tui-starter.toml";
 
-    let contents = fs::read_to_string(&config_path)
-        .with_context(|| format!("failed to read config file: {}", config_path.display()))?;
-    parse_config_bundle(&contents, &config_path)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerKind {
+    System,
+    User,
+    Project,
+    /// A `--config` file.
+    Explicit,
+    /// A `CLI_TUI_STARTER_*` environment variable ([`LayerSource::Env`]).
+    Env,
 }
 
-fn parse_config_bundle(contents: &str, source: &Path) -> Result<LoadedConfigBundle> {
-    let raw: FileConfig = toml::from_str(contents)
-        .with_context(|| format!("invalid config TOML in {}", source.display()))?;
+impl LayerKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LayerKind::System => "system",
+            LayerKind::User => "user",
+            LayerKind::Project => "project",
+            LayerKind::Explicit => "explicit",
+            LayerKind::Env => "env",
+        }
+    }
+}
 
-    let theme = match raw.demo.theme {
-        Some(name) => Some(ThemeName::parse(&name).ok_or_else(|| {
-            anyhow!(
-                "invalid theme '{}' in {}. valid themes: aurora, mono, solar",
-                name,
-                source.display()
-            )
-        })?),
-        None => None,
-    };
+/// One file in the config merge chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigLayer {
+    pub kind: LayerKind,
+    pub path: PathBuf,
+}
 
-    let demo = DemoDefaults {
-        theme,
-        no_color: raw.demo.no_color,
-        high_contrast: raw.demo.high_contrast,
-        reduced_motion: raw.demo.reduced_motion,
-        ascii: raw.demo.ascii,
-        mouse: raw.demo.mouse,
-    };
+/// Every file that can take part in the merge, lowest precedence first: the system file, the
+/// user file, the nearest project file and each `--config` in order. Only `--config` files have
+/// to exist; a file listed twice counts once, at its higher precedence.
+pub fn config_layers(explicit: &[PathBuf]) -> Vec<ConfigLayer> {
+    let project = env::current_dir()
+        .ok()
+        .and_then(|dir| find_project_config(&dir));
+    let layer = |kind, path| ConfigLayer { kind, path };
+
+    let mut chain = vec![layer(LayerKind::System, PathBuf::from(SYSTEM_CONFIG_PATH))];
+    chain.extend(default_config_path().map(|path| layer(LayerKind::User, path)));
+    chain.extend(project.map(|path| layer(LayerKind::Project, path)));
+    chain.extend(
+        explicit
+            .iter()
+            .map(|path| layer(LayerKind::Explicit, path.clone())),
+    );
+
+    let mut layers = Vec::with_capacity(chain.len());
+    for (index, layer) in chain.iter().enumerate() {
+        if !chain[index + 1..]
+            .iter()
+            .any(|later| later.path == layer.path)
+        {
+            layers.push(layer.clone());
+        }
+    }
+    layers
+}
 
-    let keys = apply_keys_overrides(KeyBindings::default(), raw.keys, source)?;
+/// The nearest `.cli-tui-starter.toml` in `start` or one of its ancestors.
+fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
 
-    Ok(LoadedConfigBundle { demo, keys })
+/// The file edits are saved to: the last `--config`, else the project file, else the user file.
+pub fn writable_config_path(explicit: &[PathBuf]) -> Result<PathBuf> {
+    config_layers(explicit)
+        .into_iter()
+        .rev()
+        .find(|layer| layer.kind != LayerKind::System)
+        .map(|layer| layer.path)
+        .ok_or_else(|| anyhow!("cannot determine default config path (HOME not set)"))
 }
 
-fn apply_keys_overrides(
-    mut keymap: KeyBindings,
-    overrides: KeysOverridesRaw,
-    source: &Path,
-) -> Result<KeyBindings> {
-    fn parse_list(values: OneOrManyStrings, source: &Path, name: &str) -> Result<Vec<KeySpec>> {
-        let raw = values.into_vec();
-        if raw.is_empty() {
-            bail!(
-                "key binding '{}' in {} must not be empty",
-                name,
-                source.display()
-            );
+/// Reads the layers that exist, as `(source, contents)`. A missing `--config` file is an error.
+fn read_layers(layers: &[ConfigLayer]) -> Result<Vec<(LayerSource, String)>> {
+    let mut sources = Vec::new();
+    for layer in layers {
+        if !layer.path.exists() {
+            if layer.kind == LayerKind::Explicit {
+                bail!("config file not found: {}", layer.path.display());
+            }
+            continue;
         }
-        raw.iter()
-            .map(|s| {
-                parse_key_spec(s).with_context(|| {
-                    format!(
-                        "invalid key spec '{}' for '{}' in {}",
-                        s,
-                        name,
-                        source.display()
-                    )
-                })
-            })
-            .collect()
+        let contents = fs::read_to_string(&layer.path)
+            .with_context(|| format!("failed to read config file: {}", layer.path.display()))?;
+        sources.push((LayerSource::File(layer.path.clone()), contents));
     }
+    Ok(sources)
+}
 
-    if let Some(v) = overrides.cycle_theme {
-        keymap.cycle_theme = parse_list(v, source, "cycle_theme")?;
-    }
-    if let Some(v) = overrides.next_panel {
-        keymap.next_panel = parse_list(v, source, "next_panel")?;
+/// Prefix of the environment variables that override config keys.
+pub const ENV_PREFIX: &str = "CLI_TUI_STARTER_";
+
+/// `CLI_TUI_STARTER_<SECTION>__<KEY>` variables (`__` separates every level, so
+/// `CLI_TUI_STARTER_KEYS__LIST__LIST_DOWN` is `[keys.list] list_down`), each as a one-entry TOML
+/// document named after the variable. They merge after the config files, one layer per variable,
+/// so any error about a value names the variable that set it. A value that can't be typed is
+/// added to `diagnostics` and its variable left out. Variables with the prefix that don't have a
+/// `__` (such as `CLI_TUI_STARTER_LOG_LEVEL`) aren't config keys and are ignored.
+fn env_layers(
+    vars: impl IntoIterator<Item = (OsString, OsString)>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<(LayerSource, String)> {
+    let mut layers = Vec::new();
+    for (name, value) in vars {
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        let Some(rest) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let path = rest
+            .split("__")
+            .map(str::to_ascii_lowercase)
+            .collect::<Vec<_>>();
+        if path.len() < 2 || path.iter().any(String::is_empty) {
+            continue;
+        }
+        let source = LayerSource::Env(name.to_string());
+        let value = match value.into_string() {
+            Ok(value) => env_value(&path, &value),
+            Err(_) => Err(anyhow!("not valid UTF-8")),
+        };
+        let value = match value {
+            Ok(value) => value,
+            Err(err) => {
+                diagnostics.push(Diagnostic::new(
+                    Some(&source),
+                    format!("invalid value for {}: {:#}", name, err),
+                ));
+                continue;
+            }
+        };
+
+        let (key, tables) = path.split_last().expect("at least two segments");
+        let mut doc = toml::Table::new();
+        doc.insert(key.clone(), value);
+        for table in tables.iter().rev() {
+            let mut parent = toml::Table::new();
+            parent.insert(table.clone(), toml::Value::Table(doc));
+            doc = parent;
+        }
+        let contents = toml::to_string(&doc).expect("a table of plain values serializes");
+        layers.push((source, contents));
     }
-    if let Some(v) = overrides.prev_panel {
-        keymap.prev_panel = parse_list(v, source, "prev_panel")?;
+    layers.sort();
+    layers
+}
+
+/// Types an environment value by the key it sets: booleans and the sequence timeout are parsed,
+/// key bindings may be a TOML array (`["q", "esc"]`), and everything else is a string.
+fn env_value(path: &[String], value: &str) -> Result<toml::Value> {
+    let path = path.iter().map(String::as_str).collect::<Vec<_>>();
+    match path.as_slice() {
+        ["demo", "no_color" | "high_contrast" | "reduced_motion" | "ascii" | "mouse"]
+        | [.., "styles", _, "bold" | "italic" | "underline"] => {
+            match value.trim().to_ascii_lowercase().as_str() {
+                "true" | "1" | "yes" | "on" => Ok(toml::Value::Boolean(true)),
+                "false" | "0" | "no" | "off" => Ok(toml::Value::Boolean(false)),
+                _ => bail!("expected true or false, got '{}'", value),
+            }
+        }
+        ["keys", "sequence_timeout_ms"] => {
+            let ms = value
+                .trim()
+                .parse::<u64>()
+                .ok()
+                .and_then(|ms| i64::try_from(ms).ok())
+                .ok_or_else(|| anyhow!("expected a number of milliseconds, got '{}'", value))?;
+            Ok(toml::Value::Integer(ms))
+        }
+        ["keys", ..] if value.trim_start().starts_with('[') => {
+            let parsed: toml::Table = toml::from_str(&format!("value = {}", value))
+                .context("expected a TOML array of key specs, e.g. [\"q\", \"esc\"]")?;
+            Ok(parsed["value"].clone())
+        }
+        _ => Ok(toml::Value::String(value.to_string())),
     }
-    if let Some(v) = overrides.list_up {
-        keymap.list_up = parse_list(v, source, "list_up")?;
+}
+
+/// The existing config files followed by the environment overrides, ready to merge.
+fn read_layers_with_env(
+    layers: &[ConfigLayer],
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<Vec<(LayerSource, String)>> {
+    let mut sources = read_layers(layers)?;
+    sources.extend(env_layers(env::vars_os(), diagnostics));
+    Ok(sources)
+}
+
+fn load_config_bundle(explicit: &[PathBuf]) -> Result<LoadedConfigBundle> {
+    let layers = config_layers(explicit);
+    let mut diagnostics = Vec::new();
+    let dir_themes = load_dir_themes(&layers, &mut diagnostics)?;
+    let sources = read_layers_with_env(&layers, &mut diagnostics)?;
+    let bundle = merge_config_layers(&sources, dir_themes, &mut diagnostics);
+    ConfigErrors::check(diagnostics)?;
+    Ok(bundle)
+}
+
+/// `themes/` next to the system, user and `--config` files; project files don't carry one. A
+/// directory is honored even when the config file next to it is absent.
+fn theme_dirs(layers: &[ConfigLayer]) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for layer in layers.iter().filter(|l| l.kind != LayerKind::Project) {
+        if let Some(dir) = layer.path.parent().map(|dir| dir.join("themes")) {
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
     }
-    if let Some(v) = overrides.list_down {
-        keymap.list_down = parse_list(v, source, "list_down")?;
+    dirs
+}
+
+/// Themes from every `themes/` directory; a later directory replaces a same-named file.
+fn load_dir_themes(
+    layers: &[ConfigLayer],
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<Vec<ThemeEntry>> {
+    let mut entries: Vec<ThemeEntry> = Vec::new();
+    for dir in theme_dirs(layers).into_iter().filter(|dir| dir.is_dir()) {
+        for theme in load_themes_dir(&dir, diagnostics)? {
+            match entries
+                .iter_mut()
+                .find(|e| e.name.eq_ignore_ascii_case(&theme.name))
+            {
+                Some(existing) => *existing = theme,
+                None => entries.push(theme),
+            }
+        }
     }
-    if let Some(v) = overrides.toggle_high_contrast {
-        keymap.toggle_high_contrast = parse_list(v, source, "toggle_high_contrast")?;
+    Ok(entries)
+}
+
+/// The `version` a config file declares, or 1 for files written before versioning.
+pub fn schema_version(contents: &str) -> Result<u32> {
+    let table: toml::Table = toml::from_str(contents)?;
+    match table.get("version") {
+        None => Ok(1),
+        Some(toml::Value::Integer(version)) if *version >= 1 => {
+            u32::try_from(*version).map_err(|_| anyhow!("unsupported config version {}", version))
+        }
+        Some(other) => bail!("`version` must be a positive integer, got {}", other),
     }
-    if let Some(v) = overrides.toggle_color {
-        keymap.toggle_color = parse_list(v, source, "toggle_color")?;
+}
+
+/// Parses one layer, adding everything wrong with it to `diagnostics` and returning what is
+/// usable. A file with a bad or newer `version` is skipped, since its keys would otherwise each
+/// look unknown; from version 2 on, keys that moved out of `[keys]` are reported where set.
+fn parse_file_config(
+    source: &LayerSource,
+    contents: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<FileConfig> {
+    let doc = parse_document(source, contents, diagnostics)?;
+    if let Some(item) = doc.get("version") {
+        match item.as_integer().map(u32::try_from) {
+            Some(Ok(version)) if version > CONFIG_VERSION => {
+                diagnostics.push(
+                    Diagnostic::located(
+                        source,
+                        contents,
+                        item.span(),
+                        format!(
+                            "config version {} is newer than this cli-tui-starter supports (up to {})",
+                            version, CONFIG_VERSION
+                        ),
+                    )
+                    .with_help("upgrade cli-tui-starter to use this file"),
+                );
+                return None;
+            }
+            Some(Ok(version)) if version >= 1 => {}
+            _ => {
+                diagnostics.push(
+                    Diagnostic::located(
+                        source,
+                        contents,
+                        item.span(),
+                        "`version` must be a positive integer",
+                    )
+                    .with_help(format!("the current config version is {}", CONFIG_VERSION)),
+                );
+                return None;
+            }
+        }
     }
-    if let Some(v) = overrides.toggle_reduced_motion {
-        keymap.toggle_reduced_motion = parse_list(v, source, "toggle_reduced_motion")?;
+    let raw: FileConfig = deserialize(source, &doc, diagnostics)?;
+    if raw.version.unwrap_or(1) >= 2 {
+        for (action, value) in raw.keys.actions() {
+            let home = action.home_context();
+            let Some(value) = value.filter(|_| home != KeyContext::Global) else {
+                continue;
+            };
+            diagnostics.push(
+                Diagnostic::located(
+                    source,
+                    contents,
+                    Some(value.span()),
+                    format!(
+                        "'{}' belongs in [keys.{}] since config version 2, not [keys]",
+                        action.as_str(),
+                        home.as_str()
+                    ),
+                )
+                .with_help(format!("move it under [keys.{}]", home.as_str())),
+            );
+        }
     }
-    if let Some(v) = overrides.toggle_help {
-        keymap.toggle_help = parse_list(v, source, "toggle_help")?;
+    Some(raw)
+}
+
+/// Merges config files given lowest precedence first. Later layers override earlier ones field
+/// by field: `[demo]` values, individual `[keys]` entries and single slots of a `[themes.<name>]`.
+/// Every problem found on the way is added to `diagnostics`, pointing at the entry at fault, and
+/// the merge carries on without it; the bundle is only usable when none were.
+fn merge_config_layers(
+    layers: &[(LayerSource, String)],
+    dir_themes: Vec<ThemeEntry>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> LoadedConfigBundle {
+    let parsed = layers
+        .iter()
+        .filter_map(|(source, contents)| {
+            parse_file_config(source, contents, diagnostics).map(|raw| (source, contents, raw))
+        })
+        .collect::<Vec<_>>();
+
+    let mut entries: Vec<ThemeEntry> = Vec::new();
+    for (source, contents, raw) in &parsed {
+        let earlier = entries.len();
+        for (name, theme) in &raw.themes {
+            diagnostics.extend(theme.check(
+                &format!("themes.{}", name.get_ref()),
+                source,
+                contents,
+            ));
+            let location = ConfigLocation::new(source, contents, name.span());
+            let extends_location = theme
+                .extends
+                .as_ref()
+                .map(|parent| ConfigLocation::new(source, contents, parent.span()));
+            match entries[..earlier]
+                .iter_mut()
+                .find(|e| e.name.eq_ignore_ascii_case(name.get_ref()))
+            {
+                Some(entry) => {
+                    entry.raw.merge(theme.clone());
+                    entry.location = location;
+                    if extends_location.is_some() {
+                        entry.extends_location = extends_location;
+                    }
+                }
+                None => entries.push(ThemeEntry {
+                    name: name.get_ref().clone(),
+                    raw: theme.clone(),
+                    location,
+                    extends_location,
+                }),
+            }
+        }
     }
-    if let Some(v) = overrides.quit {
-        keymap.quit = parse_list(v, source, "quit")?;
+    for theme in dir_themes {
+        if let Some(entry) = entries
+            .iter()
+            .find(|e| e.name.eq_ignore_ascii_case(&theme.name))
+        {
+            diagnostics.push(Diagnostic::at(
+                &theme.location,
+                format!(
+                    "theme '{}' from the themes directory is already defined in {}",
+                    theme.name, entry.location
+                ),
+            ));
+            continue;
+        }
+        entries.push(theme);
     }
+    let mut list = resolve_theme_entries(entries, diagnostics);
 
-    keymap
-        .validate()
-        .with_context(|| format!("invalid key bindings configuration in {}", source.display()))?;
-    Ok(keymap)
-}
-
-fn resolve_with_sources(
-    args: &DemoArgs,
-    defaults: &DemoDefaults,
-    env_no_color: bool,
-) -> DemoSettings {
-    let theme = args.theme.or(defaults.theme).unwrap_or(ThemeName::Aurora);
+    let mut demo = DemoDefaults::default();
+    let mut theme_name: Option<(&String, ConfigLocation)> = None;
+    for (source, contents, raw) in &parsed {
+        let location = |span| ConfigLocation::new(source, contents, span);
+        for (name, overrides) in &raw.demo.theme_overrides {
+            let overrides = ThemeRaw::from(overrides.clone());
+            let table = format!("demo.theme_overrides.{}", name.get_ref());
+            diagnostics.extend(overrides.check(&table, source, contents));
+            match list
+                .iter()
+                .position(|t| t.name.eq_ignore_ascii_case(name.get_ref().trim()))
+            {
+                Some(index) => apply_theme_slots(&mut list[index], &overrides),
+                None => diagnostics.push(unknown_theme(
+                    &location(name.span()),
+                    name.get_ref(),
+                    &list,
+                    false,
+                )),
+            }
+        }
 
-    let no_color = if args.no_color {
-        true
-    } else if args.color {
-        false
-    } else if let Some(value) = defaults.no_color {
-        value
-    } else {
-        env_no_color
-    };
+        if let Some(name) = &raw.demo.theme {
+            theme_name = Some((name.get_ref(), location(name.span())));
+            demo.origins.insert("theme", location(name.span()));
+        }
+        if let Some(value) = &raw.demo.color_depth {
+            demo.origins.insert("color_depth", location(value.span()));
+            match ColorDepthArg::parse(value.get_ref()) {
+                Some(depth) => demo.color_depth = Some(depth),
+                None => diagnostics.push(
+                    Diagnostic::at(
+                        &location(value.span()),
+                        format!("invalid color_depth '{}'", value.get_ref()),
+                    )
+                    .with_help("valid values: auto, truecolor, 256, 16"),
+                ),
+            }
+        }
+        if let Some(value) = &raw.demo.background {
+            demo.origins.insert("background", location(value.span()));
+            match Background::parse(value.get_ref()) {
+                Some(background) => demo.background = Some(background),
+                None => diagnostics.push(
+                    Diagnostic::at(
+                        &location(value.span()),
+                        format!("invalid background '{}'", value.get_ref()),
+                    )
+                    .with_suggestion(did_you_mean(value.get_ref(), ["light", "dark"]))
+                    .with_help("valid values: light, dark"),
+                ),
+            }
+        }
+        for (key, value, target) in [
+            ("no_color", &raw.demo.no_color, &mut demo.no_color),
+            (
+                "high_contrast",
+                &raw.demo.high_contrast,
+                &mut demo.high_contrast,
+            ),
+            (
+                "reduced_motion",
+                &raw.demo.reduced_motion,
+                &mut demo.reduced_motion,
+            ),
+            ("ascii", &raw.demo.ascii, &mut demo.ascii),
+            ("mouse", &raw.demo.mouse, &mut demo.mouse),
+        ] {
+            if let Some(value) = value {
+                *target = Some(*value.get_ref());
+                demo.origins.insert(key, location(value.span()));
+            }
+        }
+    }
 
-    let high_contrast = if args.high_contrast {
-        true
-    } else if args.normal_contrast {
-        false
-    } else {
-        defaults.high_contrast.unwrap_or(false)
+    // Checked against the merged theme list, so a project file can pick a user-defined theme.
+    demo.theme = match theme_name {
+        Some((name, _)) if name.trim().eq_ignore_ascii_case(AUTO_THEME) => {
+            Some(AUTO_THEME.to_string())
+        }
+        Some((name, location)) => match find_theme(&list, name) {
+            Some(theme) => Some(theme.name.clone()),
+            None => {
+                diagnostics.push(unknown_theme(&location, name, &list, true));
+                None
+            }
+        },
+        None => None,
     };
 
-    let reduced_motion = if args.reduced_motion {
-        true
-    } else if args.motion {
-        false
-    } else {
-        defaults.reduced_motion.unwrap_or(false)
-    };
+    let keys_raw = parsed
+        .into_iter()
+        .map(|(source, contents, raw)| KeysSource {
+            raw: raw.keys,
+            source: source.clone(),
+            contents: contents.clone(),
+        })
+        .collect::<Vec<_>>();
+    let keys = resolve_keys(&keys_raw, None, diagnostics);
 
-    let mouse = if args.mouse {
-        true
-    } else if args.no_mouse {
-        false
-    } else {
-        defaults.mouse.unwrap_or(false)
+    LoadedConfigBundle {
+        demo,
+        keys,
+        keys_raw,
+        themes: list,
+    }
+}
+
+/// A theme name at `location` that isn't in `list` (or `auto`, where `allow_auto`).
+fn unknown_theme(
+    location: &ConfigLocation,
+    name: &str,
+    list: &[Theme],
+    allow_auto: bool,
+) -> Diagnostic {
+    let mut valid: Vec<&str> = Vec::new();
+    if allow_auto {
+        valid.push(AUTO_THEME);
+    }
+    valid.extend(list.iter().map(|theme| theme.name.as_str()));
+    Diagnostic::at(location, format!("unknown theme '{}'", name))
+        .with_suggestion(did_you_mean(name, valid.iter().copied()))
+        .with_help(format!("valid themes: {}", valid.join(", ")))
+}
+
+/// Builds the final theme list: built-ins followed by `entries` in order, with `extends`
+/// resolved. Parents may be built-ins or other entries, in any order.
+fn resolve_theme_entries(
+    entries: Vec<ThemeEntry>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<Theme> {
+    let builtins = themes();
+    let mut resolved: Vec<Option<Theme>> = vec![None; entries.len()];
+    for index in 0..entries.len() {
+        resolve_theme_entry(
+            index,
+            &entries,
+            &builtins,
+            &mut resolved,
+            &mut Vec::new(),
+            diagnostics,
+        );
+    }
+
+    let mut list = builtins;
+    list.extend(resolved.into_iter().flatten());
+    list
+}
+
+/// Resolves one entry, reporting each problem with it. A theme that has problems still resolves
+/// (with unset colors where needed), so one mistake isn't reported again by everything that
+/// names the theme.
+fn resolve_theme_entry(
+    index: usize,
+    entries: &[ThemeEntry],
+    builtins: &[Theme],
+    resolved: &mut [Option<Theme>],
+    chain: &mut Vec<usize>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Theme {
+    if let Some(theme) = &resolved[index] {
+        return theme.clone();
+    }
+    let entry = &entries[index];
+    let unset = Color::Reset;
+    let blank = Theme {
+        name: entry.name.clone(),
+        description: "Custom theme".to_string(),
+        palette: ThemePalette {
+            fg: unset,
+            bg: unset,
+            accent: unset,
+            muted: unset,
+            success: unset,
+            danger: unset,
+        },
+        style_overrides: BTreeMap::new(),
     };
+    let extends_location = entry.extends_location.as_ref().unwrap_or(&entry.location);
+    if let Some(start) = chain.iter().position(|&i| i == index) {
+        let names = chain[start..]
+            .iter()
+            .chain([&index])
+            .map(|&i| entries[i].name.as_str())
+            .collect::<Vec<_>>();
+        diagnostics.push(Diagnostic::at(
+            extends_location,
+            format!("theme inheritance cycle: {}", names.join(" -> ")),
+        ));
+        return blank;
+    }
+    if let Err(err) = validate_theme_name(&entry.name) {
+        diagnostics.push(Diagnostic::at(&entry.location, err.to_string()));
+    }
 
+    let mut theme = match &entry.raw.extends {
+        Some(parent) => {
+            let parent_index = entries
+                .iter()
+                .position(|e| e.name.eq_ignore_ascii_case(parent.get_ref().trim()));
+            let parent_theme = match parent_index {
+                Some(parent_index) => {
+                    chain.push(index);
+                    let theme = resolve_theme_entry(
+                        parent_index,
+                        entries,
+                        builtins,
+                        resolved,
+                        chain,
+                        diagnostics,
+                    );
+                    chain.pop();
+                    theme
+                }
+                None => match find_theme(builtins, parent.get_ref()) {
+                    Some(theme) => theme.clone(),
+                    None => {
+                        let names = builtins
+                            .iter()
+                            .map(|t| t.name.as_str())
+                            .chain(entries.iter().map(|e| e.name.as_str()))
+                            .collect::<Vec<_>>();
+                        diagnostics.push(
+                            Diagnostic::at(
+                                extends_location,
+                                format!(
+                                    "theme '{}' extends unknown theme '{}'",
+                                    entry.name,
+                                    parent.get_ref()
+                                ),
+                            )
+                            .with_suggestion(did_you_mean(parent.get_ref(), names.iter().copied()))
+                            .with_help(format!("valid themes: {}", names.join(", "))),
+                        );
+                        blank.clone()
+                    }
+                },
+            };
+            Theme {
+                name: entry.name.clone(),
+                description: format!("Based on {}", parent_theme.name),
+                ..parent_theme
+            }
+        }
+        None => {
+            let missing = PaletteSlot::ALL
+                .into_iter()
+                .filter(|&slot| entry.raw.palette_slot(slot).is_none())
+                .map(|slot| format!("'{}'", slot.as_str()))
+                .collect::<Vec<_>>();
+            if !missing.is_empty() {
+                diagnostics.push(
+                    Diagnostic::at(
+                        &entry.location,
+                        format!("theme '{}' is missing {}", entry.name, missing.join(", ")),
+                    )
+                    .with_help("set every palette slot, or use `extends`"),
+                );
+            }
+            // Every slot is set by `apply_theme_slots` below.
+            blank
+        }
+    };
+
+    if let Some(description) = &entry.raw.description {
+        theme.description = description.clone();
+    }
+    apply_theme_slots(&mut theme, &entry.raw);
+
+    resolved[index] = Some(theme.clone());
+    theme
+}
+
+/// Applies the palette slots and style overrides that are set, leaving the rest as inherited.
+/// Colors and slot names that are no good were reported by `ThemeRaw::check` when their layer
+/// was read, and are skipped here.
+fn apply_theme_slots(theme: &mut Theme, raw: &ThemeRaw) {
+    let color = |value: &Spanned<String>| parse_color(value.get_ref()).ok();
+
+    for slot in PaletteSlot::ALL {
+        if let Some(color) = raw.palette_slot(slot).and_then(color) {
+            *theme.palette.slot_mut(slot) = color;
+        }
+    }
+
+    for (slot_name, style) in &raw.styles {
+        let Some(slot) = StyleSlot::parse(slot_name.get_ref()) else {
+            continue;
+        };
+        // Merge field-by-field so a child theme can tweak one attribute of an inherited slot.
+        let inherited = theme
+            .style_overrides
+            .get(&slot)
+            .copied()
+            .unwrap_or_default();
+        theme.style_overrides.insert(
+            slot,
+            StyleOverride {
+                fg: style.fg.as_ref().and_then(color).or(inherited.fg),
+                bg: style.bg.as_ref().and_then(color).or(inherited.bg),
+                bold: style.bold.or(inherited.bold),
+                italic: style.italic.or(inherited.italic),
+                underline: style.underline.or(inherited.underline),
+            },
+        );
+    }
+}
+
+/// Loads `themes/*.toml`; each file defines one theme named after its file stem.
+fn load_themes_dir(dir: &Path, diagnostics: &mut Vec<Diagnostic>) -> Result<Vec<ThemeEntry>> {
+    let mut paths = fs::read_dir(dir)
+        .with_context(|| format!("failed to read themes directory: {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension() == Some(OsStr::new("toml")))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut out = Vec::with_capacity(paths.len());
+    for path in paths {
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read theme file: {}", path.display()))?;
+        out.extend(parse_theme_file(name, &path, &contents, diagnostics));
+    }
+    Ok(out)
+}
+
+/// The theme `name` that a file in `themes/` defines, read like a config layer.
+fn parse_theme_file(
+    name: &str,
+    path: &Path,
+    contents: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<ThemeEntry> {
+    let source = LayerSource::File(path.to_path_buf());
+    let doc = parse_document(&source, contents, diagnostics)?;
+    let raw: ThemeRaw = deserialize(&source, &doc, diagnostics)?;
+    diagnostics.extend(raw.check(&format!("themes.{}", name), &source, contents));
+    Some(ThemeEntry {
+        name: name.to_string(),
+        extends_location: raw
+            .extends
+            .as_ref()
+            .map(|parent| ConfigLocation::new(&source, contents, parent.span())),
+        location: ConfigLocation::file(path),
+        raw,
+    })
+}
+
+/// Builds the keymap from a preset (`preset_override`, else the last `[keys] preset`, else
+/// `default`) with the individual `[keys]` entries of every layer applied on top. An entry in a
+/// later layer replaces the same action (and context) from an earlier one. Entries that can't be
+/// used are reported and left out, as is every conflict in the resulting keymap.
+fn resolve_keys(
+    layers: &[KeysSource],
+    preset_override: Option<KeymapPreset>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> ResolvedKeys {
+    /// The bindings of `name`, or `None` after reporting why they can't be used.
+    fn parse_list(
+        values: &Spanned<OneOrManyStrings>,
+        name: &str,
+        layer: &KeysSource,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Option<Vec<KeySequence>> {
+        let specs = values.get_ref().specs(values.span());
+        if specs.is_empty() {
+            diagnostics.push(Diagnostic::located(
+                &layer.source,
+                &layer.contents,
+                Some(values.span()),
+                format!("key binding `{}` must not be empty", name),
+            ));
+            return None;
+        }
+        let mut keys = Vec::with_capacity(specs.len());
+        let mut valid = true;
+        for (spec, span) in specs {
+            match parse_key_sequence(spec) {
+                Ok(sequence) => keys.push(sequence),
+                Err(err) => {
+                    valid = false;
+                    diagnostics.push(
+                        Diagnostic::located(
+                            &layer.source,
+                            &layer.contents,
+                            Some(span),
+                            format!("invalid key spec '{}' for `{}`: {:#}", spec, name, err),
+                        )
+                        .with_suggestion(suggest_key_spec(spec).as_deref()),
+                    );
+                }
+            }
+        }
+        valid.then_some(keys)
+    }
+
+    let mut config_preset = None;
+    let mut sequence_timeout_ms = None;
+    // Bindings for an action's home context, and extras for other contexts, in first-seen order.
+    let mut home: Vec<(Vec<KeySequence>, KeyEntry)> = Vec::new();
+    let mut scoped: Vec<(ScopedBinding, KeyEntry)> = Vec::new();
+    for layer in layers {
+        let overrides = &layer.raw;
+        let location = |span| ConfigLocation::new(&layer.source, &layer.contents, span);
+
+        if let Some(value) = &overrides.preset {
+            match KeymapPreset::parse(value.get_ref()) {
+                Some(preset) => config_preset = Some((preset, location(value.span()))),
+                None => {
+                    let presets = KeymapPreset::ALL.map(KeymapPreset::as_str);
+                    diagnostics.push(
+                        Diagnostic::at(
+                            &location(value.span()),
+                            format!("invalid keymap preset '{}'", value.get_ref()),
+                        )
+                        .with_suggestion(did_you_mean(value.get_ref(), presets))
+                        .with_help(format!("valid values: {}", presets.join(", "))),
+                    );
+                }
+            }
+        }
+        if let Some(ms) = &overrides.sequence_timeout_ms {
+            sequence_timeout_ms = Some((*ms.get_ref(), location(ms.span())));
+        }
+
+        let mut set_here = Vec::new();
+        let mut set_home =
+            |keys, entry: KeyEntry| match home.iter_mut().find(|(_, e)| e.action == entry.action) {
+                Some(slot) => *slot = (keys, entry),
+                None => home.push((keys, entry)),
+            };
+        for (action, values) in overrides.actions() {
+            if let Some(values) = values {
+                set_here.push(action);
+                let name = format!("keys.{}", action.as_str());
+                let Some(keys) = parse_list(values, &name, layer, diagnostics) else {
+                    continue;
+                };
+                set_home(
+                    keys,
+                    KeyEntry {
+                        context: action.home_context(),
+                        action,
+                        table: "keys".to_string(),
+                        location: location(values.span()),
+                    },
+                );
+            }
+        }
+        // A context table entry for the action's home context is the same slot as the flat
+        // entry; anywhere else it adds bindings that only apply in that context.
+        for (context, table) in overrides.contexts() {
+            for (name, values) in table.into_iter().flatten() {
+                let Some(action) = KeyAction::parse(name.get_ref()) else {
+                    let actions = KeyAction::ALL.map(KeyAction::as_str);
+                    diagnostics.push(
+                        Diagnostic::at(
+                            &location(name.span()),
+                            format!(
+                                "unknown action '{}' in [keys.{}]",
+                                name.get_ref(),
+                                context.as_str()
+                            ),
+                        )
+                        .with_suggestion(did_you_mean(name.get_ref(), actions))
+                        .with_help(format!("valid actions: {}", actions.join(", "))),
+                    );
+                    continue;
+                };
+                let entry = KeyEntry {
+                    context,
+                    action,
+                    table: format!("keys.{}", context.as_str()),
+                    location: location(values.span()),
+                };
+                let home_slot = context == action.home_context();
+                if home_slot && set_here.contains(&action) {
+                    diagnostics.push(Diagnostic::at(
+                        &entry.location,
+                        format!(
+                            "key binding '{}' is set in both [keys] and [keys.{}]",
+                            action.as_str(),
+                            context.as_str()
+                        ),
+                    ));
+                    continue;
+                }
+                let name = format!("{}.{}", entry.table, action.as_str());
+                let keys = parse_list(values, &name, layer, diagnostics);
+                if home_slot {
+                    set_here.push(action);
+                }
+                let Some(keys) = keys else {
+                    continue;
+                };
+                if home_slot {
+                    set_home(keys, entry);
+                    continue;
+                }
+                let binding = ScopedBinding {
+                    context,
+                    action,
+                    keys,
+                };
+                match scoped
+                    .iter_mut()
+                    .find(|(b, _)| b.context == context && b.action == action)
+                {
+                    Some(slot) => *slot = (binding, entry),
+                    None => scoped.push((binding, entry)),
+                }
+            }
+        }
+    }
+
+    let preset = preset_override
+        .or(config_preset.as_ref().map(|(preset, _)| *preset))
+        .unwrap_or(KeymapPreset::Default);
+    let preset_location = match preset_override {
+        Some(_) => None,
+        None => config_preset.map(|(_, location)| location),
+    };
+
+    let mut keymap = KeyBindings::preset(preset);
+    let mut from_config = Vec::new();
+    let mut entries = Vec::new();
+    for (keys, entry) in home {
+        *keymap.keys_mut(entry.action) = keys;
+        from_config.push(entry.action);
+        entries.push(entry);
+    }
+    for (binding, entry) in scoped {
+        keymap.scoped.push(binding);
+        entries.push(entry);
+    }
+    let sequence_timeout_location = sequence_timeout_ms.map(|(ms, location)| {
+        keymap.sequence_timeout = Duration::from_millis(ms);
+        location
+    });
+
+    // Each problem points at the config entry behind the first binding involved that has one,
+    // naming where the other was set in the help; preset bindings never conflict on their own.
+    for problem in keymap.problems() {
+        let mut locations = problem.bindings.iter().filter_map(|&(context, action)| {
+            entries
+                .iter()
+                .find(|entry| entry.context == context && entry.action == action)
+                .map(|entry| &entry.location)
+        });
+        let location = if problem.bindings.is_empty() {
+            sequence_timeout_location.as_ref()
+        } else {
+            locations.next()
+        };
+        let mut diagnostic = match location {
+            Some(location) => Diagnostic::at(location, problem.message),
+            None => Diagnostic::new(None, problem.message),
+        };
+        if let Some(other) = locations.next() {
+            diagnostic = diagnostic.with_help(format!("the other binding is set at {}", other));
+        }
+        diagnostics.push(diagnostic);
+    }
+    ResolvedKeys {
+        bindings: keymap,
+        preset,
+        preset_location,
+        sequence_timeout_location,
+        from_config,
+        entries,
+    }
+}
+
+/// CLI flags win over `defaults` (the merged config files with `CLI_TUI_STARTER_*` variables on
+/// top), which win over what `env` detects. Also returns where each setting came from.
+fn resolve_with_sources(
+    args: &SettingsArgs,
+    defaults: &DemoDefaults,
+    env: EnvDefaults,
+) -> (DemoSettings, BTreeMap<&'static str, Origin>) {
+    let mut origins = BTreeMap::new();
+    let configured = |key: &str| {
+        defaults
+            .origins
+            .get(key)
+            .cloned()
+            .map_or(Origin::Default, Origin::Config)
+    };
+
+    let theme = match (&args.theme, &defaults.theme) {
+        (Some(theme), _) => (theme.clone(), Origin::Cli("--theme")),
+        (None, Some(theme)) => (theme.clone(), configured("theme")),
+        (None, None) => (ThemeName::Aurora.as_str().to_string(), Origin::Default),
+    };
+
+    // A `--x`/`--no-x` flag pair beats the config value, which beats `fallback`.
+    let mut toggle = |key: &'static str,
+                      on: (bool, &'static str),
+                      off: (bool, &'static str),
+                      config: Option<bool>,
+                      fallback: (bool, Origin)| {
+        let (value, origin) = if on.0 {
+            (true, Origin::Cli(on.1))
+        } else if off.0 {
+            (false, Origin::Cli(off.1))
+        } else if let Some(value) = config {
+            (value, configured(key))
+        } else {
+            fallback
+        };
+        origins.insert(key, origin);
+        value
+    };
+
+    let no_color = toggle(
+        "no_color",
+        (args.no_color, "--no-color"),
+        (args.color, "--color"),
+        defaults.no_color,
+        match env.no_color {
+            Some(var) => (true, Origin::Env(var)),
+            None => (false, Origin::Default),
+        },
+    );
+    let high_contrast = toggle(
+        "high_contrast",
+        (args.high_contrast, "--high-contrast"),
+        (args.normal_contrast, "--normal-contrast"),
+        defaults.high_contrast,
+        (false, Origin::Default),
+    );
+    let reduced_motion = toggle(
+        "reduced_motion",
+        (args.reduced_motion, "--reduced-motion"),
+        (args.motion, "--motion"),
+        defaults.reduced_motion,
+        (false, Origin::Default),
+    );
+    let mouse = toggle(
+        "mouse",
+        (args.mouse, "--mouse"),
+        (args.no_mouse, "--no-mouse"),
+        defaults.mouse,
+        (false, Origin::Default),
+    );
     // Only affects `demo --no-tty` rendering; keeping it in the config makes CI/docs output
     // deterministic without needing extra CLI flags.
-    let ascii = if args.ascii {
-        true
-    } else {
-        defaults.ascii.unwrap_or(false)
+    let ascii = toggle(
+        "ascii",
+        (args.ascii, "--ascii"),
+        (false, ""),
+        defaults.ascii,
+        (false, Origin::Default),
+    );
+
+    // An explicit depth from the CLI wins; `auto` (from either layer) defers to the environment.
+    let requested = match (args.color_depth, defaults.color_depth) {
+        (Some(depth), _) => Some((depth, Origin::Cli("--color-depth"))),
+        (None, Some(depth)) => Some((depth, configured("color_depth"))),
+        (None, None) => None,
+    };
+    let (color_depth, color_depth_origin) = match requested {
+        Some((depth, origin)) if depth.depth().is_some() => (depth.depth(), origin),
+        _ => (None, Origin::Env("COLORTERM/TERM")),
+    };
+    let color_depth = color_depth.unwrap_or(env.color_depth);
+
+    let background = match (args.background, defaults.background) {
+        (Some(background), _) => Some((background, Origin::Cli("--background"))),
+        (None, Some(background)) => Some((background, configured("background"))),
+        (None, None) => None,
     };
 
-    DemoSettings {
-        theme,
+    origins.insert("theme", theme.1);
+    origins.insert("color_depth", color_depth_origin);
+    if let Some((_, origin)) = &background {
+        origins.insert("background", origin.clone());
+    }
+    let settings = DemoSettings {
+        theme: theme.0,
         no_color,
         high_contrast,
         reduced_motion,
         ascii,
         mouse,
-    }
+        color_depth,
+        background: background.map(|(background, _)| background),
+    };
+    (settings, origins)
 }
 
 pub fn default_config_path() -> Option<PathBuf> {
@@ -299,16 +1783,24 @@ pub fn default_config_path() -> Option<PathBuf> {
 
 pub fn starter_config_toml() -> &'static str {
     // Keep this ASCII-only, and keep defaults aligned with `Default` impls and README schema.
-    r#"# cli-tui-starter config
+    r##"# cli-tui-starter config
 #
 # Precedence:
 # 1) CLI flags
-# 2) Config file values
-# 3) Built-in defaults and NO_COLOR/CLICOLOR=0/TERM=dumb
+# 2) CLI_TUI_STARTER_<SECTION>__<KEY> environment variables, e.g. CLI_TUI_STARTER_DEMO__THEME
+# 3) Config file values: --config files, then ./.cli-tui-starter.toml (nearest parent
+#    directory), then this file, then /etc/cli-tui-starter/config.toml
+# 4) Built-in defaults and NO_COLOR/CLICOLOR=0/TERM=dumb
+
+# Config schema version. `cli-tui-starter config migrate` upgrades files from older releases.
+version = 2
 
 [demo]
-# theme = "aurora"         # aurora | mono | solar
+# theme = "aurora"         # aurora | mono | solar | *-light | auto | any [themes.<name>] below
 theme = "aurora"
+# Terminal background: light | dark. Picks the matching variant (solar -> solar-light).
+# With theme = "auto" and no background set, the terminal is queried (OSC 11), then COLORFGBG.
+# background = "dark"
 no_color = false
 high_contrast = false
 reduced_motion = false
@@ -316,27 +1808,312 @@ reduced_motion = false
 ascii = false
 # Enable mouse support in the interactive demo (off by default).
 mouse = false
+# Terminal color depth: auto | truecolor | 256 | 16. `auto` checks COLORTERM and TERM.
+color_depth = "auto"
+
+# Adjust individual slots of an existing theme without defining a new one.
+# [demo.theme_overrides.solar]
+# accent = "208"
 
 [keys]
+# Start from a preset: default | vim | emacs | arrows-only. Each entry below replaces the preset's
+# binding for that action; delete the entries you want to inherit from the preset.
+# preset = "default"
 cycle_theme = "t"
 next_panel = "tab"
 prev_panel = "backtab"     # shift+tab
-list_up = "up"
-list_down = "down"
 toggle_high_contrast = "h"
 toggle_color = "c"
 toggle_reduced_motion = "r"
 toggle_help = "?"
 quit = ["q", "esc"]        # `esc` and `ctrl+c` always quit even if not listed
-"#
+# Bindings can be sequences of keys separated by spaces, e.g. "g g" or "ctrl+x ctrl+s". A binding
+# cannot be a prefix of another one. Esc cancels a half-typed sequence.
+sequence_timeout_ms = 1000 # how long to wait for the next key of a sequence
+
+# [keys] binds actions everywhere; list_up/list_down live in the list panel's table. Bind any
+# action in one context with [keys.global], [keys.overview], [keys.list] or [keys.help]; sibling
+# contexts (overview, list) can reuse the same key.
+[keys.list]
+list_up = "up"
+list_down = "down"
+# [keys.overview]
+# cycle_theme = "j"
+
+# Custom themes define every palette slot. Colors are named ANSI colors ("lightblue"),
+# "#rrggbb" truecolor values, or 256-color palette indices ("208"). Themes can also live in
+# `themes/<name>.toml` next to this file (same keys, without the table header).
+# [themes.brand]
+# description = "Our brand palette"
+# fg = "white"
+# bg = "black"
+# accent = "lightmagenta"
+# muted = "gray"
+# success = "lightgreen"
+# danger = "lightred"
+#
+# Optional per-element styles: border, border_focused, title, selection, tab_active, tab_inactive,
+# gauge_fill, gauge_empty, warning, success, info, header_bg, popup_bg. Each takes fg/bg colors
+# and bold/italic/underline; unset fields keep the defaults derived from the palette.
+# [themes.brand.styles.border]
+# fg = "magenta"
+# [themes.brand.styles.title]
+# underline = true
+#
+# `extends` inherits every slot from another theme; set only what differs.
+# [themes.warm]
+# extends = "solar"
+# danger = "#ff5f5f"
+"##
+}
+
+/// Checks every existing layer and returns the layers (environment overrides last) with all the
+/// problems found, located in their files. Unreadable files are still a hard error.
+pub fn diagnose_config(explicit: &[PathBuf]) -> Result<(Vec<CheckedLayer>, Vec<Diagnostic>)> {
+    let layers = config_layers(explicit);
+    let mut sources = read_layers(&layers)?;
+    let mut diagnostics = Vec::new();
+    sources.extend(env_layers(env::vars_os(), &mut diagnostics));
+    let dir_themes = load_dir_themes(&layers, &mut diagnostics).unwrap_or_else(|err| {
+        diagnostics.push(Diagnostic::new(None, format!("{:#}", err)));
+        Vec::new()
+    });
+    merge_config_layers(&sources, dir_themes, &mut diagnostics);
+
+    let checked = sources
+        .into_iter()
+        .map(|(source, contents)| {
+            let (kind, version) = match &source {
+                LayerSource::Env(_) => (LayerKind::Env, None),
+                LayerSource::File(path) => (
+                    layers
+                        .iter()
+                        .find(|layer| &layer.path == path)
+                        .map_or(LayerKind::Explicit, |layer| layer.kind),
+                    schema_version(&contents).ok(),
+                ),
+            };
+            CheckedLayer {
+                kind,
+                source,
+                version,
+            }
+        })
+        .collect();
+    Ok((checked, diagnostics))
+}
+
+/// A layer `config validate` read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckedLayer {
+    pub kind: LayerKind,
+    pub source: LayerSource,
+    /// The file's schema version; `None` for environment overrides and unparsable files.
+    pub version: Option<u32>,
 }
 
-pub fn validate_config_file(path: &Path) -> Result<()> {
-    let _ = load_config_bundle(Some(path))?;
+/// Validates config text that is about to be written to `path`, merged with the other layers
+/// (and their `themes/`) as it would be on the next load.
+pub fn validate_config_contents(contents: &str, path: &Path, explicit: &[PathBuf]) -> Result<()> {
+    let mut layers = config_layers(explicit);
+    if !layers.iter().any(|layer| layer.path == path) {
+        layers.push(ConfigLayer {
+            kind: LayerKind::Explicit,
+            path: path.to_path_buf(),
+        });
+    }
+    let mut sources = Vec::new();
+    for layer in &layers {
+        if layer.path == path {
+            sources.push((LayerSource::File(layer.path.clone()), contents.to_string()));
+        } else {
+            sources.extend(read_layers(std::slice::from_ref(layer))?);
+        }
+    }
+    let mut diagnostics = Vec::new();
+    sources.extend(env_layers(env::vars_os(), &mut diagnostics));
+    let dir_themes = load_dir_themes(&layers, &mut diagnostics)?;
+    merge_config_layers(&sources, dir_themes, &mut diagnostics);
+    ConfigErrors::check(diagnostics)?;
     Ok(())
 }
 
-fn env_disables_color_current() -> bool {
+/// Sets the bindings for `edits` in `[keys]` and returns the new file contents. Everything else,
+/// including comments and formatting, is kept. An action already set in its home context table
+/// (`[keys.list] list_down`) is updated there instead, so it isn't defined twice; from config
+/// version 2 on (and in a new file) such actions always go in their home context table.
+pub fn update_keys_toml(
+    contents: &str,
+    edits: &[(KeyContext, KeyAction, Vec<KeySequence>)],
+) -> Result<String> {
+    let mut doc = contents
+        .parse::<toml_edit::DocumentMut>()
+        .context("invalid config TOML")?;
+    let version = if contents.trim().is_empty() {
+        set_schema_version(&mut doc);
+        CONFIG_VERSION
+    } else {
+        schema_version(contents).context("invalid config TOML")?
+    };
+    let keys = doc
+        .entry("keys")
+        .or_insert_with(toml_edit::table)
+        .as_table_like_mut()
+        .ok_or_else(|| anyhow!("`keys` in the config file is not a table"))?;
+
+    for (context, action, sequences) in edits {
+        let labels = sequences
+            .iter()
+            .map(key_sequence_display)
+            .collect::<Vec<_>>();
+        let mut value = match labels.as_slice() {
+            [single] => toml_edit::Value::from(single.as_str()),
+            _ => toml_edit::Value::Array(labels.iter().map(String::as_str).collect()),
+        };
+
+        // Bindings outside the action's home context always live in `[keys.<context>]`; home
+        // bindings do too from version 2 on, except for `global` ones.
+        let in_context_table = keys
+            .get(context.as_str())
+            .and_then(|item| item.as_table_like())
+            .is_some_and(|table| table.contains_key(action.as_str()));
+        let scoped = *context != action.home_context();
+        let table =
+            if scoped || in_context_table || (version >= 2 && *context != KeyContext::Global) {
+                keys.entry(context.as_str())
+                    .or_insert_with(toml_edit::table)
+                    .as_table_like_mut()
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "`keys.{}` in the config file is not a table",
+                            context.as_str()
+                        )
+                    })?
+            } else {
+                &mut *keys
+            };
+        if sequences.is_empty() && scoped {
+            table.remove(action.as_str());
+            continue;
+        }
+        match table
+            .get_mut(action.as_str())
+            .and_then(|item| item.as_value_mut())
+        {
+            // Keep the existing value's surrounding whitespace and trailing comment.
+            Some(existing) => {
+                *value.decor_mut() = existing.decor().clone();
+                *existing = value;
+            }
+            None => {
+                table.insert(action.as_str(), toml_edit::Item::Value(value));
+            }
+        }
+    }
+
+    Ok(doc.to_string())
+}
+
+/// Rewrites config text to [`CONFIG_VERSION`] and returns the version it started from. Comments
+/// and formatting are kept; entries that move keep the comments attached to them.
+pub fn migrate_config_toml(contents: &str) -> Result<(u32, String)> {
+    let from = schema_version(contents).context("invalid config TOML")?;
+    if from > CONFIG_VERSION {
+        bail!(
+            "config version {} is newer than this cli-tui-starter supports (up to {})",
+            from,
+            CONFIG_VERSION
+        );
+    }
+    if from == CONFIG_VERSION {
+        return Ok((from, contents.to_string()));
+    }
+
+    let mut doc = contents
+        .parse::<toml_edit::DocumentMut>()
+        .context("invalid config TOML")?;
+    // One step per version, so a file from any older release can be brought forward.
+    let steps: [fn(&mut toml_edit::DocumentMut) -> Result<()>; 1] = [migrate_v1_scoped_keys];
+    for step in &steps[from as usize - 1..] {
+        step(&mut doc)?;
+    }
+    set_schema_version(&mut doc);
+    Ok((from, doc.to_string()))
+}
+
+/// Version 1 -> 2: actions whose home context isn't `global` move from `[keys]` into their
+/// `[keys.<context>]` table.
+fn migrate_v1_scoped_keys(doc: &mut toml_edit::DocumentMut) -> Result<()> {
+    let Some(keys) = doc
+        .get_mut("keys")
+        .and_then(|item| item.as_table_like_mut())
+    else {
+        return Ok(());
+    };
+    for action in KeyAction::ALL {
+        let home = action.home_context();
+        if home == KeyContext::Global {
+            continue;
+        }
+        let Some((decor, value)) = keys
+            .get_key_value(action.as_str())
+            .map(|(key, _)| key.leaf_decor().clone())
+            .and_then(|decor| Some((decor, keys.remove(action.as_str())?)))
+        else {
+            continue;
+        };
+        let context = keys
+            .entry(home.as_str())
+            .or_insert_with(toml_edit::table)
+            .as_table_like_mut()
+            .ok_or_else(|| anyhow!("`keys.{}` in the config file is not a table", home.as_str()))?;
+        if context.contains_key(action.as_str()) {
+            bail!(
+                "key binding '{}' is set in both [keys] and [keys.{}]; remove one before migrating",
+                action.as_str(),
+                home.as_str()
+            );
+        }
+        context.insert(action.as_str(), value);
+        if let Some(mut moved) = context.key_mut(action.as_str()) {
+            *moved.leaf_decor_mut() = decor;
+        }
+    }
+    Ok(())
+}
+
+/// Sets `version`. A new `version` goes at the top of the file, below its leading comment.
+fn set_schema_version(doc: &mut toml_edit::DocumentMut) {
+    let version = toml_edit::Value::from(i64::from(CONFIG_VERSION));
+    if let Some(existing) = doc.get_mut("version").and_then(|item| item.as_value_mut()) {
+        let decor = existing.decor().clone();
+        *existing = version;
+        *existing.decor_mut() = decor;
+        return;
+    }
+
+    let root = doc.as_table_mut();
+    let mut comment = String::new();
+    if let Some((mut key, item)) = root.iter_mut().next() {
+        let decor = match item {
+            toml_edit::Item::Table(table) => table.decor_mut(),
+            _ => key.leaf_decor_mut(),
+        };
+        if let Some(prefix) = decor.prefix().and_then(|prefix| prefix.as_str()) {
+            comment = prefix.trim_end().to_string();
+        }
+        decor.set_prefix("\n");
+    }
+    if !comment.is_empty() {
+        comment.push('\n');
+    }
+    root.insert("version", toml_edit::Item::Value(version));
+    if let Some(mut key) = root.key_mut("version") {
+        key.leaf_decor_mut().set_prefix(comment);
+    }
+}
+
+pub fn env_disables_color_current() -> bool {
     env_disables_color(
         env::var_os("NO_COLOR").as_deref(),
         env::var_os("CLICOLOR").as_deref(),
@@ -349,116 +2126,846 @@ fn env_disables_color(
     clicolor: Option<&OsStr>,
     term: Option<&OsStr>,
 ) -> bool {
+    env_no_color_source(no_color, clicolor, term).is_some()
+}
+
+/// The setting that turns color off: `NO_COLOR` (any value), `TERM=dumb` or `CLICOLOR=0`.
+fn env_no_color_source(
+    no_color: Option<&OsStr>,
+    clicolor: Option<&OsStr>,
+    term: Option<&OsStr>,
+) -> Option<&'static str> {
     if no_color.is_some() {
-        return true;
+        return Some("NO_COLOR");
     }
 
     if matches!(term.and_then(|t| t.to_str()), Some("dumb")) {
-        return true;
+        return Some("TERM=dumb");
+    }
+
+    matches!(clicolor.and_then(|c| c.to_str()), Some("0")).then_some("CLICOLOR=0")
+}
+
+pub fn env_color_depth_current() -> ColorDepth {
+    env_color_depth(
+        env::var_os("COLORTERM").as_deref(),
+        env::var_os("TERM").as_deref(),
+    )
+}
+
+fn env_color_depth(colorterm: Option<&OsStr>, term: Option<&OsStr>) -> ColorDepth {
+    let colorterm = colorterm
+        .and_then(|c| c.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        return ColorDepth::Truecolor;
     }
 
-    matches!(clicolor.and_then(|c| c.to_str()), Some("0"))
+    let term = term
+        .and_then(|t| t.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    if term.contains("truecolor") || term.contains("24bit") || term.contains("direct") {
+        ColorDepth::Truecolor
+    } else if term.contains("256color") {
+        ColorDepth::Ansi256
+    } else {
+        ColorDepth::Ansi16
+    }
+}
+
+/// Reads `COLORFGBG` (`fg;bg` or `fg;default;bg`, set by rxvt, Konsole and others). The last
+/// field is an ANSI color index: 7 and 9-15 are light backgrounds, 0-6 and 8 are dark.
+fn background_from_colorfgbg(value: Option<&OsStr>) -> Option<Background> {
+    let value = value?.to_str()?;
+    let bg = value.rsplit(';').next()?.trim().parse::<u8>().ok()?;
+    match bg {
+        0..=6 | 8 => Some(Background::Dark),
+        7 | 9..=15 => Some(Background::Light),
+        _ => None,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::keys::key_labels;
+
+    fn default_args() -> SettingsArgs {
+        SettingsArgs::default()
+    }
+
+    /// A single config file, as when it is the only layer.
+    fn parse_config_bundle(
+        contents: &str,
+        source: &Path,
+        dir_themes: Vec<ThemeEntry>,
+    ) -> Result<LoadedConfigBundle> {
+        merge_layers(
+            &[(
+                LayerSource::File(source.to_path_buf()),
+                contents.to_string(),
+            )],
+            dir_themes,
+        )
+    }
+
+    /// Merges `layers`, failing with every diagnostic found.
+    fn merge_layers(
+        layers: &[(LayerSource, String)],
+        dir_themes: Vec<ThemeEntry>,
+    ) -> Result<LoadedConfigBundle> {
+        let mut diagnostics = Vec::new();
+        let bundle = merge_config_layers(layers, dir_themes, &mut diagnostics);
+        ConfigErrors::check(diagnostics)?;
+        Ok(bundle)
+    }
+
+    /// Everything wrong with a single config file.
+    fn diagnose(contents: &str) -> Vec<Diagnostic> {
+        let layers = [(file("/tmp/config.toml"), contents.to_string())];
+        let mut diagnostics = Vec::new();
+        merge_config_layers(&layers, Vec::new(), &mut diagnostics);
+        diagnostics
+    }
+
+    fn file(path: impl Into<PathBuf>) -> LayerSource {
+        LayerSource::File(path.into())
+    }
+
+    /// `themes/<name>.toml` with `contents`.
+    fn dir_theme(name: &str, contents: &str) -> ThemeEntry {
+        let path = PathBuf::from(format!("/tmp/themes/{}.toml", name));
+        let mut diagnostics = Vec::new();
+        let entry = parse_theme_file(name, &path, contents, &mut diagnostics).expect("theme file");
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        entry
+    }
+
+    fn env_defaults(no_color: bool) -> EnvDefaults {
+        EnvDefaults {
+            no_color: no_color.then_some("NO_COLOR"),
+            color_depth: ColorDepth::Truecolor,
+            background: None,
+        }
+    }
+
+    #[test]
+    fn parse_config_bundle_reads_demo_section() {
+        let path = Path::new("/tmp/config.toml");
+        let parsed = parse_config_bundle(
+            r#"
+            [demo]
+            theme = "solar"
+            no_color = true
+            high_contrast = false
+            reduced_motion = true
+            ascii = true
+            mouse = true
+            "#,
+            path,
+            Vec::new(),
+        )
+        .expect("config should parse");
+
+        assert_eq!(parsed.demo.theme.as_deref(), Some("solar"));
+        assert_eq!(parsed.demo.no_color, Some(true));
+        assert_eq!(parsed.demo.high_contrast, Some(false));
+        assert_eq!(parsed.demo.reduced_motion, Some(true));
+        assert_eq!(parsed.demo.ascii, Some(true));
+        assert_eq!(parsed.demo.mouse, Some(true));
+    }
+
+    #[test]
+    fn parse_config_bundle_rejects_unknown_theme() {
+        let path = Path::new("/tmp/config.toml");
+        let error = parse_config_bundle(
+            r#"
+            [demo]
+            theme = "neon"
+            "#,
+            path,
+            Vec::new(),
+        )
+        .expect_err("invalid theme must fail");
+
+        assert!(error
+            .to_string()
+            .contains("valid themes: auto, aurora, mono, solar"));
+    }
+
+    #[test]
+    fn parse_config_bundle_reads_custom_themes() {
+        let path = Path::new("/tmp/config.toml");
+        let parsed = parse_config_bundle(
+            r#"
+            [demo]
+            theme = "Brand"
+
+            [themes.brand]
+            description = "Brand palette"
+            fg = "white"
+            bg = "black"
+            accent = "lightmagenta"
+            muted = "gray"
+            success = "lightgreen"
+            danger = "lightred"
+            "#,
+            path,
+            Vec::new(),
+        )
+        .expect("config should parse");
+
+        assert_eq!(parsed.demo.theme.as_deref(), Some("brand"));
+        let brand = find_theme(&parsed.themes, "brand").expect("brand theme");
+        assert_eq!(brand.description, "Brand palette");
+        assert_eq!(brand.palette.accent, Color::LightMagenta);
+        assert_eq!(parsed.themes.len(), themes().len() + 1);
+    }
+
+    #[test]
+    fn parse_config_bundle_reads_theme_style_overrides() {
+        let path = Path::new("/tmp/config.toml");
+        let theme_toml = |styles: &str| {
+            format!(
+                "[themes.brand]\nfg = \"white\"\nbg = \"black\"\naccent = \"cyan\"\n\
+                 muted = \"gray\"\nsuccess = \"green\"\ndanger = \"lightred\"\n{}",
+                styles
+            )
+        };
+
+        let parsed = parse_config_bundle(
+            &theme_toml("[themes.brand.styles.border]\nfg = \"#ff00ff\"\nitalic = true\n"),
+            path,
+            Vec::new(),
+        )
+        .expect("config should parse");
+        let brand = find_theme(&parsed.themes, "brand").expect("brand theme");
+        assert_eq!(
+            brand.style_overrides.get(&StyleSlot::Border),
+            Some(&StyleOverride {
+                fg: Some(Color::Rgb(255, 0, 255)),
+                italic: Some(true),
+                ..StyleOverride::default()
+            })
+        );
+
+        let error = parse_config_bundle(
+            &theme_toml("[themes.brand.styles.borders]\nfg = \"red\"\n"),
+            path,
+            Vec::new(),
+        )
+        .expect_err("unknown slot must fail");
+        assert!(error.to_string().contains("unknown style slot 'borders'"));
+
+        let error = parse_config_bundle(
+            &theme_toml("[themes.brand.styles.title]\nbg = \"neon\"\n"),
+            path,
+            Vec::new(),
+        )
+        .expect_err("invalid slot color must fail");
+        assert!(format!("{:#}", error).contains("styles.title.bg"));
+    }
+
+    #[test]
+    fn parse_config_bundle_rejects_invalid_custom_themes() {
+        let path = Path::new("/tmp/config.toml");
+        let bad_color = parse_config_bundle(
+            r#"
+            [themes.brand]
+            fg = "white"
+            bg = "black"
+            accent = "neon"
+            muted = "gray"
+            success = "green"
+            danger = "red"
+            "#,
+            path,
+            Vec::new(),
+        )
+        .expect_err("invalid color must fail");
+        let msg = format!("{:#}", bad_color);
+        assert!(
+            msg.contains("in `themes.brand.accent`\n --> /tmp/config.toml:5:22"),
+            "msg was: {}",
+            msg
+        );
+
+        let shadowed = parse_config_bundle(
+            r#"
+            [themes.solar]
+            fg = "white"
+            bg = "black"
+            accent = "yellow"
+            muted = "gray"
+            success = "green"
+            danger = "red"
+            "#,
+            path,
+            Vec::new(),
+        )
+        .expect_err("built-in names are reserved");
+        let msg = format!("{:#}", shadowed);
+        assert!(
+            msg.contains("reserved for a built-in theme"),
+            "msg was: {}",
+            msg
+        );
+    }
+
+    #[test]
+    fn parse_config_bundle_accepts_themes_from_directory() {
+        let path = Path::new("/tmp/config.toml");
+        let dir_theme = dir_theme("ocean", "extends = \"aurora\"\n");
+        let parsed = parse_config_bundle(
+            r#"
+            [demo]
+            theme = "ocean"
+            "#,
+            path,
+            vec![dir_theme.clone()],
+        )
+        .expect("config should parse");
+        assert_eq!(parsed.demo.theme.as_deref(), Some("ocean"));
+
+        let error = parse_config_bundle(
+            r#"
+            [themes.ocean]
+            fg = "white"
+            bg = "black"
+            accent = "cyan"
+            muted = "gray"
+            success = "green"
+            danger = "red"
+            "#,
+            path,
+            vec![dir_theme],
+        )
+        .expect_err("duplicate theme names must fail");
+        assert!(error.to_string().contains("already defined"));
+    }
+
+    #[test]
+    fn parse_config_bundle_resolves_theme_inheritance() {
+        let path = Path::new("/tmp/config.toml");
+        let dir_theme = dir_theme("dusk", "extends = \"brand\"\nmuted = \"darkgray\"\n");
+        let parsed = parse_config_bundle(
+            r##"
+            # `brand` extends a theme defined after it; order doesn't matter.
+            [themes.brand]
+            extends = "warm"
+            accent = "lightmagenta"
+
+            [themes.warm]
+            extends = "solar"
+            description = "Warmer solar"
+            danger = "#ff5f5f"
+
+            [themes.warm.styles.title]
+            italic = true
+            "##,
+            path,
+            vec![dir_theme],
+        )
+        .expect("config should parse");
+
+        let solar = find_theme(&parsed.themes, "solar").expect("solar").clone();
+        let warm = find_theme(&parsed.themes, "warm").expect("warm");
+        assert_eq!(warm.description, "Warmer solar");
+        assert_eq!(warm.palette.fg, solar.palette.fg);
+        assert_eq!(warm.palette.danger, Color::Rgb(0xff, 0x5f, 0x5f));
+
+        let brand = find_theme(&parsed.themes, "brand").expect("brand");
+        assert_eq!(brand.description, "Based on warm");
+        assert_eq!(brand.palette.accent, Color::LightMagenta);
+        assert_eq!(brand.palette.danger, Color::Rgb(0xff, 0x5f, 0x5f));
+        assert_eq!(brand.style_overrides[&StyleSlot::Title].italic, Some(true));
+
+        let dusk = find_theme(&parsed.themes, "dusk").expect("dusk");
+        assert_eq!(dusk.palette.accent, Color::LightMagenta);
+        assert_eq!(dusk.palette.muted, Color::DarkGray);
+    }
+
+    #[test]
+    fn parse_config_bundle_rejects_bad_theme_inheritance() {
+        let path = Path::new("/tmp/config.toml");
+        let error = parse_config_bundle("[themes.brand]\nextends = \"neon\"\n", path, Vec::new())
+            .expect_err("unknown parent must fail");
+        let message = error.to_string();
+        assert!(message
+            .contains("theme 'brand' extends unknown theme 'neon'\n --> /tmp/config.toml:2:11"));
+        assert!(message.contains("valid themes: aurora"));
+
+        let error = parse_config_bundle(
+            r#"
+            [themes.a]
+            extends = "b"
+            [themes.b]
+            extends = "c"
+            [themes.c]
+            extends = "a"
+            "#,
+            path,
+            Vec::new(),
+        )
+        .expect_err("cycles must fail");
+        assert!(error
+            .to_string()
+            .contains("theme inheritance cycle: a -> b -> c -> a\n --> /tmp/config.toml:3:23"));
+
+        let error = parse_config_bundle("[themes.brand]\nfg = \"white\"\n", path, Vec::new())
+            .expect_err("missing slots without extends must fail");
+        assert!(error
+            .to_string()
+            .contains("theme 'brand' is missing 'bg', 'accent', 'muted', 'success', 'danger'"));
+    }
+
+    #[test]
+    fn schema_and_merge_problems_are_reported_together_with_their_line_and_column() {
+        let diagnostics = diagnose(
+            "[demo]\ntheme = \"solr\"\nmose = true\n\n[keys]\nquit = [\"q\", \"ctl+x\"]\n\n[dmo]\nx = 1\n",
+        );
+        let summary = diagnostics
+            .iter()
+            .map(|d| {
+                let span = d.span.as_ref().expect("located");
+                (
+                    d.message.as_str(),
+                    span.line,
+                    span.column,
+                    d.suggestion.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                ("unknown key 'mose' in [demo]", 3, 1, Some("mouse")),
+                ("unknown key 'dmo'", 8, 2, Some("demo")),
+                ("unknown theme 'solr'", 2, 9, Some("solar")),
+                (
+                    "invalid key spec 'ctl+x' for `keys.quit`: unsupported modifier 'ctl'",
+                    6,
+                    14,
+                    Some("ctrl+x")
+                ),
+            ]
+        );
+        assert!(diagnostics[0]
+            .help
+            .as_deref()
+            .is_some_and(|help| help.starts_with("valid keys: theme, no_color, ")));
+        assert_eq!(
+            diagnostics[2].to_string(),
+            "error: unknown theme 'solr'\n \
+             --> /tmp/config.toml:2:9\n  \
+             |\n\
+             2 | theme = \"solr\"\n  \
+             |         ^^^^^^\n  \
+             = help: did you mean 'solar'?\n"
+        );
+    }
+
+    #[test]
+    fn syntax_errors_skip_the_layer_and_themes_come_from_every_layer() {
+        let diagnostics = diagnose("[demo\ntheme = 1\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.starts_with("invalid TOML"));
+        assert_eq!(diagnostics[0].span.as_ref().map(|s| s.line), Some(1));
+
+        let layers = [
+            (
+                file("/etc/config.toml"),
+                "[themes.brand]\nextends = \"mono\"\nacent = \"red\"\n".to_string(),
+            ),
+            (
+                file("/tmp/config.toml"),
+                "[demo]\ntheme = \"brand\"\n[demo.theme_overrides.dusk]\naccent = \"red\"\n"
+                    .to_string(),
+            ),
+        ];
+        let dusk = dir_theme("dusk", "extends = \"mono\"\n");
+        let mut diagnostics = Vec::new();
+        merge_config_layers(&layers, vec![dusk], &mut diagnostics);
+        let messages = diagnostics
+            .iter()
+            .map(|d| d.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["unknown key 'acent' in [themes.brand]"]);
+    }
+
+    #[test]
+    fn merge_problems_are_all_reported_where_they_were_set() {
+        let diagnostics = diagnose(
+            "[themes.x]\nfg = \"white\"\n\n[keys]\ncycle_theme = \"g\"\ntoggle_help = \"g g\"\nquit = [\"q\", \"ctl+x\"]\n",
+        );
+        let summary = diagnostics
+            .iter()
+            .map(|d| {
+                let span = d.span.as_ref().expect("located");
+                (d.message.as_str(), span.line, span.column)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                (
+                    "theme 'x' is missing 'bg', 'accent', 'muted', 'success', 'danger'",
+                    1,
+                    9
+                ),
+                (
+                    "invalid key spec 'ctl+x' for `keys.quit`: unsupported modifier 'ctl'",
+                    7,
+                    14
+                ),
+                (
+                    "key binding 'g' for 'cycle_theme' is a prefix of 'g g' for 'toggle_help'",
+                    5,
+                    15
+                ),
+            ]
+        );
+        assert_eq!(diagnostics[1].suggestion.as_deref(), Some("ctrl+x"));
+    }
+
+    #[test]
+    fn parse_config_bundle_applies_demo_theme_overrides() {
+        let path = Path::new("/tmp/config.toml");
+        let parsed = parse_config_bundle(
+            r#"
+            [demo.theme_overrides.solar]
+            accent = "208"
+
+            [demo.theme_overrides.solar.styles.border]
+            fg = "yellow"
+            "#,
+            path,
+            Vec::new(),
+        )
+        .expect("config should parse");
+        let solar = find_theme(&parsed.themes, "solar").expect("solar");
+        let builtin = find_theme(&themes(), "solar").expect("solar").clone();
+        assert!(solar.is_builtin());
+        assert_eq!(solar.palette.accent, Color::Indexed(208));
+        assert_eq!(solar.palette.fg, builtin.palette.fg);
+        assert_eq!(
+            solar.style_overrides[&StyleSlot::Border].fg,
+            Some(Color::Yellow)
+        );
 
-    fn default_args() -> DemoArgs {
-        DemoArgs {
-            theme: None,
-            no_tty: false,
-            width: None,
-            height: None,
-            ascii: false,
-            no_color: false,
-            color: false,
-            high_contrast: false,
-            normal_contrast: false,
-            reduced_motion: false,
-            motion: false,
-            mouse: false,
-            no_mouse: false,
-            config: None,
-        }
+        let error = parse_config_bundle(
+            "[demo.theme_overrides.neon]\naccent = \"red\"\n",
+            path,
+            Vec::new(),
+        )
+        .expect_err("unknown theme must fail");
+        assert!(error
+            .to_string()
+            .contains("unknown theme 'neon'\n --> /tmp/config.toml:1:23"));
     }
 
     #[test]
-    fn parse_config_bundle_reads_demo_section() {
+    fn parse_config_bundle_reads_keys_section() {
         let path = Path::new("/tmp/config.toml");
         let parsed = parse_config_bundle(
             r#"
-            [demo]
-            theme = "solar"
-            no_color = true
-            high_contrast = false
-            reduced_motion = true
-            ascii = true
-            mouse = true
+            [keys]
+            cycle_theme = "n"
+            toggle_help = ["?", "g h"]
+            quit = "x"
+            sequence_timeout_ms = 400
             "#,
             path,
+            Vec::new(),
         )
         .expect("config should parse");
 
-        assert_eq!(parsed.demo.theme, Some(ThemeName::Solar));
-        assert_eq!(parsed.demo.no_color, Some(true));
-        assert_eq!(parsed.demo.high_contrast, Some(false));
-        assert_eq!(parsed.demo.reduced_motion, Some(true));
-        assert_eq!(parsed.demo.ascii, Some(true));
-        assert_eq!(parsed.demo.mouse, Some(true));
+        assert_eq!(
+            crate::keys::key_labels(&parsed.keys.bindings.cycle_theme),
+            ["n"]
+        );
+        assert_eq!(
+            crate::keys::key_labels(&parsed.keys.bindings.toggle_help),
+            ["?", "g h"]
+        );
+        assert_eq!(crate::keys::key_labels(&parsed.keys.bindings.quit), ["x"]);
+        assert_eq!(
+            parsed.keys.bindings.sequence_timeout,
+            Duration::from_millis(400)
+        );
     }
 
     #[test]
-    fn parse_config_bundle_rejects_unknown_theme() {
+    fn keys_start_from_preset_with_entries_on_top() {
         let path = Path::new("/tmp/config.toml");
-        let error = parse_config_bundle(
+        let parsed = parse_config_bundle(
             r#"
-            [demo]
-            theme = "neon"
+            [keys]
+            preset = "vim"
+            cycle_theme = "n"
             "#,
             path,
+            Vec::new(),
         )
-        .expect_err("invalid theme must fail");
+        .expect("config should parse");
 
-        assert!(error
-            .to_string()
-            .contains("valid themes: aurora, mono, solar"));
+        assert_eq!(parsed.keys.preset, KeymapPreset::Vim);
+        assert_eq!(parsed.keys.from_config, [KeyAction::CycleTheme]);
+        let keys = &parsed.keys.bindings;
+        assert_eq!(crate::keys::key_labels(&keys.cycle_theme), ["n"]);
+        assert_eq!(crate::keys::key_labels(&keys.list_down), ["j", "down"]);
+
+        // A CLI preset replaces the config preset but keeps the entries.
+        let emacs = parsed
+            .keys_with_preset(Some(KeymapPreset::Emacs))
+            .expect("emacs preset");
+        assert_eq!(emacs.preset, KeymapPreset::Emacs);
+        assert_eq!(crate::keys::key_labels(&emacs.bindings.cycle_theme), ["n"]);
+        assert_eq!(
+            crate::keys::key_labels(&emacs.bindings.list_down),
+            ["ctrl+n", "down"]
+        );
+
+        let diagnostics = diagnose("[keys]\npreset = \"helix\"\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "invalid keymap preset 'helix'");
+        assert_eq!(diagnostics[0].span.as_ref().map(|s| s.line), Some(2));
+        assert_eq!(
+            diagnostics[0].help.as_deref(),
+            Some("valid values: default, vim, emacs, arrows-only")
+        );
     }
 
     #[test]
-    fn parse_config_bundle_reads_keys_section() {
+    fn keys_record_the_config_line_of_each_entry() {
+        let path = Path::new("/tmp/config.toml");
+        let parsed = parse_config_bundle(
+            "# keys\n[keys]\npreset = \"vim\"\nquit = [\n  \"x\",\n]\n\n[keys.list]\nlist_down = \"n\"\ncycle_theme = \"m\"\n",
+            path,
+            Vec::new(),
+        )
+        .expect("config should parse");
+
+        let line = |context, action| {
+            parsed
+                .keys
+                .entry(context, action)
+                .map(|entry| (entry.table.as_str(), entry.location.line))
+        };
+        assert_eq!(
+            parsed.keys.preset_location.as_ref().map(|l| l.line),
+            Some(3)
+        );
+        assert_eq!(line(KeyContext::Global, KeyAction::Quit), Some(("keys", 4)));
+        assert_eq!(
+            line(KeyContext::List, KeyAction::ListDown),
+            Some(("keys.list", 9))
+        );
+        assert_eq!(
+            line(KeyContext::List, KeyAction::CycleTheme),
+            Some(("keys.list", 10))
+        );
+        assert_eq!(line(KeyContext::Global, KeyAction::CycleTheme), None);
+
+        // A CLI preset overrides `[keys] preset`, so its line no longer explains anything.
+        let emacs = parsed
+            .keys_with_preset(Some(KeymapPreset::Emacs))
+            .expect("emacs preset");
+        assert!(emacs.preset_location.is_none());
+        assert_eq!(emacs.entries, parsed.keys.entries);
+    }
+
+    #[test]
+    fn later_layers_override_earlier_ones_field_by_field() {
+        let user = file("/home/me/.config/cli-tui-starter/config.toml");
+        let project = file("/work/app/.cli-tui-starter.toml");
+        let layers = [
+            (
+                user.clone(),
+                "[demo]\ntheme = \"brand\"\nmouse = true\n\n[keys]\npreset = \"vim\"\nquit = \"x\"\ncycle_theme = \"n\"\n\n[themes.brand]\nextends = \"aurora\"\naccent = \"red\"\nmuted = \"gray\"\n"
+                    .to_string(),
+            ),
+            (
+                project.clone(),
+                "[demo]\nmouse = false\n\n[keys.global]\ncycle_theme = \"m\"\n\n[themes.brand]\naccent = \"blue\"\n"
+                    .to_string(),
+            ),
+        ];
+        let merged = merge_layers(&layers, Vec::new()).expect("layers should merge");
+
+        assert_eq!(merged.demo.theme.as_deref(), Some("brand"));
+        assert_eq!(merged.demo.mouse, Some(false));
+        let brand = find_theme(&merged.themes, "brand").expect("brand theme");
+        assert_eq!(brand.palette.accent, Color::Blue);
+        assert_eq!(brand.palette.muted, Color::Gray);
+
+        let keys = &merged.keys;
+        assert_eq!(keys.preset, KeymapPreset::Vim);
+        assert_eq!(
+            keys.preset_location.as_ref().map(|l| &l.source),
+            Some(&user)
+        );
+        assert_eq!(key_labels(&keys.bindings.quit), ["x"]);
+        assert_eq!(key_labels(&keys.bindings.cycle_theme), ["m"]);
+        let cycle = keys
+            .entry(KeyContext::Global, KeyAction::CycleTheme)
+            .expect("cycle_theme entry");
+        assert_eq!(cycle.table, "keys.global");
+        assert_eq!((&cycle.location.source, cycle.location.line), (&project, 5));
+        assert_eq!(
+            cycle.location.span.as_ref().map(|span| span.column),
+            Some(15)
+        );
+        assert_eq!(keys.from_config, [KeyAction::CycleTheme, KeyAction::Quit]);
+    }
+
+    #[test]
+    fn env_overrides_map_onto_config_keys_and_name_the_variable() {
+        let vars = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(name, value)| (OsString::from(name), OsString::from(value)))
+                .collect::<Vec<_>>()
+        };
+        let mut layers = vec![(
+            file("/tmp/config.toml"),
+            "[demo]\ntheme = \"solar\"\nmouse = true\n\n[keys]\nquit = \"x\"\n".to_string(),
+        )];
+        let mut diagnostics = Vec::new();
+        layers.extend(env_layers(
+            vars(&[
+                ("CLI_TUI_STARTER_DEMO__THEME", "mono"),
+                ("CLI_TUI_STARTER_DEMO__MOUSE", "off"),
+                ("CLI_TUI_STARTER_KEYS__QUIT", "[\"z\", \"ctrl+q\"]"),
+                ("CLI_TUI_STARTER_KEYS__LIST__LIST_DOWN", "n"),
+                ("CLI_TUI_STARTER_KEYS__SEQUENCE_TIMEOUT_MS", "5000000000"),
+                ("HOME", "/home/me"),
+            ]),
+            &mut diagnostics,
+        ));
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(layers.len(), 6);
+        let merged = merge_layers(&layers, Vec::new()).expect("layers should merge");
+        assert_eq!(merged.demo.theme.as_deref(), Some("mono"));
+        assert_eq!(merged.demo.mouse, Some(false));
+        let keys = &merged.keys;
+        assert_eq!(key_labels(&keys.bindings.quit), ["z", "ctrl+q"]);
+        assert_eq!(key_labels(&keys.bindings.list_down), ["n"]);
+        assert_eq!(
+            keys.bindings.sequence_timeout,
+            Duration::from_millis(5_000_000_000)
+        );
+        assert_eq!(
+            keys.entry(KeyContext::List, KeyAction::ListDown)
+                .map(|entry| entry.location.source.clone()),
+            Some(LayerSource::Env(
+                "CLI_TUI_STARTER_KEYS__LIST__LIST_DOWN".to_string()
+            ))
+        );
+
+        let error = |name: &str, value: &str| {
+            let mut diagnostics = Vec::new();
+            let layers = env_layers(vars(&[(name, value)]), &mut diagnostics);
+            merge_config_layers(&layers, Vec::new(), &mut diagnostics);
+            ConfigErrors(diagnostics).to_string()
+        };
+        assert_eq!(
+            error("CLI_TUI_STARTER_DEMO__MOUSE", "maybe"),
+            "error: invalid value for CLI_TUI_STARTER_DEMO__MOUSE: expected true or false, got \
+             'maybe'\n --> CLI_TUI_STARTER_DEMO__MOUSE\n"
+        );
+        assert!(error("CLI_TUI_STARTER_KEYS__SEQUENCE_TIMEOUT_MS", "-1")
+            .contains("expected a number of milliseconds, got '-1'"));
+        assert!(error("CLI_TUI_STARTER_DEMO__THEME", "neon")
+            .contains("unknown theme 'neon'\n --> CLI_TUI_STARTER_DEMO__THEME\n"));
+        assert!(error("CLI_TUI_STARTER_KEYS__QUIT", "ctrl+").contains("for `keys.quit`: "));
+        assert!(error("CLI_TUI_STARTER_DEMO__ZOOM", "2")
+            .contains("unknown key 'zoom' in [demo]\n --> CLI_TUI_STARTER_DEMO__ZOOM\n"));
+        assert!(env_layers(
+            vars(&[
+                ("CLI_TUI_STARTER_LOG_LEVEL", "1"),
+                ("CLI_TUI_STARTER_DEMO__", "x"),
+            ]),
+            &mut diagnostics,
+        )
+        .is_empty());
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn project_config_is_found_in_the_nearest_ancestor() {
+        let root = temp_config_dir("project");
+        let nested = root.join("src").join("deep");
+        fs::create_dir_all(&nested).expect("create nested dir");
+        assert_eq!(find_project_config(&nested), None);
+
+        fs::write(root.join(PROJECT_CONFIG_FILE), "").expect("write project config");
+        assert_eq!(
+            find_project_config(&nested),
+            Some(root.join(PROJECT_CONFIG_FILE))
+        );
+        fs::write(nested.join(PROJECT_CONFIG_FILE), "").expect("write nested config");
+        assert_eq!(
+            find_project_config(&nested),
+            Some(nested.join(PROJECT_CONFIG_FILE))
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn context_tables_allow_reuse_between_sibling_contexts() {
         let path = Path::new("/tmp/config.toml");
         let parsed = parse_config_bundle(
             r#"
-            [keys]
-            cycle_theme = "n"
-            toggle_help = ["?", "g"]
-            quit = "x"
+            [keys.list]
+            list_down = "j"
+
+            [keys.overview]
+            cycle_theme = "j"
             "#,
             path,
+            Vec::new(),
         )
-        .expect("config should parse");
+        .expect("siblings may share keys");
+        let keys = &parsed.keys.bindings;
+        assert_eq!(crate::keys::key_labels(&keys.list_down), ["j"]);
+        assert_eq!(parsed.keys.from_config, [KeyAction::ListDown]);
+        assert_eq!(
+            keys.scoped,
+            [ScopedBinding {
+                context: KeyContext::Overview,
+                action: KeyAction::CycleTheme,
+                keys: vec![parse_key_sequence("j").unwrap()],
+            }]
+        );
 
-        assert!(parsed
-            .keys
-            .cycle_theme
-            .iter()
-            .any(|k| k.code == crossterm::event::KeyCode::Char('n')));
-        assert!(parsed
-            .keys
-            .toggle_help
-            .iter()
-            .any(|k| k.code == crossterm::event::KeyCode::Char('?')));
-        assert!(parsed
-            .keys
-            .toggle_help
-            .iter()
-            .any(|k| k.code == crossterm::event::KeyCode::Char('g')));
-        assert!(parsed
-            .keys
-            .quit
-            .iter()
-            .any(|k| k.code == crossterm::event::KeyCode::Char('x')));
+        let diagnostics =
+            diagnose("[keys.list]\nlist_down = \"j\"\n[keys.global]\ntoggle_color = \"j\"\n");
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert_eq!(
+            diagnostics[0].message,
+            "key binding 'j' for 'list_down' conflicts with 'toggle_color'"
+        );
+        assert_eq!(diagnostics[0].span.as_ref().map(|s| s.line), Some(2));
+
+        let error = parse_config_bundle(
+            "[keys]\nlist_up = \"k\"\n[keys.list]\nlist_up = \"i\"\n",
+            path,
+            Vec::new(),
+        )
+        .expect_err("the same slot twice must fail");
+        assert!(error
+            .to_string()
+            .contains("key binding 'list_up' is set in both [keys] and [keys.list]"));
+
+        let diagnostics = diagnose("[keys.help]\ntoggle_hlp = \"z\"\n");
+        assert_eq!(
+            diagnostics[0].message,
+            "unknown action 'toggle_hlp' in [keys.help]"
+        );
+        assert_eq!(diagnostics[0].suggestion.as_deref(), Some("toggle_help"));
     }
 
     #[test]
@@ -471,6 +2978,7 @@ mod tests {
             toggle_color = "t"
             "#,
             path,
+            Vec::new(),
         )
         .expect_err("duplicates must fail");
 
@@ -481,31 +2989,36 @@ mod tests {
     #[test]
     fn resolve_prefers_cli_over_config_and_env() {
         let mut args = default_args();
-        args.theme = Some(ThemeName::Mono);
+        args.theme = Some("mono".to_string());
         args.color = true;
         args.normal_contrast = true;
         args.motion = true;
         args.no_mouse = true;
 
         let defaults = DemoDefaults {
-            theme: Some(ThemeName::Solar),
+            theme: Some("solar".to_string()),
             no_color: Some(true),
             high_contrast: Some(true),
             reduced_motion: Some(true),
             ascii: Some(false),
             mouse: Some(true),
+            color_depth: None,
+            background: None,
+            origins: BTreeMap::new(),
         };
 
-        let resolved = resolve_with_sources(&args, &defaults, true);
+        let (resolved, _) = resolve_with_sources(&args, &defaults, env_defaults(true));
         assert_eq!(
             resolved,
             DemoSettings {
-                theme: ThemeName::Mono,
+                theme: "mono".to_string(),
                 no_color: false,
                 high_contrast: false,
                 reduced_motion: false,
                 ascii: false,
                 mouse: false,
+                color_depth: ColorDepth::Truecolor,
+                background: None,
             }
         );
     }
@@ -522,9 +3035,12 @@ mod tests {
             reduced_motion: None,
             ascii: None,
             mouse: Some(false),
+            color_depth: None,
+            background: None,
+            origins: BTreeMap::new(),
         };
 
-        let resolved = resolve_with_sources(&args, &defaults, false);
+        let (resolved, _) = resolve_with_sources(&args, &defaults, env_defaults(false));
         assert!(resolved.mouse);
     }
 
@@ -532,22 +3048,344 @@ mod tests {
     fn resolve_prefers_config_over_env_default() {
         let args = default_args();
         let defaults = DemoDefaults {
-            theme: Some(ThemeName::Solar),
+            theme: Some("solar".to_string()),
             no_color: Some(false),
             high_contrast: Some(true),
             reduced_motion: Some(true),
             ascii: Some(false),
             mouse: Some(true),
+            color_depth: None,
+            background: None,
+            origins: BTreeMap::new(),
         };
 
-        let resolved = resolve_with_sources(&args, &defaults, true);
-        assert_eq!(resolved.theme, ThemeName::Solar);
+        let (resolved, _) = resolve_with_sources(&args, &defaults, env_defaults(true));
+        assert_eq!(resolved.theme, "solar");
         assert!(!resolved.no_color);
         assert!(resolved.high_contrast);
         assert!(resolved.reduced_motion);
         assert!(resolved.mouse);
     }
 
+    #[test]
+    fn resolve_records_where_each_setting_came_from() {
+        let path = Path::new("/tmp/config.toml");
+        let parsed = parse_config_bundle(
+            "[demo]\ntheme = \"solar\"\nmouse = true\ncolor_depth = \"256\"\n",
+            path,
+            Vec::new(),
+        )
+        .expect("config should parse");
+        let mut args = default_args();
+        args.no_mouse = true;
+
+        let (_, origins) = resolve_with_sources(&args, &parsed.demo, env_defaults(true));
+        let origin = |key| origins.get(key).map(ToString::to_string);
+        assert_eq!(origin("theme").as_deref(), Some("/tmp/config.toml:2"));
+        assert_eq!(origin("mouse").as_deref(), Some("--no-mouse"));
+        assert_eq!(origin("color_depth").as_deref(), Some("/tmp/config.toml:4"));
+        assert_eq!(origin("no_color").as_deref(), Some("env NO_COLOR"));
+        assert_eq!(origin("high_contrast").as_deref(), Some("default"));
+        assert_eq!(origin("background"), None);
+    }
+
+    #[test]
+    fn resolve_color_depth_prefers_cli_then_config_then_env() {
+        let mut args = default_args();
+        let mut defaults = DemoDefaults {
+            color_depth: Some(ColorDepthArg::Ansi256),
+            ..DemoDefaults::default()
+        };
+        let env = EnvDefaults {
+            no_color: None,
+            color_depth: ColorDepth::Ansi16,
+            background: None,
+        };
+
+        assert_eq!(
+            resolve_with_sources(&args, &defaults, env).0.color_depth,
+            ColorDepth::Ansi256
+        );
+
+        args.color_depth = Some(ColorDepthArg::Truecolor);
+        assert_eq!(
+            resolve_with_sources(&args, &defaults, env).0.color_depth,
+            ColorDepth::Truecolor
+        );
+
+        args.color_depth = Some(ColorDepthArg::Auto);
+        assert_eq!(
+            resolve_with_sources(&args, &defaults, env).0.color_depth,
+            ColorDepth::Ansi16
+        );
+
+        args.color_depth = None;
+        defaults.color_depth = None;
+        assert_eq!(
+            resolve_with_sources(&args, &defaults, env).0.color_depth,
+            ColorDepth::Ansi16
+        );
+    }
+
+    #[test]
+    fn parse_config_bundle_rejects_unknown_color_depth() {
+        let path = Path::new("/tmp/config.toml");
+        let error = parse_config_bundle(
+            r#"
+            [demo]
+            color_depth = "8"
+            "#,
+            path,
+            Vec::new(),
+        )
+        .expect_err("invalid color depth must fail");
+
+        assert!(error
+            .to_string()
+            .contains("valid values: auto, truecolor, 256, 16"));
+    }
+
+    #[test]
+    fn parse_config_bundle_reads_auto_theme_and_background() {
+        let path = Path::new("/tmp/config.toml");
+        let parsed = parse_config_bundle(
+            r#"
+            [demo]
+            theme = "Auto"
+            background = "light"
+            "#,
+            path,
+            Vec::new(),
+        )
+        .expect("config should parse");
+        assert_eq!(parsed.demo.theme.as_deref(), Some("auto"));
+        assert_eq!(parsed.demo.background, Some(Background::Light));
+
+        let error = parse_config_bundle("[demo]\nbackground = \"sepia\"\n", path, Vec::new())
+            .expect_err("invalid background must fail");
+        assert!(error.to_string().contains("valid values: light, dark"));
+    }
+
+    #[test]
+    fn resolve_background_prefers_cli_over_config() {
+        let mut args = default_args();
+        let defaults = DemoDefaults {
+            background: Some(Background::Light),
+            ..DemoDefaults::default()
+        };
+        assert_eq!(
+            resolve_with_sources(&args, &defaults, env_defaults(false))
+                .0
+                .background,
+            Some(Background::Light)
+        );
+
+        args.background = Some(Background::Dark);
+        assert_eq!(
+            resolve_with_sources(&args, &defaults, env_defaults(false))
+                .0
+                .background,
+            Some(Background::Dark)
+        );
+    }
+
+    #[test]
+    fn background_from_colorfgbg_cases() {
+        assert_eq!(
+            background_from_colorfgbg(Some(OsStr::new("15;0"))),
+            Some(Background::Dark)
+        );
+        assert_eq!(
+            background_from_colorfgbg(Some(OsStr::new("0;15"))),
+            Some(Background::Light)
+        );
+        assert_eq!(
+            background_from_colorfgbg(Some(OsStr::new("0;default;7"))),
+            Some(Background::Light)
+        );
+        assert_eq!(
+            background_from_colorfgbg(Some(OsStr::new("7;8"))),
+            Some(Background::Dark)
+        );
+        assert_eq!(
+            background_from_colorfgbg(Some(OsStr::new("0;default"))),
+            None
+        );
+        assert_eq!(background_from_colorfgbg(None), None);
+    }
+
+    fn temp_config_dir(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let dir = env::temp_dir().join(format!(
+            "cli-tui-starter-{}-{}-{}",
+            label,
+            std::process::id(),
+            nanos
+        ));
+        fs::create_dir_all(dir.join("themes")).expect("create temp dir");
+        dir
+    }
+
+    #[test]
+    fn config_watcher_detects_config_and_theme_file_changes() {
+        let dir = temp_config_dir("watch");
+        let config_path = dir.join("config.toml");
+        fs::write(&config_path, "[demo]\n").expect("write config");
+
+        let mut watcher = ConfigWatcher::new(std::slice::from_ref(&config_path));
+        assert!(!watcher.poll_changed());
+
+        // Set mtimes explicitly so coarse filesystem timestamps can't hide the edit.
+        let bump = |path: &Path, secs: u64| {
+            let file = fs::File::options().write(true).open(path).expect("open");
+            file.set_modified(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs))
+                .expect("set mtime");
+        };
+        bump(&config_path, 1_000);
+        assert!(watcher.poll_changed());
+        assert!(!watcher.poll_changed());
+        bump(&config_path, 1_500);
+        watcher.acknowledge_write(&config_path);
+        assert!(!watcher.poll_changed());
+
+        let theme_path = dir.join("themes").join("ocean.toml");
+        fs::write(&theme_path, "extends = \"aurora\"\n").expect("write theme");
+        assert!(watcher.poll_changed());
+        bump(&theme_path, 2_000);
+        assert!(watcher.poll_changed());
+
+        fs::remove_file(&theme_path).expect("remove theme");
+        assert!(watcher.poll_changed());
+
+        let reloaded = reload_config(std::slice::from_ref(&config_path), None).expect("reload");
+        assert!(find_theme(&reloaded.themes, "ocean").is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn update_keys_toml_preserves_comments_and_context_tables() {
+        let contents = "# my config\n[demo]\ntheme = \"mono\"\n\n[keys]\nquit = \"q\" # leave\n\n[keys.list]\nlist_down = \"j\"\n";
+        let seq = |spec: &str| parse_key_sequence(spec).expect("parse");
+        let updated = update_keys_toml(
+            contents,
+            &[
+                (KeyContext::Global, KeyAction::Quit, vec![seq("x")]),
+                (
+                    KeyContext::List,
+                    KeyAction::ListDown,
+                    vec![seq("n"), seq("down")],
+                ),
+                (
+                    KeyContext::Global,
+                    KeyAction::CycleTheme,
+                    vec![seq("ctrl+t")],
+                ),
+                (KeyContext::List, KeyAction::CycleTheme, vec![seq("g t")]),
+            ],
+        )
+        .expect("update");
+
+        assert!(updated.starts_with("# my config\n[demo]\ntheme = \"mono\"\n"));
+        assert!(updated.contains("quit = \"x\" # leave\n"));
+        assert!(updated.contains("cycle_theme = \"ctrl+t\""));
+        assert!(
+            updated.contains("[keys.list]\nlist_down = [\"n\", \"down\"]\ncycle_theme = \"g t\"\n")
+        );
+        assert!(!updated.contains("\nlist_down = \"j\""));
+        validate_config_contents(&updated, Path::new("config.toml"), &[]).expect("valid");
+
+        // Removing the last binding of a context entry removes the entry.
+        let removed = update_keys_toml(
+            &updated,
+            &[(KeyContext::List, KeyAction::CycleTheme, Vec::new())],
+        )
+        .expect("remove");
+        assert!(removed.contains("[keys.list]\nlist_down = [\"n\", \"down\"]\n"));
+        assert!(!removed.contains("[keys.list]\nlist_down = [\"n\", \"down\"]\ncycle_theme"));
+    }
+
+    #[test]
+    fn update_keys_toml_scopes_list_keys_in_current_and_new_files() {
+        let seq = |spec: &str| parse_key_sequence(spec).expect("parse");
+        let edits = [
+            (KeyContext::Global, KeyAction::Quit, vec![seq("x")]),
+            (KeyContext::List, KeyAction::ListDown, vec![seq("n")]),
+        ];
+        let current =
+            update_keys_toml("version = 2\n\n[keys]\nquit = \"q\"\n", &edits).expect("update");
+        assert_eq!(
+            current,
+            "version = 2\n\n[keys]\nquit = \"x\"\n\n[keys.list]\nlist_down = \"n\"\n"
+        );
+
+        let created = update_keys_toml("", &edits).expect("create");
+        assert!(created.starts_with("version = 2\n"));
+        assert!(created.contains("[keys.list]\nlist_down = \"n\"\n"));
+        validate_config_contents(&created, Path::new("config.toml"), &[]).expect("valid");
+    }
+
+    #[test]
+    fn migrate_moves_list_keys_into_their_context_and_keeps_comments() {
+        let contents = "# my config\n[demo]\ntheme = \"mono\"\n\n[keys]\nquit = \"q\" # leave\n# scroll up\nlist_up = \"k\" # vi\nlist_down = \"j\"\n\n[themes.brand]\nextends = \"mono\"\n";
+        let (from, migrated) = migrate_config_toml(contents).expect("migrate");
+        assert_eq!(from, 1);
+        assert_eq!(
+            migrated,
+            "# my config\nversion = 2\n\n[demo]\ntheme = \"mono\"\n\n[keys]\nquit = \"q\" # leave\n\n[keys.list]\n# scroll up\nlist_up = \"k\" # vi\nlist_down = \"j\"\n\n[themes.brand]\nextends = \"mono\"\n"
+        );
+        validate_config_contents(&migrated, Path::new("config.toml"), &[]).expect("valid");
+        assert_eq!(
+            migrate_config_toml(&migrated).expect("current"),
+            (CONFIG_VERSION, migrated.clone())
+        );
+    }
+
+    #[test]
+    fn versions_are_checked_before_the_schema() {
+        let newer = diagnose("version = 3\n[keys]\nscroll = \"j\"\n");
+        assert_eq!(newer.len(), 1, "{:?}", newer);
+        assert_eq!(
+            newer[0].message,
+            "config version 3 is newer than this cli-tui-starter supports (up to 2)"
+        );
+
+        let moved = diagnose("version = 2\n[keys]\nlist_down = \"j\"\n");
+        assert_eq!(
+            moved[0].message,
+            "'list_down' belongs in [keys.list] since config version 2, not [keys]"
+        );
+        assert_eq!(moved[0].span.as_ref().map(|s| s.line), Some(3));
+
+        let path = Path::new("/tmp/config.toml");
+        let legacy = parse_config_bundle("[keys]\nlist_down = \"j\"\n", path, Vec::new())
+            .expect("version 1");
+        assert_eq!(key_labels(&legacy.keys.bindings.list_down), ["j"]);
+    }
+
+    #[test]
+    fn env_color_depth_cases() {
+        assert_eq!(
+            env_color_depth(Some(OsStr::new("truecolor")), Some(OsStr::new("xterm"))),
+            ColorDepth::Truecolor
+        );
+        assert_eq!(
+            env_color_depth(None, Some(OsStr::new("xterm-256color"))),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(
+            env_color_depth(None, Some(OsStr::new("xterm-direct"))),
+            ColorDepth::Truecolor
+        );
+        assert_eq!(
+            env_color_depth(None, Some(OsStr::new("linux"))),
+            ColorDepth::Ansi16
+        );
+        assert_eq!(env_color_depth(None, None), ColorDepth::Ansi16);
+    }
+
     #[test]
     fn env_disables_color_cases() {
         assert!(env_disables_color(
@@ -0,0 +1,529 @@
+//! Config errors located in their source file.
+//!
+//! Every layer goes through [`deserialize`], which keeps reading past a rejected entry so that all
+//! of a file's problems are reported at once: the file, line and column of the offending key or
+//! value, a caret-underlined snippet, and a "did you mean" suggestion when a key, theme or key
+//! name looks like a typo of a valid one. The valid keys come from serde's own error messages, so
+//! they always match the config types.
+
+use crate::config::{ConfigLocation, LayerSource};
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::ops::Range;
+use toml_edit::{ImDocument, Item, TableLike};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The config file (or `CLI_TUI_STARTER_*` variable) at fault; `None` for problems that only
+    /// appear once the layers are merged.
+    pub source: Option<LayerSource>,
+    pub message: String,
+    pub span: Option<SourceSpan>,
+    /// The value that was most likely meant, for a misspelled key, theme or key name.
+    pub suggestion: Option<String>,
+    /// Guidance shown when there is no suggestion, usually the valid values.
+    pub help: Option<String>,
+}
+
+/// Where a diagnostic points in its file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceSpan {
+    /// 1-based line of the first offending character.
+    pub line: usize,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+    /// Characters to underline, clipped to the end of the line.
+    pub width: usize,
+    /// The source line, for the snippet.
+    pub text: String,
+}
+
+impl SourceSpan {
+    /// The span of the bytes in `span` within `contents`.
+    pub fn new(contents: &str, span: Range<usize>) -> Self {
+        let mut start = span.start.min(contents.len());
+        while !contents.is_char_boundary(start) {
+            start -= 1;
+        }
+        let line_start = contents[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = contents[start..]
+            .find('\n')
+            .map_or(contents.len(), |i| start + i);
+        let mut end = span.end.clamp(start, line_end);
+        while !contents.is_char_boundary(end) {
+            end -= 1;
+        }
+        Self {
+            line: contents[..start].matches('\n').count() + 1,
+            column: contents[line_start..start].chars().count() + 1,
+            width: contents[start..end]
+                .trim_end_matches('\r')
+                .chars()
+                .count()
+                .max(1),
+            text: contents[line_start..line_end]
+                .trim_end_matches('\r')
+                .replace('\t', " "),
+        }
+    }
+}
+
+impl Diagnostic {
+    pub fn new(source: Option<&LayerSource>, message: impl Into<String>) -> Self {
+        Self {
+            source: source.cloned(),
+            message: message.into(),
+            span: None,
+            suggestion: None,
+            help: None,
+        }
+    }
+
+    /// A diagnostic pointing at `span` in `contents`. Environment overrides are one-line
+    /// documents generated from the variable, so they are reported by name only.
+    pub fn located(
+        source: &LayerSource,
+        contents: &str,
+        span: Option<Range<usize>>,
+        message: impl Into<String>,
+    ) -> Self {
+        let mut diagnostic = Self::new(Some(source), message);
+        if !source.is_env() {
+            diagnostic.span = span.map(|span| SourceSpan::new(contents, span));
+        }
+        diagnostic
+    }
+
+    /// A diagnostic pointing at an entry recorded while the layers were read.
+    pub fn at(location: &ConfigLocation, message: impl Into<String>) -> Self {
+        let mut diagnostic = Self::new(Some(&location.source), message);
+        diagnostic.span = location.span.clone();
+        diagnostic
+    }
+
+    pub fn with_suggestion(mut self, suggestion: Option<&str>) -> Self {
+        self.suggestion = suggestion.map(str::to_string);
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+}
+
+/// Config problems as an error, for commands that load the config rather than check it. It
+/// displays the diagnostics the way `config validate` prints them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigErrors(pub Vec<Diagnostic>);
+
+impl ConfigErrors {
+    /// Fails with `diagnostics` unless there are none.
+    pub fn check(diagnostics: Vec<Diagnostic>) -> Result<(), Self> {
+        if diagnostics.is_empty() {
+            Ok(())
+        } else {
+            Err(Self(diagnostics))
+        }
+    }
+
+    /// The first problem on one line with where it is, for the demo's status line.
+    pub fn summary(&self) -> String {
+        let Some(first) = self.0.first() else {
+            return "no config errors".to_string();
+        };
+        let mut summary = first.message.clone();
+        match (&first.source, &first.span) {
+            (Some(source), Some(span)) => {
+                summary += &format!(" ({}:{}:{})", source, span.line, span.column)
+            }
+            (Some(source), None) => summary += &format!(" ({})", source),
+            (None, _) => {}
+        }
+        if self.0.len() > 1 {
+            summary += &format!(" and {} more", self.0.len() - 1);
+        }
+        summary
+    }
+}
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, diagnostic) in self.0.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", diagnostic)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigErrors {}
+
+/// Renders like a compiler error: message, `--> path:line:column`, the source line with the
+/// offending part underlined, then the suggestion or help.
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "error: {}", self.message)?;
+        let gutter = self
+            .span
+            .as_ref()
+            .map_or(1, |span| span.line.to_string().len());
+        let pad = " ".repeat(gutter);
+        match (&self.source, &self.span) {
+            (Some(source), Some(span)) => {
+                writeln!(f, "{}--> {}:{}:{}", pad, source, span.line, span.column)?;
+                writeln!(f, "{} |", pad)?;
+                writeln!(f, "{} | {}", span.line, span.text)?;
+                writeln!(
+                    f,
+                    "{} | {}{}",
+                    pad,
+                    " ".repeat(span.column - 1),
+                    "^".repeat(span.width)
+                )?;
+            }
+            (Some(source), None) => writeln!(f, "{}--> {}", pad, source)?,
+            (None, _) => {}
+        }
+        if let Some(suggestion) = &self.suggestion {
+            writeln!(f, "{} = help: did you mean '{}'?", pad, suggestion)?;
+        } else if let Some(help) = &self.help {
+            writeln!(f, "{} = help: {}", pad, help)?;
+        }
+        Ok(())
+    }
+}
+
+/// The candidate closest to `input` by edit distance, ignoring case, when it is close enough to
+/// be a likely typo (one edit per three characters, at least one).
+pub fn did_you_mean<'a>(
+    input: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let input = input.trim().to_ascii_lowercase();
+    let limit = (input.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| {
+            (
+                edit_distance(&input, &candidate.to_ascii_lowercase()),
+                candidate,
+            )
+        })
+        .filter(|&(distance, _)| distance <= limit)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Parses a config file for span lookups, reporting a syntax error as a diagnostic.
+pub fn parse_document<'a>(
+    source: &LayerSource,
+    contents: &'a str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<ImDocument<&'a str>> {
+    match ImDocument::parse(contents) {
+        Ok(doc) => Some(doc),
+        Err(err) => {
+            diagnostics.push(Diagnostic::located(
+                source,
+                contents,
+                err.span(),
+                format!(
+                    "invalid TOML: {}",
+                    err.message().trim_end().replace('\n', "; ")
+                ),
+            ));
+            None
+        }
+    }
+}
+
+/// Deserializes `doc`, reporting every entry serde rejects rather than only the first: each
+/// rejected entry is blanked out and the rest of the file read again, so the value returned is
+/// the file without them. `None` when an error can't be traced to an entry.
+pub fn deserialize<T: DeserializeOwned>(
+    source: &LayerSource,
+    doc: &ImDocument<&str>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<T> {
+    let contents = doc.raw();
+    let mut text = contents.to_string();
+    loop {
+        let err = match toml::from_str::<T>(&text) {
+            Ok(value) => return Some(value),
+            Err(err) => err,
+        };
+        let span = err.span();
+        let message = err.message().trim_end();
+        let Some((keys, ranges)) = span
+            .clone()
+            .and_then(|span| entry_at(doc.as_table(), span.start))
+        else {
+            diagnostics.push(Diagnostic::located(source, contents, span, message));
+            return None;
+        };
+        diagnostics.push(rejection(source, contents, span, &keys, message));
+        let before = text.clone();
+        blank(&mut text, &ranges);
+        if text == before {
+            return None;
+        }
+    }
+}
+
+/// A diagnostic for the entry at `keys` that serde rejected with `message`.
+fn rejection(
+    source: &LayerSource,
+    contents: &str,
+    span: Option<Range<usize>>,
+    keys: &[String],
+    message: &str,
+) -> Diagnostic {
+    let Some((field, expected)) = unknown_field(message) else {
+        return Diagnostic::located(
+            source,
+            contents,
+            span,
+            format!("invalid value for `{}`: {}", keys.join("."), message),
+        );
+    };
+    let table = match keys.split_last() {
+        Some((last, parents)) if *last == field => parents,
+        _ => keys,
+    };
+    let message = if table.is_empty() {
+        format!("unknown key '{}'", field)
+    } else {
+        format!("unknown key '{}' in [{}]", field, table.join("."))
+    };
+    let diagnostic = Diagnostic::located(source, contents, span, message)
+        .with_suggestion(did_you_mean(field, expected.iter().copied()));
+    if expected.is_empty() {
+        diagnostic
+    } else {
+        diagnostic.with_help(format!("valid keys: {}", expected.join(", ")))
+    }
+}
+
+/// The field and the expected field names of serde's "unknown field `x`, expected one of `a`,
+/// `b`" message.
+fn unknown_field(message: &str) -> Option<(&str, Vec<&str>)> {
+    let rest = message.strip_prefix("unknown field `")?;
+    let (field, expected) = rest.split_once('`')?;
+    Some((field, expected.split('`').skip(1).step_by(2).collect()))
+}
+
+/// The keys leading to the entry of `table` whose key or value contains `offset`, and the byte
+/// ranges to blank to drop it. A value is dropped with its key; a table, when the error is on
+/// its header or not inside any of its entries, with everything under it.
+fn entry_at(table: &dyn TableLike, offset: usize) -> Option<(Vec<String>, Vec<Range<usize>>)> {
+    for (name, item) in table.iter() {
+        let key_span = table.get_key_value(name).and_then(|(key, _)| key.span());
+        let on_key = key_span.as_ref().is_some_and(|span| span.contains(&offset));
+        if let Item::Value(value) = item {
+            let Some(span) = value.span() else {
+                continue;
+            };
+            if !on_key && !span.contains(&offset) {
+                continue;
+            }
+            let mut keys = vec![name.to_string()];
+            if let Some(inline) = value.as_inline_table().filter(|_| !on_key) {
+                keys.extend(inline_keys(inline, offset));
+            }
+            let start = key_span.map_or(span.start, |key| key.start);
+            return Some((
+                keys,
+                vec![Range {
+                    start,
+                    end: span.end,
+                }],
+            ));
+        }
+
+        if !on_key {
+            if let Some((mut keys, ranges)) = tables(item)
+                .into_iter()
+                .find_map(|table| entry_at(table, offset))
+            {
+                keys.insert(0, name.to_string());
+                return Some((keys, ranges));
+            }
+        }
+        let mut ranges = Vec::new();
+        extent(item, &mut ranges);
+        if on_key || ranges.iter().any(|range| range.contains(&offset)) {
+            return Some((vec![name.to_string()], ranges));
+        }
+    }
+    None
+}
+
+/// The table, or every table of an array of tables, that `item` is.
+fn tables(item: &Item) -> Vec<&toml_edit::Table> {
+    match item {
+        Item::Table(table) => vec![table],
+        Item::ArrayOfTables(array) => array.iter().collect(),
+        Item::None | Item::Value(_) => Vec::new(),
+    }
+}
+
+/// The keys inside an inline table leading to `offset`, for naming the entry at fault.
+fn inline_keys(table: &toml_edit::InlineTable, offset: usize) -> Vec<String> {
+    for (name, value) in table.iter() {
+        let key_span = table.key(name).and_then(|key| key.span());
+        let value_span = value.span();
+        let on_key = key_span.is_some_and(|span| span.contains(&offset));
+        if on_key || value_span.is_some_and(|span| span.contains(&offset)) {
+            let mut keys = vec![name.to_string()];
+            if let Some(inline) = value.as_inline_table().filter(|_| !on_key) {
+                keys.extend(inline_keys(inline, offset));
+            }
+            return keys;
+        }
+    }
+    Vec::new()
+}
+
+/// Every byte range `item` occupies, with the tables nested under it.
+fn extent(item: &Item, ranges: &mut Vec<Range<usize>>) {
+    if let Item::Value(value) = item {
+        ranges.extend(value.span());
+    }
+    for table in tables(item) {
+        ranges.extend(table.span());
+        for (name, child) in table.iter() {
+            if let Some(key) = table.key(name).and_then(|key| key.span()) {
+                ranges.push(key);
+            }
+            extent(child, ranges);
+        }
+    }
+}
+
+/// Replaces `ranges`, each widened to the start of its line, with spaces. Line breaks stay, so
+/// later errors still point at the right place in the original text.
+fn blank(text: &mut String, ranges: &[Range<usize>]) {
+    let mut bytes = std::mem::take(text).into_bytes();
+    for range in ranges {
+        let end = range.end.min(bytes.len());
+        let start = bytes[..range.start.min(end)]
+            .iter()
+            .rposition(|&byte| byte == b'\n')
+            .map_or(0, |i| i + 1);
+        for byte in &mut bytes[start..end] {
+            if *byte != b'\n' {
+                *byte = b' ';
+            }
+        }
+    }
+    *text = String::from_utf8(bytes).expect("only whole characters are blanked");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Root {
+        name: Option<String>,
+        #[serde(default)]
+        sections: BTreeMap<String, Section>,
+    }
+
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Section {
+        size: Option<u32>,
+        flag: Option<bool>,
+    }
+
+    fn read(contents: &str) -> (Option<Root>, Vec<Diagnostic>) {
+        let source = LayerSource::File("/tmp/config.toml".into());
+        let mut diagnostics = Vec::new();
+        let doc = parse_document(&source, contents, &mut diagnostics).expect("valid TOML");
+        (deserialize(&source, &doc, &mut diagnostics), diagnostics)
+    }
+
+    #[test]
+    fn suggestions_pick_the_closest_candidate_within_the_typo_budget() {
+        assert_eq!(
+            did_you_mean("solr", ["aurora", "mono", "solar"]),
+            Some("solar")
+        );
+        assert_eq!(did_you_mean("MOSE", ["mouse", "theme"]), Some("mouse"));
+        assert_eq!(did_you_mean("neon", ["aurora", "mono", "solar"]), None);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn every_rejected_entry_is_reported_and_the_rest_is_kept() {
+        let (root, diagnostics) = read(
+            "name = \"x\"\nnme = 1\n[sections.a]\nsize = -1\nflag = true\n[sections.b]\nsise = 2\n\
+             [sections.c]\ninner = { deep = 1 }\n[extra]\nsize = 1\n",
+        );
+        let summary = diagnostics
+            .iter()
+            .map(|d| {
+                let line = d.span.as_ref().map(|span| span.line);
+                (d.message.as_str(), line, d.suggestion.as_deref())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                ("unknown key 'nme'", Some(2), Some("name")),
+                (
+                    "invalid value for `sections.a.size`: invalid value: integer `-1`, expected u32",
+                    Some(4),
+                    None
+                ),
+                ("unknown key 'sise' in [sections.b]", Some(7), Some("size")),
+                ("unknown key 'inner' in [sections.c]", Some(9), None),
+                ("unknown key 'extra'", Some(10), None),
+            ]
+        );
+        assert_eq!(
+            diagnostics[0].help.as_deref(),
+            Some("valid keys: name, sections")
+        );
+
+        let root = root.expect("the rest still reads");
+        assert_eq!(root.name.as_deref(), Some("x"));
+        assert_eq!(root.sections["a"].flag, Some(true));
+        assert_eq!(root.sections["a"].size, None);
+    }
+
+    #[test]
+    fn syntax_errors_are_located() {
+        let mut diagnostics = Vec::new();
+        assert!(parse_document(
+            &LayerSource::File("/tmp/config.toml".into()),
+            "[a\n",
+            &mut diagnostics
+        )
+        .is_none());
+        assert!(diagnostics[0].message.starts_with("invalid TOML: "));
+        assert_eq!(diagnostics[0].span.as_ref().map(|s| s.line), Some(1));
+    }
+}
@@ -0,0 +1,426 @@
+//! Conversions between theme palettes and other tools' color scheme formats.
+//!
+//! A palette has six slots while terminal emulators expect 16 ANSI colors, so exports map them
+//! as: black = `bg`, red = `danger`, green = `success`, white = `fg`, bright black = `muted`,
+//! and yellow/blue/magenta/cyan = `accent`. Bright colors repeat the normal ones.
+
+use crate::cli::ExportFormat;
+use crate::color::{hex, to_rgb};
+use crate::theme::{validate_theme_name, PaletteSlot, Theme};
+use anyhow::{anyhow, bail, Result};
+use std::collections::BTreeMap;
+
+type Rgb = (u8, u8, u8);
+
+/// Names used by most emulators for ANSI colors 0-7.
+const ANSI_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// A palette resolved to concrete RGB values, as every export format needs hex colors.
+struct ResolvedPalette {
+    fg: Rgb,
+    bg: Rgb,
+    accent: Rgb,
+    muted: Rgb,
+    success: Rgb,
+    danger: Rgb,
+}
+
+impl ResolvedPalette {
+    fn new(theme: &Theme) -> Self {
+        let p = theme.palette;
+        // `reset` means "terminal default"; export the xterm defaults instead.
+        let rgb = |color, fallback| to_rgb(color).unwrap_or(fallback);
+        Self {
+            fg: rgb(p.fg, (229, 229, 229)),
+            bg: rgb(p.bg, (0, 0, 0)),
+            accent: rgb(p.accent, (229, 229, 229)),
+            muted: rgb(p.muted, (127, 127, 127)),
+            success: rgb(p.success, (229, 229, 229)),
+            danger: rgb(p.danger, (229, 229, 229)),
+        }
+    }
+
+    /// ANSI colors 0-7; see the module docs for the slot mapping.
+    fn normal(&self) -> [Rgb; 8] {
+        [
+            self.bg,
+            self.danger,
+            self.success,
+            self.accent,
+            self.accent,
+            self.accent,
+            self.accent,
+            self.fg,
+        ]
+    }
+
+    /// ANSI colors 8-15.
+    fn bright(&self) -> [Rgb; 8] {
+        let mut bright = self.normal();
+        bright[0] = self.muted;
+        bright
+    }
+}
+
+/// Renders `theme` in the syntax of another tool's color scheme file.
+pub fn export_theme(theme: &Theme, format: ExportFormat) -> String {
+    let p = ResolvedPalette::new(theme);
+    match format {
+        ExportFormat::Alacritty => export_alacritty(theme, &p),
+        ExportFormat::Kitty => export_kitty(theme, &p),
+        ExportFormat::Wezterm => export_wezterm(theme, &p),
+        ExportFormat::Base16Yaml => export_base16(theme, &p),
+        ExportFormat::VscodeTerminal => export_vscode(theme, &p),
+    }
+}
+
+fn export_alacritty(theme: &Theme, p: &ResolvedPalette) -> String {
+    let mut out = format!("# {}: {}\n", theme.name, theme.description);
+    out.push_str("[colors.primary]\n");
+    out.push_str(&format!("background = \"{}\"\n", hex(p.bg)));
+    out.push_str(&format!("foreground = \"{}\"\n", hex(p.fg)));
+    out.push_str("\n[colors.cursor]\n");
+    out.push_str(&format!("text = \"{}\"\n", hex(p.bg)));
+    out.push_str(&format!("cursor = \"{}\"\n", hex(p.accent)));
+    out.push_str("\n[colors.selection]\n");
+    out.push_str(&format!("text = \"{}\"\n", hex(p.fg)));
+    out.push_str(&format!("background = \"{}\"\n", hex(p.muted)));
+    for (table, colors) in [("normal", p.normal()), ("bright", p.bright())] {
+        out.push_str(&format!("\n[colors.{}]\n", table));
+        for (name, rgb) in ANSI_NAMES.iter().zip(colors) {
+            out.push_str(&format!("{} = \"{}\"\n", name, hex(rgb)));
+        }
+    }
+    out
+}
+
+fn export_kitty(theme: &Theme, p: &ResolvedPalette) -> String {
+    let mut out = format!("# {}: {}\n", theme.name, theme.description);
+    out.push_str(&format!("foreground {}\n", hex(p.fg)));
+    out.push_str(&format!("background {}\n", hex(p.bg)));
+    out.push_str(&format!("cursor {}\n", hex(p.accent)));
+    out.push_str(&format!("cursor_text_color {}\n", hex(p.bg)));
+    out.push_str(&format!("selection_foreground {}\n", hex(p.fg)));
+    out.push_str(&format!("selection_background {}\n", hex(p.muted)));
+    for (index, rgb) in p.normal().into_iter().chain(p.bright()).enumerate() {
+        out.push_str(&format!("color{} {}\n", index, hex(rgb)));
+    }
+    out
+}
+
+fn export_wezterm(theme: &Theme, p: &ResolvedPalette) -> String {
+    let list = |colors: [Rgb; 8]| {
+        colors
+            .iter()
+            .map(|rgb| format!("\"{}\"", hex(*rgb)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let mut out = String::from("[colors]\n");
+    out.push_str(&format!("foreground = \"{}\"\n", hex(p.fg)));
+    out.push_str(&format!("background = \"{}\"\n", hex(p.bg)));
+    out.push_str(&format!("cursor_bg = \"{}\"\n", hex(p.accent)));
+    out.push_str(&format!("cursor_border = \"{}\"\n", hex(p.accent)));
+    out.push_str(&format!("cursor_fg = \"{}\"\n", hex(p.bg)));
+    out.push_str(&format!("selection_bg = \"{}\"\n", hex(p.muted)));
+    out.push_str(&format!("selection_fg = \"{}\"\n", hex(p.fg)));
+    out.push_str(&format!("ansi = [{}]\n", list(p.normal())));
+    out.push_str(&format!("brights = [{}]\n", list(p.bright())));
+    out.push_str("\n[metadata]\n");
+    out.push_str(&format!("name = {}\n", toml_string(&theme.name)));
+    out
+}
+
+/// base16 expects a background-to-foreground ramp in base00-07; intermediate shades are blended.
+fn export_base16(theme: &Theme, p: &ResolvedPalette) -> String {
+    let slots = [
+        p.bg,
+        blend(p.bg, p.muted, 1, 3),
+        blend(p.bg, p.muted, 2, 3),
+        p.muted,
+        blend(p.muted, p.fg, 1, 2),
+        p.fg,
+        p.fg,
+        p.fg,
+        p.danger,
+        p.danger,
+        p.accent,
+        p.success,
+        p.accent,
+        p.accent,
+        p.accent,
+        p.danger,
+    ];
+    let mut out = format!("scheme: {}\n", yaml_string(&theme.name));
+    out.push_str("author: \"cli-tui-starter\"\n");
+    for (index, rgb) in slots.iter().enumerate() {
+        out.push_str(&format!(
+            "base{:02X}: \"{}\"\n",
+            index,
+            hex(*rgb).trim_start_matches('#')
+        ));
+    }
+    out
+}
+
+fn export_vscode(theme: &Theme, p: &ResolvedPalette) -> String {
+    let mut entries = vec![
+        ("terminal.background".to_string(), p.bg),
+        ("terminal.foreground".to_string(), p.fg),
+        ("terminalCursor.foreground".to_string(), p.accent),
+        ("terminal.selectionBackground".to_string(), p.muted),
+    ];
+    for (prefix, colors) in [("ansi", p.normal()), ("ansiBright", p.bright())] {
+        for (name, rgb) in ANSI_NAMES.iter().zip(colors) {
+            let mut name = name.to_string();
+            name[..1].make_ascii_uppercase();
+            entries.push((format!("terminal.{}{}", prefix, name), rgb));
+        }
+    }
+
+    let mut out = format!(
+        "// {}: {}\n{{\n  \"workbench.colorCustomizations\": {{\n",
+        theme.name, theme.description
+    );
+    let last = entries.len() - 1;
+    for (index, (key, rgb)) in entries.iter().enumerate() {
+        let comma = if index == last { "" } else { "," };
+        out.push_str(&format!("    \"{}\": \"{}\"{}\n", key, hex(*rgb), comma));
+    }
+    out.push_str("  }\n}\n");
+    out
+}
+
+fn blend(from: Rgb, to: Rgb, num: u16, den: u16) -> Rgb {
+    let mix = |a: u8, b: u8| ((a as u16 * (den - num) + b as u16 * num + den / 2) / den) as u8;
+    (mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+/// A base16 scheme read from YAML: its display name and `base00`-`base0F` as `#rrggbb`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Base16Scheme {
+    pub name: String,
+    pub author: Option<String>,
+    pub colors: BTreeMap<String, String>,
+}
+
+/// Parses a base16 scheme. Handles both the classic flat layout (`base00: "1d1f21"`) and the
+/// newer tinted-theming layout with a nested `palette:` block and `#`-prefixed values.
+pub fn parse_base16_yaml(contents: &str) -> Result<Base16Scheme> {
+    let mut name = None;
+    let mut author = None;
+    let mut colors = BTreeMap::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line == "---" {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = key.trim();
+        let value = unquote(strip_comment(value.trim()));
+        match key {
+            "scheme" | "name" => name = Some(value.to_string()),
+            "author" => author = Some(value.to_string()),
+            _ if key.len() == 6 && key.starts_with("base") => {
+                let digits = value.trim_start_matches('#');
+                if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                    bail!(
+                        "invalid color '{}' for {} on line {} (expected 6 hex digits)",
+                        value,
+                        key,
+                        index + 1
+                    );
+                }
+                colors.insert(
+                    key.to_ascii_lowercase(),
+                    format!("#{}", digits.to_ascii_lowercase()),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    let name = name
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| anyhow!("base16 scheme is missing a `scheme` (or `name`) field"))?;
+    for slot in BASE16_SLOTS {
+        if !colors.contains_key(slot) {
+            bail!("base16 scheme '{}' is missing {}", name, slot);
+        }
+    }
+    Ok(Base16Scheme {
+        name,
+        author,
+        colors,
+    })
+}
+
+/// The base16 entries an imported theme reads, in palette-slot order.
+const BASE16_SLOTS: [&str; 6] = ["base05", "base00", "base0d", "base03", "base0b", "base08"];
+
+impl Base16Scheme {
+    /// Theme name derived from the scheme name: lowercase, with runs of other characters as `-`.
+    pub fn slug(&self) -> String {
+        let mut slug = String::new();
+        for c in self.name.chars() {
+            if c.is_ascii_alphanumeric() {
+                slug.push(c.to_ascii_lowercase());
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        slug.trim_end_matches('-').to_string()
+    }
+
+    /// Renders the scheme in our theme TOML format: a `[themes.<name>]` table for `config.toml`,
+    /// or (with `table = false`) the body of a standalone `themes/<name>.toml` file.
+    pub fn to_theme_toml(&self, name: &str, table: bool) -> Result<String> {
+        validate_theme_name(name)?;
+
+        let description = match &self.author {
+            Some(author) if !author.is_empty() => format!("{} (base16, {})", self.name, author),
+            _ => format!("{} (base16)", self.name),
+        };
+        let mut out = String::new();
+        if table {
+            out.push_str(&format!("[themes.{}]\n", name));
+        }
+        out.push_str(&format!("description = {}\n", toml_string(&description)));
+        for (slot, key) in PaletteSlot::ALL.into_iter().zip(BASE16_SLOTS) {
+            out.push_str(&format!("{} = \"{}\"\n", slot.as_str(), self.colors[key]));
+        }
+        Ok(out)
+    }
+}
+
+fn strip_comment(value: &str) -> &str {
+    // A ` #` starts a YAML comment; a leading `#` is a color value.
+    match value.find(" #") {
+        Some(index) => value[..index].trim_end(),
+        None => value,
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
+fn toml_string(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+fn yaml_string(value: &str) -> String {
+    // JSON strings are valid double-quoted YAML scalars.
+    serde_json::Value::String(value.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::{themes, ThemePalette};
+    use ratatui::style::Color;
+
+    fn sample() -> Theme {
+        Theme {
+            name: "sample".to_string(),
+            description: "Sample".to_string(),
+            palette: ThemePalette {
+                fg: Color::Rgb(0xee, 0xee, 0xee),
+                bg: Color::Rgb(0x11, 0x11, 0x11),
+                accent: Color::Rgb(0x33, 0x66, 0xff),
+                muted: Color::Rgb(0x77, 0x77, 0x77),
+                success: Color::Rgb(0x22, 0xaa, 0x44),
+                danger: Color::Rgb(0xdd, 0x22, 0x22),
+            },
+            style_overrides: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn exports_map_palette_slots_to_ansi_colors() {
+        let theme = sample();
+
+        let kitty = export_theme(&theme, ExportFormat::Kitty);
+        assert!(kitty.contains("background #111111\n"));
+        assert!(kitty.contains("color1 #dd2222\n"));
+        assert!(kitty.contains("color8 #777777\n"));
+        assert!(kitty.contains("color15 #eeeeee\n"));
+
+        let alacritty = export_theme(&theme, ExportFormat::Alacritty);
+        assert!(alacritty.contains("[colors.normal]\nblack = \"#111111\"\nred = \"#dd2222\""));
+        toml::from_str::<toml::Value>(&alacritty).expect("alacritty export is valid TOML");
+
+        let wezterm = export_theme(&theme, ExportFormat::Wezterm);
+        let parsed: toml::Value = toml::from_str(&wezterm).expect("wezterm export is valid TOML");
+        assert_eq!(parsed["colors"]["ansi"].as_array().map(Vec::len), Some(8));
+        assert_eq!(parsed["colors"]["brights"][0].as_str(), Some("#777777"));
+
+        let vscode = export_theme(&theme, ExportFormat::VscodeTerminal);
+        let body = vscode.split_once('\n').map(|(_, body)| body).unwrap();
+        let json: serde_json::Value = serde_json::from_str(body).expect("valid JSON");
+        let colors = &json["workbench.colorCustomizations"];
+        assert_eq!(colors["terminal.ansiGreen"], "#22aa44");
+        assert_eq!(colors["terminal.ansiBrightBlack"], "#777777");
+    }
+
+    #[test]
+    fn base16_export_round_trips_through_import() {
+        for theme in themes().into_iter().chain([sample()]) {
+            let yaml = export_theme(&theme, ExportFormat::Base16Yaml);
+            let scheme = parse_base16_yaml(&yaml).expect("exported yaml parses");
+            assert_eq!(scheme.name, theme.name);
+
+            let toml = scheme.to_theme_toml("roundtrip", false).unwrap();
+            let parsed: toml::Value = toml::from_str(&toml).unwrap();
+            let p = ResolvedPalette::new(&theme);
+            assert_eq!(parsed["fg"].as_str(), Some(hex(p.fg).as_str()));
+            assert_eq!(parsed["bg"].as_str(), Some(hex(p.bg).as_str()));
+            assert_eq!(parsed["accent"].as_str(), Some(hex(p.accent).as_str()));
+            assert_eq!(parsed["muted"].as_str(), Some(hex(p.muted).as_str()));
+            assert_eq!(parsed["success"].as_str(), Some(hex(p.success).as_str()));
+            assert_eq!(parsed["danger"].as_str(), Some(hex(p.danger).as_str()));
+        }
+    }
+
+    #[test]
+    fn parse_base16_yaml_accepts_classic_and_palette_layouts() {
+        let classic = "scheme: \"Tomorrow Night\"\nauthor: \"Chris Kempson\"\n\
+            base00: \"1d1f21\"\nbase03: \"969896\"\nbase05: \"c5c8c6\"\n\
+            base08: \"cc6666\"\nbase0B: \"b5bd68\"\nbase0D: \"81a2be\"\n";
+        let scheme = parse_base16_yaml(classic).unwrap();
+        assert_eq!(scheme.slug(), "tomorrow-night");
+        assert_eq!(scheme.colors["base0d"], "#81a2be");
+        let toml = scheme.to_theme_toml("tomorrow-night", true).unwrap();
+        assert!(toml.starts_with("[themes.tomorrow-night]\n"));
+        assert!(toml.contains("description = \"Tomorrow Night (base16, Chris Kempson)\"\n"));
+        assert!(toml.contains("accent = \"#81a2be\"\n"));
+
+        let tinted = "system: \"base16\"\nname: 'Nord'\nvariant: \"dark\"\npalette:\n  \
+            base00: \"#2E3440\" # background\n  base03: \"#4C566A\"\n  base05: \"#D8DEE9\"\n  \
+            base08: \"#BF616A\"\n  base0B: \"#A3BE8C\"\n  base0D: \"#81A1C1\"\n";
+        let scheme = parse_base16_yaml(tinted).unwrap();
+        assert_eq!(scheme.name, "Nord");
+        assert_eq!(scheme.colors["base00"], "#2e3440");
+    }
+
+    #[test]
+    fn parse_base16_yaml_reports_missing_and_invalid_colors() {
+        let error = parse_base16_yaml("scheme: x\nbase00: \"000000\"\n").unwrap_err();
+        assert!(error.to_string().contains("missing base05"));
+
+        let error = parse_base16_yaml("scheme: x\nbase00: \"zzzzzz\"\n").unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+
+        let error = parse_base16_yaml("base00: \"000000\"\n").unwrap_err();
+        assert!(error.to_string().contains("missing a `scheme`"));
+    }
+}
@@ -0,0 +1,299 @@
+//! Key reference cards rendered from the active keymap, for checking generated key docs into
+//! other repositories.
+//!
+//! Every format lists the same rows: actions grouped by [`KeyCategory`], one row per action and
+//! context (`[keys.<context>]` extras get their own row), followed by the reserved quit keys.
+//! Output is deterministic (no dates) so regenerated files only change when the keymap does.
+
+use crate::cli::{KeymapPreset, KeysFormat};
+use crate::keys::{
+    key_labels, KeyAction, KeyBindings, KeyCategory, KeyContext, RESERVED_QUIT_KEYS,
+};
+
+const TITLE: &str = "cli-tui-starter key bindings";
+const RESERVED_NOTE: &str =
+    "always quit and cannot be rebound; esc cancels a pending key sequence first";
+
+struct Row {
+    action: KeyAction,
+    context: KeyContext,
+    keys: Vec<String>,
+}
+
+/// Rows per category, in [`KeyCategory::ALL`] order; empty categories are left out.
+fn sections(keymap: &KeyBindings) -> Vec<(KeyCategory, Vec<Row>)> {
+    KeyCategory::ALL
+        .into_iter()
+        .map(|category| {
+            let mut rows = Vec::new();
+            for action in KeyAction::ALL
+                .into_iter()
+                .filter(|action| action.category() == category)
+            {
+                rows.push(Row {
+                    action,
+                    context: action.home_context(),
+                    keys: key_labels(keymap.keys(action)),
+                });
+                for binding in keymap.scoped.iter().filter(|b| b.action == action) {
+                    rows.push(Row {
+                        action,
+                        context: binding.context,
+                        keys: key_labels(&binding.keys),
+                    });
+                }
+            }
+            (category, rows)
+        })
+        .filter(|(_, rows)| !rows.is_empty())
+        .collect()
+}
+
+/// Renders the reference card. Text and JSON are handled by `keys` itself.
+pub fn render(format: KeysFormat, keymap: &KeyBindings, preset: KeymapPreset) -> String {
+    let sections = sections(keymap);
+    match format {
+        KeysFormat::Markdown => render_markdown(&sections, preset),
+        KeysFormat::Html => render_html(&sections, preset),
+        KeysFormat::Man => render_man(&sections, preset),
+        KeysFormat::Csv => render_csv(&sections),
+        KeysFormat::Text | KeysFormat::Json => {
+            unreachable!("text and json key output is rendered by `keys`")
+        }
+    }
+}
+
+fn render_markdown(sections: &[(KeyCategory, Vec<Row>)], preset: KeymapPreset) -> String {
+    // Code spans can't hold a backtick with single delimiters, and GFM splits table cells on
+    // `|` even inside code.
+    let code = |label: &str| {
+        let label = label.replace('|', "\\|");
+        if label.contains('`') {
+            format!("`` {} ``", label)
+        } else {
+            format!("`{}`", label)
+        }
+    };
+
+    let mut out = format!("# {}\n\nPreset: `{}`\n", TITLE, preset.as_str());
+    for (category, rows) in sections {
+        out.push_str(&format!(
+            "\n## {}\n\n| Keys | Action | Context |\n|---|---|---|\n",
+            category.title()
+        ));
+        for row in rows {
+            let keys = row
+                .keys
+                .iter()
+                .map(|key| code(key))
+                .collect::<Vec<_>>()
+                .join(", ");
+            out.push_str(&format!(
+                "| {} | {} | {} |\n",
+                keys,
+                row.action.description(),
+                row.context.as_str()
+            ));
+        }
+    }
+    let reserved = RESERVED_QUIT_KEYS.map(code).join(" and ");
+    out.push_str(&format!(
+        "\n## Reserved\n\n{} {}.\n",
+        reserved, RESERVED_NOTE
+    ));
+    out
+}
+
+fn render_html(sections: &[(KeyCategory, Vec<Row>)], preset: KeymapPreset) -> String {
+    let kbd = |label: &str| format!("<kbd>{}</kbd>", html_escape(label));
+
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n</head>\n<body>\n<h1>{title}</h1>\n\
+         <p>Preset: <code>{preset}</code></p>\n",
+        title = TITLE,
+        preset = preset.as_str()
+    );
+    for (category, rows) in sections {
+        out.push_str(&format!(
+            "<h2>{}</h2>\n<table>\n<thead><tr><th>Keys</th><th>Action</th><th>Context</th></tr></thead>\n<tbody>\n",
+            category.title()
+        ));
+        for row in rows {
+            let keys = row
+                .keys
+                .iter()
+                .map(|key| kbd(key))
+                .collect::<Vec<_>>()
+                .join(", ");
+            out.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                keys,
+                row.action.description(),
+                row.context.as_str()
+            ));
+        }
+        out.push_str("</tbody>\n</table>\n");
+    }
+    out.push_str(&format!(
+        "<h2>Reserved</h2>\n<p>{} {}.</p>\n</body>\n</html>\n",
+        RESERVED_QUIT_KEYS.map(kbd).join(" and "),
+        RESERVED_NOTE
+    ));
+    out
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_man(sections: &[(KeyCategory, Vec<Row>)], preset: KeymapPreset) -> String {
+    let mut out = format!(
+        ".TH CLI-TUI-STARTER-KEYS 7 \"\" \"cli-tui-starter {}\" \"Key bindings\"\n\
+         .SH NAME\ncli-tui-starter-keys \\- key bindings for the cli-tui-starter demo\n\
+         .SH DESCRIPTION\nPreset: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        preset.as_str()
+    );
+    for (category, rows) in sections {
+        out.push_str(&format!(".SH {}\n", category.title().to_uppercase()));
+        for row in rows {
+            let keys = row
+                .keys
+                .iter()
+                .map(|key| roff_escape(key))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let context = match row.context {
+                KeyContext::Global => String::new(),
+                context => format!(" (in {})", context.as_str()),
+            };
+            out.push_str(&format!(
+                ".TP\n.B {}\n{}{}\n",
+                keys,
+                row.action.description(),
+                context
+            ));
+        }
+    }
+    out.push_str(&format!(
+        ".SH RESERVED KEYS\n.B {}\n{}.\n",
+        RESERVED_QUIT_KEYS.map(roff_escape).join(", "),
+        RESERVED_NOTE
+    ));
+    out
+}
+
+/// Escapes text for a roff line: backslashes, hyphens (so `-` stays a minus in every
+/// renderer), quotes (`.B` takes quoted arguments) and a leading control character.
+fn roff_escape(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\e")
+        .replace('-', "\\-")
+        .replace('"', "\\(dq");
+    if escaped.starts_with(['.', '\'']) {
+        format!("\\&{}", escaped)
+    } else {
+        escaped
+    }
+}
+
+/// One row per key, so spreadsheets and scripts don't have to split key lists.
+fn render_csv(sections: &[(KeyCategory, Vec<Row>)]) -> String {
+    let mut out = String::from("category,action,description,context,key\n");
+    let mut push = |category: &str, action: KeyAction, context: KeyContext, key: &str| {
+        let fields = [
+            category,
+            action.as_str(),
+            action.description(),
+            context.as_str(),
+            key,
+        ];
+        let line = fields.map(csv_field).join(",");
+        out.push_str(&line);
+        out.push('\n');
+    };
+    for (category, rows) in sections {
+        for row in rows {
+            for key in &row.keys {
+                push(category.title(), row.action, row.context, key);
+            }
+        }
+    }
+    for key in RESERVED_QUIT_KEYS {
+        push("Reserved", KeyAction::Quit, KeyContext::Global, key);
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) || value.trim() != value {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::{parse_key_sequence, ScopedBinding};
+
+    fn keymap() -> KeyBindings {
+        KeyBindings {
+            toggle_help: vec![parse_key_sequence("?").unwrap()],
+            toggle_color: vec![
+                parse_key_sequence(",").unwrap(),
+                parse_key_sequence("`").unwrap(),
+            ],
+            scoped: vec![ScopedBinding {
+                context: KeyContext::Overview,
+                action: KeyAction::CycleTheme,
+                keys: vec![parse_key_sequence("-").unwrap()],
+            }],
+            ..KeyBindings::preset(KeymapPreset::Vim)
+        }
+    }
+
+    #[test]
+    fn markdown_groups_actions_and_escapes_code_spans() {
+        let out = render(KeysFormat::Markdown, &keymap(), KeymapPreset::Vim);
+        assert!(out.starts_with("# cli-tui-starter key bindings\n\nPreset: `vim`\n"));
+        let navigation = out.find("## Navigation").unwrap();
+        let display = out.find("## Display").unwrap();
+        let general = out.find("## General").unwrap();
+        assert!(navigation < display && display < general);
+        assert!(out.contains("| `g t`, `tab` | next panel | global |\n"));
+        assert!(out.contains("| `j`, `down` | list down | list |\n"));
+        assert!(out.contains("| `,`, `` ` `` | toggle color | global |\n"));
+        assert!(out.contains("| `-` | cycle theme | overview |\n"));
+        assert!(out.contains("## Reserved\n\n`esc` and `ctrl+c` always quit"));
+    }
+
+    #[test]
+    fn html_man_and_csv_escape_their_special_characters() {
+        let mut keymap = keymap();
+        keymap.quit = vec![parse_key_sequence("<").unwrap()];
+
+        let html = render(KeysFormat::Html, &keymap, KeymapPreset::Vim);
+        assert!(html.contains("<tr><td><kbd>&lt;</kbd></td><td>quit</td><td>global</td></tr>"));
+        assert!(html.trim_end().ends_with("</html>"));
+
+        let man = render(KeysFormat::Man, &keymap, KeymapPreset::Vim);
+        assert!(man.starts_with(".TH CLI-TUI-STARTER-KEYS 7"));
+        assert!(man.contains(".SH NAVIGATION\n.TP\n.B g t, tab\nnext panel\n"));
+        assert!(man.contains(".TP\n.B \\-\ncycle theme (in overview)\n"));
+        assert!(man.contains(".SH RESERVED KEYS\n.B esc, ctrl+c\n"));
+
+        let csv = render(KeysFormat::Csv, &keymap, KeymapPreset::Vim);
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "category,action,description,context,key");
+        assert!(lines.contains(&"Display,toggle_color,toggle color,global,\",\""));
+        assert!(lines.contains(&"General,quit,quit,global,<"));
+        assert_eq!(lines.last(), Some(&"Reserved,quit,quit,global,ctrl+c"));
+    }
+}
@@ -1,115 +1,649 @@
+use crate::cli::KeymapPreset;
+use crate::diagnostics::did_you_mean;
 use anyhow::{anyhow, bail, Result};
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use std::collections::HashSet;
+use std::time::Duration;
+
+/// How long the demo waits for the next key of a multi-key sequence before dropping it.
+pub const DEFAULT_SEQUENCE_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Keys that always quit, whatever the keymap says.
+pub const RESERVED_QUIT_KEYS: [&str; 2] = ["esc", "ctrl+c"];
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct KeySpec {
     pub code: KeyCode,
     pub modifiers: KeyModifiers,
+    /// Press, repeat or release. Terminals only report repeat and release with the kitty keyboard
+    /// protocol; elsewhere every event is a press.
+    pub kind: KeyEventKind,
 }
 
+/// Modifiers that can be part of a binding; anything else a terminal reports is ignored.
+/// `super`, `hyper` and `meta` only arrive through the kitty keyboard protocol.
+const BINDABLE_MODIFIERS: KeyModifiers = KeyModifiers::CONTROL
+    .union(KeyModifiers::ALT)
+    .union(KeyModifiers::SHIFT)
+    .union(KeyModifiers::SUPER)
+    .union(KeyModifiers::HYPER)
+    .union(KeyModifiers::META);
+
+/// Spec prefixes for the non-press event kinds (`release+space`).
+const KIND_NAMES: [(&str, KeyEventKind); 2] = [
+    ("repeat", KeyEventKind::Repeat),
+    ("release", KeyEventKind::Release),
+];
+
 impl KeySpec {
+    /// A key press.
+    pub const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+        }
+    }
+
+    pub fn from_event(event: KeyEvent) -> Self {
+        Self {
+            code: event.code,
+            modifiers: event.modifiers,
+            kind: event.kind,
+        }
+        .normalized()
+    }
+
+    /// Canonical form, so the same key compares equal however it was typed or reported:
+    /// - shift on a printable character is folded into the character (`shift+t` -> `T`,
+    ///   `shift+?` -> `?`), since terminals disagree on whether they report it;
+    /// - `shift+tab` becomes `backtab`;
+    /// - modifier bits that bindings can't express are dropped. Lock states live in
+    ///   `KeyEvent::state` and never reach a `KeySpec`.
+    pub fn normalized(self) -> Self {
+        let mut code = self.code;
+        let mut modifiers = self.modifiers & BINDABLE_MODIFIERS;
+        if modifiers.contains(KeyModifiers::SHIFT) {
+            match code {
+                KeyCode::Char(c) => {
+                    code = KeyCode::Char(shifted_char(c));
+                    modifiers.remove(KeyModifiers::SHIFT);
+                }
+                KeyCode::Tab | KeyCode::BackTab => {
+                    code = KeyCode::BackTab;
+                    modifiers.remove(KeyModifiers::SHIFT);
+                }
+                _ => {}
+            }
+        }
+        Self {
+            code,
+            modifiers,
+            kind: self.kind,
+        }
+    }
+
+    /// A press binding also fires on auto-repeat, the way terminals without the kitty protocol
+    /// report a held key. `repeat+` and `release+` bindings only match their own kind.
     pub fn matches(self, event: KeyEvent) -> bool {
-        // Crossterm can set extra modifier bits; we want exact matches for now.
-        event.code == self.code && event.modifiers == self.modifiers
+        let spec = self.normalized();
+        let event = Self::from_event(event);
+        spec.code == event.code
+            && spec.modifiers == event.modifiers
+            && (spec.kind == event.kind
+                || (spec.kind == KeyEventKind::Press && event.kind == KeyEventKind::Repeat))
+    }
+}
+
+/// Uppercases a letter when it has a single-character uppercase form; other characters are
+/// already the shifted symbol the terminal produced.
+fn shifted_char(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(u), None) => u,
+        _ => c,
     }
 }
 
+/// One binding: a single key (`"t"`) or a space-separated sequence pressed in order
+/// (`"g g"`, `"ctrl+x ctrl+s"`). Never empty.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeySequence {
+    keys: Vec<KeySpec>,
+}
+
+impl KeySequence {
+    /// The keys pressed in order, e.g. as captured in the Keys panel. `None` when empty.
+    pub fn from_keys(keys: Vec<KeySpec>) -> Option<Self> {
+        (!keys.is_empty()).then(|| Self {
+            keys: keys.into_iter().map(KeySpec::normalized).collect(),
+        })
+    }
+
+    pub fn keys(&self) -> &[KeySpec] {
+        &self.keys
+    }
+
+    /// True when `events` is exactly this sequence.
+    pub fn matches(&self, events: &[KeyEvent]) -> bool {
+        self.keys.len() == events.len() && self.starts_with(events)
+    }
+
+    /// True when `events` is a proper prefix of this sequence, i.e. more keys could complete it.
+    pub fn continues(&self, events: &[KeyEvent]) -> bool {
+        self.keys.len() > events.len() && self.starts_with(events)
+    }
+
+    fn starts_with(&self, events: &[KeyEvent]) -> bool {
+        self.keys
+            .iter()
+            .zip(events)
+            .all(|(key, event)| key.matches(*event))
+    }
+
+    fn is_prefix_of(&self, other: &KeySequence) -> bool {
+        self.keys.len() < other.keys.len() && other.keys.starts_with(&self.keys)
+    }
+}
+
+impl From<KeySpec> for KeySequence {
+    fn from(spec: KeySpec) -> Self {
+        Self {
+            keys: vec![spec.normalized()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    CycleTheme,
+    NextPanel,
+    PrevPanel,
+    ListUp,
+    ListDown,
+    ToggleHighContrast,
+    ToggleColor,
+    ToggleReducedMotion,
+    ToggleHelp,
+    Quit,
+}
+
+impl KeyAction {
+    pub const ALL: [KeyAction; 10] = [
+        KeyAction::CycleTheme,
+        KeyAction::NextPanel,
+        KeyAction::PrevPanel,
+        KeyAction::ListUp,
+        KeyAction::ListDown,
+        KeyAction::ToggleHighContrast,
+        KeyAction::ToggleColor,
+        KeyAction::ToggleReducedMotion,
+        KeyAction::ToggleHelp,
+        KeyAction::Quit,
+    ];
+
+    /// The `[keys]` config name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            KeyAction::CycleTheme => "cycle_theme",
+            KeyAction::NextPanel => "next_panel",
+            KeyAction::PrevPanel => "prev_panel",
+            KeyAction::ListUp => "list_up",
+            KeyAction::ListDown => "list_down",
+            KeyAction::ToggleHighContrast => "toggle_high_contrast",
+            KeyAction::ToggleColor => "toggle_color",
+            KeyAction::ToggleReducedMotion => "toggle_reduced_motion",
+            KeyAction::ToggleHelp => "toggle_help",
+            KeyAction::Quit => "quit",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.as_str() == value.trim())
+    }
+
+    /// The context the flat `[keys]` table (and each preset) binds this action in.
+    pub fn home_context(self) -> KeyContext {
+        match self {
+            KeyAction::ListUp | KeyAction::ListDown => KeyContext::List,
+            _ => KeyContext::Global,
+        }
+    }
+
+    /// Human-readable label for `keys` output.
+    pub fn description(self) -> &'static str {
+        match self {
+            KeyAction::CycleTheme => "cycle theme",
+            KeyAction::NextPanel => "next panel",
+            KeyAction::PrevPanel => "previous panel",
+            KeyAction::ListUp => "list up",
+            KeyAction::ListDown => "list down",
+            KeyAction::ToggleHighContrast => "toggle high contrast",
+            KeyAction::ToggleColor => "toggle color",
+            KeyAction::ToggleReducedMotion => "toggle reduced motion",
+            KeyAction::ToggleHelp => "toggle help",
+            KeyAction::Quit => "quit",
+        }
+    }
+
+    /// The section of generated key reference cards the action is listed under.
+    pub fn category(self) -> KeyCategory {
+        match self {
+            KeyAction::NextPanel
+            | KeyAction::PrevPanel
+            | KeyAction::ListUp
+            | KeyAction::ListDown => KeyCategory::Navigation,
+            KeyAction::CycleTheme
+            | KeyAction::ToggleHighContrast
+            | KeyAction::ToggleColor
+            | KeyAction::ToggleReducedMotion => KeyCategory::Display,
+            KeyAction::ToggleHelp | KeyAction::Quit => KeyCategory::General,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyCategory {
+    Navigation,
+    Display,
+    General,
+}
+
+impl KeyCategory {
+    pub const ALL: [KeyCategory; 3] = [
+        KeyCategory::Navigation,
+        KeyCategory::Display,
+        KeyCategory::General,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            KeyCategory::Navigation => "Navigation",
+            KeyCategory::Display => "Display",
+            KeyCategory::General => "General",
+        }
+    }
+}
+
+/// Where a binding is active. The demo resolves keys from the innermost active context outward:
+/// the help overlay (when open), then the current panel, then global.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyContext {
+    Global,
+    Overview,
+    List,
+    /// The Keys panel (binding editor).
+    Editor,
+    Help,
+}
+
+impl KeyContext {
+    /// The `[keys.<context>]` table name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            KeyContext::Global => "global",
+            KeyContext::Overview => "overview",
+            KeyContext::List => "list",
+            KeyContext::Editor => "editor",
+            KeyContext::Help => "help",
+        }
+    }
+
+    /// Contexts that can be active underneath this one. The help overlay opens over any panel.
+    pub fn ancestors(self) -> &'static [KeyContext] {
+        match self {
+            KeyContext::Global => &[],
+            KeyContext::Overview | KeyContext::List | KeyContext::Editor => &[KeyContext::Global],
+            KeyContext::Help => &[
+                KeyContext::Overview,
+                KeyContext::List,
+                KeyContext::Editor,
+                KeyContext::Global,
+            ],
+        }
+    }
+
+    /// True when bindings in both contexts can be active at once.
+    fn overlaps(self, other: KeyContext) -> bool {
+        self == other || self.ancestors().contains(&other) || other.ancestors().contains(&self)
+    }
+}
+
+/// Bindings for `action` in a context other than its home, from `[keys.<context>]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopedBinding {
+    pub context: KeyContext,
+    pub action: KeyAction,
+    pub keys: Vec<KeySequence>,
+}
+
+/// Something `KeyBindings::problems` rejects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeymapProblem {
+    pub message: String,
+    /// The bindings involved, by context and action, so a caller can point at whatever set them.
+    pub bindings: Vec<(KeyContext, KeyAction)>,
+}
+
+/// Result of feeding the keys pressed so far to [`KeyBindings::match_sequence`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceMatch {
+    Action(KeyAction),
+    /// The keys are the start of at least one longer binding; wait for more.
+    Pending,
+    NoMatch,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct KeyBindings {
-    pub cycle_theme: Vec<KeySpec>,
+    pub cycle_theme: Vec<KeySequence>,
     /// Switch to the next demo panel (tabs).
-    pub next_panel: Vec<KeySpec>,
+    pub next_panel: Vec<KeySequence>,
     /// Switch to the previous demo panel (tabs).
-    pub prev_panel: Vec<KeySpec>,
+    pub prev_panel: Vec<KeySequence>,
     /// Move selection up in list-style demos.
-    pub list_up: Vec<KeySpec>,
+    pub list_up: Vec<KeySequence>,
     /// Move selection down in list-style demos.
-    pub list_down: Vec<KeySpec>,
-    pub toggle_high_contrast: Vec<KeySpec>,
-    pub toggle_color: Vec<KeySpec>,
-    pub toggle_reduced_motion: Vec<KeySpec>,
-    pub toggle_help: Vec<KeySpec>,
+    pub list_down: Vec<KeySequence>,
+    pub toggle_high_contrast: Vec<KeySequence>,
+    pub toggle_color: Vec<KeySequence>,
+    pub toggle_reduced_motion: Vec<KeySequence>,
+    pub toggle_help: Vec<KeySequence>,
     /// Additional quit keys. `Esc` and `Ctrl+C` are treated as emergency quit regardless.
-    pub quit: Vec<KeySpec>,
+    pub quit: Vec<KeySequence>,
+    /// Bindings from `[keys.<context>]` for actions outside their home context.
+    pub scoped: Vec<ScopedBinding>,
+    /// How long a partially typed sequence waits for its next key.
+    pub sequence_timeout: Duration,
 }
 
 impl Default for KeyBindings {
     fn default() -> Self {
-        Self {
-            cycle_theme: vec![parse_key_spec("t").expect("default key spec")],
-            next_panel: vec![parse_key_spec("tab").expect("default key spec")],
-            prev_panel: vec![parse_key_spec("backtab").expect("default key spec")],
-            list_up: vec![parse_key_spec("up").expect("default key spec")],
-            list_down: vec![parse_key_spec("down").expect("default key spec")],
-            toggle_high_contrast: vec![parse_key_spec("h").expect("default key spec")],
-            toggle_color: vec![parse_key_spec("c").expect("default key spec")],
-            toggle_reduced_motion: vec![parse_key_spec("r").expect("default key spec")],
-            toggle_help: vec![parse_key_spec("?").expect("default key spec")],
-            quit: vec![parse_key_spec("q").expect("default key spec")],
-        }
+        Self::preset(KeymapPreset::Default)
     }
 }
 
 impl KeyBindings {
+    /// A complete keymap; config `[keys]` entries replace individual actions on top of it.
+    pub fn preset(preset: KeymapPreset) -> Self {
+        fn keys(specs: &[&str]) -> Vec<KeySequence> {
+            specs
+                .iter()
+                .map(|spec| parse_key_sequence(spec).expect("preset key spec"))
+                .collect()
+        }
+
+        let default = Self {
+            cycle_theme: keys(&["t"]),
+            next_panel: keys(&["tab"]),
+            prev_panel: keys(&["backtab"]),
+            list_up: keys(&["up"]),
+            list_down: keys(&["down"]),
+            toggle_high_contrast: keys(&["h"]),
+            toggle_color: keys(&["c"]),
+            toggle_reduced_motion: keys(&["r"]),
+            toggle_help: keys(&["?"]),
+            quit: keys(&["q"]),
+            scoped: Vec::new(),
+            sequence_timeout: DEFAULT_SEQUENCE_TIMEOUT,
+        };
+
+        match preset {
+            KeymapPreset::Default => default,
+            KeymapPreset::Vim => Self {
+                next_panel: keys(&["g t", "tab"]),
+                prev_panel: keys(&["g T", "backtab"]),
+                list_up: keys(&["k", "up"]),
+                list_down: keys(&["j", "down"]),
+                quit: keys(&["q", ": q"]),
+                ..default
+            },
+            // `ctrl+x ctrl+c` needs no binding: `ctrl+c` always quits.
+            KeymapPreset::Emacs => Self {
+                next_panel: keys(&["ctrl+x o", "tab"]),
+                prev_panel: keys(&["ctrl+x O", "backtab"]),
+                list_up: keys(&["ctrl+p", "up"]),
+                list_down: keys(&["ctrl+n", "down"]),
+                ..default
+            },
+            KeymapPreset::ArrowsOnly => Self {
+                next_panel: keys(&["right"]),
+                prev_panel: keys(&["left"]),
+                ..default
+            },
+        }
+    }
+
+    pub fn keys(&self, action: KeyAction) -> &[KeySequence] {
+        match action {
+            KeyAction::CycleTheme => &self.cycle_theme,
+            KeyAction::NextPanel => &self.next_panel,
+            KeyAction::PrevPanel => &self.prev_panel,
+            KeyAction::ListUp => &self.list_up,
+            KeyAction::ListDown => &self.list_down,
+            KeyAction::ToggleHighContrast => &self.toggle_high_contrast,
+            KeyAction::ToggleColor => &self.toggle_color,
+            KeyAction::ToggleReducedMotion => &self.toggle_reduced_motion,
+            KeyAction::ToggleHelp => &self.toggle_help,
+            KeyAction::Quit => &self.quit,
+        }
+    }
+
+    pub fn keys_mut(&mut self, action: KeyAction) -> &mut Vec<KeySequence> {
+        match action {
+            KeyAction::CycleTheme => &mut self.cycle_theme,
+            KeyAction::NextPanel => &mut self.next_panel,
+            KeyAction::PrevPanel => &mut self.prev_panel,
+            KeyAction::ListUp => &mut self.list_up,
+            KeyAction::ListDown => &mut self.list_down,
+            KeyAction::ToggleHighContrast => &mut self.toggle_high_contrast,
+            KeyAction::ToggleColor => &mut self.toggle_color,
+            KeyAction::ToggleReducedMotion => &mut self.toggle_reduced_motion,
+            KeyAction::ToggleHelp => &mut self.toggle_help,
+            KeyAction::Quit => &mut self.quit,
+        }
+    }
+
+    /// The keys for `action` in `context`: its own list in its home context, otherwise its
+    /// `[keys.<context>]` entry (empty when there is none).
+    pub fn context_keys(&self, context: KeyContext, action: KeyAction) -> &[KeySequence] {
+        if context == action.home_context() {
+            return self.keys(action);
+        }
+        self.scoped
+            .iter()
+            .find(|binding| binding.context == context && binding.action == action)
+            .map_or(&[], |binding| binding.keys.as_slice())
+    }
+
+    pub fn context_keys_mut(
+        &mut self,
+        context: KeyContext,
+        action: KeyAction,
+    ) -> &mut Vec<KeySequence> {
+        if context == action.home_context() {
+            return self.keys_mut(action);
+        }
+        let index = match self
+            .scoped
+            .iter()
+            .position(|binding| binding.context == context && binding.action == action)
+        {
+            Some(index) => index,
+            None => {
+                self.scoped.push(ScopedBinding {
+                    context,
+                    action,
+                    keys: Vec::new(),
+                });
+                self.scoped.len() - 1
+            }
+        };
+        &mut self.scoped[index].keys
+    }
+
+    /// The first problem `problems` finds, as an error.
     pub fn validate(&self) -> Result<()> {
-        fn ensure_non_empty(name: &str, keys: &[KeySpec]) -> Result<()> {
-            if keys.is_empty() {
-                bail!("key binding '{}' must not be empty", name);
+        match self.problems().into_iter().next() {
+            Some(problem) => Err(anyhow!(problem.message)),
+            None => Ok(()),
+        }
+    }
+
+    /// Every reason the keymap can't be used, in a stable order; empty when it is valid.
+    pub fn problems(&self) -> Vec<KeymapProblem> {
+        let mut problems = Vec::new();
+        let mut report = |message: String, bindings: Vec<(KeyContext, KeyAction)>| {
+            problems.push(KeymapProblem { message, bindings })
+        };
+        if self.sequence_timeout.is_zero() {
+            report(
+                "key sequence timeout must be greater than zero".to_string(),
+                Vec::new(),
+            );
+        }
+
+        for action in KeyAction::ALL {
+            if self.keys(action).is_empty() {
+                report(
+                    format!("key binding '{}' must not be empty", action.as_str()),
+                    vec![(action.home_context(), action)],
+                );
             }
-            Ok(())
-        }
-
-        ensure_non_empty("cycle_theme", &self.cycle_theme)?;
-        ensure_non_empty("next_panel", &self.next_panel)?;
-        ensure_non_empty("prev_panel", &self.prev_panel)?;
-        ensure_non_empty("list_up", &self.list_up)?;
-        ensure_non_empty("list_down", &self.list_down)?;
-        ensure_non_empty("toggle_high_contrast", &self.toggle_high_contrast)?;
-        ensure_non_empty("toggle_color", &self.toggle_color)?;
-        ensure_non_empty("toggle_reduced_motion", &self.toggle_reduced_motion)?;
-        ensure_non_empty("toggle_help", &self.toggle_help)?;
-        ensure_non_empty("quit", &self.quit)?;
-
-        // `Esc` and `Ctrl+C` are reserved as emergency quit. They should not appear in other bindings
-        // to avoid confusing UX in the help panel.
-        let reserved = [parse_key_spec("esc")?, parse_key_spec("ctrl+c")?];
-
-        let mut seen = HashSet::<KeySpec>::new();
-        for (name, keys) in [
-            ("cycle_theme", &self.cycle_theme),
-            ("next_panel", &self.next_panel),
-            ("prev_panel", &self.prev_panel),
-            ("list_up", &self.list_up),
-            ("list_down", &self.list_down),
-            ("toggle_high_contrast", &self.toggle_high_contrast),
-            ("toggle_color", &self.toggle_color),
-            ("toggle_reduced_motion", &self.toggle_reduced_motion),
-            ("toggle_help", &self.toggle_help),
-            ("quit", &self.quit),
-        ] {
-            for key in keys.iter().copied() {
-                if reserved.contains(&key) && name != "quit" {
-                    bail!(
-                        "key '{}' is reserved for quitting and cannot be used for '{}'",
-                        key_spec_display(key),
-                        name
+        }
+
+        // `Esc` and `Ctrl+C` are reserved as emergency quit (and `Esc` cancels a pending
+        // sequence). They may only appear as a single-key `quit` binding, to avoid confusing UX
+        // in the help panel.
+        let reserved = RESERVED_QUIT_KEYS.map(|spec| parse_key_spec(spec).expect("valid key spec"));
+
+        let all = self.entries();
+        for &(context, action, sequence) in &all {
+            let single_quit = action == KeyAction::Quit && sequence.keys.len() == 1;
+            let is_reserved = |key: &&KeySpec| {
+                reserved
+                    .iter()
+                    .any(|r| r.code == key.code && r.modifiers == key.modifiers)
+            };
+            if let Some(key) = sequence.keys.iter().find(is_reserved) {
+                if !single_quit {
+                    report(
+                        format!(
+                            "key '{}' is reserved for quitting and cannot be used in '{}' for '{}'",
+                            key_spec_display(*key),
+                            key_sequence_display(sequence),
+                            action.as_str()
+                        ),
+                        vec![(context, action)],
                     );
                 }
-                if !seen.insert(key) {
-                    bail!(
-                        "duplicate key binding '{}' used for multiple actions",
-                        key_spec_display(key)
+            }
+        }
+
+        // Only bindings that can be active at the same time conflict: a context with itself and
+        // its ancestors. Sibling contexts (overview, list) may reuse keys.
+        for (index, &(context, action, sequence)) in all.iter().enumerate() {
+            for (other_index, &(other_context, other_action, other)) in all.iter().enumerate() {
+                if !context.overlaps(other_context) {
+                    continue;
+                }
+                let bindings = vec![(context, action), (other_context, other_action)];
+                if other_index > index && sequence == other {
+                    if context == other_context {
+                        report(
+                            format!(
+                                "duplicate key binding '{}' used for multiple actions",
+                                key_sequence_display(sequence)
+                            ),
+                            bindings,
+                        );
+                    } else {
+                        report(
+                            format!(
+                                "key binding '{}' for {} conflicts with {}",
+                                key_sequence_display(sequence),
+                                binding_label(context, action),
+                                binding_label(other_context, other_action)
+                            ),
+                            bindings,
+                        );
+                    }
+                } else if sequence.is_prefix_of(other) {
+                    // A binding that is a prefix of another would either shadow it or fire only
+                    // after the sequence timeout; reject the ambiguity instead of picking one.
+                    report(
+                        format!(
+                            "key binding '{}' for {} is a prefix of '{}' for {}",
+                            key_sequence_display(sequence),
+                            binding_label(context, action),
+                            key_sequence_display(other),
+                            binding_label(other_context, other_action)
+                        ),
+                        bindings,
                     );
                 }
             }
         }
+        problems
+    }
 
-        Ok(())
+    /// Every binding with the context it lives in: each action's own list in its home context,
+    /// then the `[keys.<context>]` extras.
+    fn entries(&self) -> Vec<(KeyContext, KeyAction, &KeySequence)> {
+        let home = KeyAction::ALL.into_iter().flat_map(|action| {
+            self.keys(action)
+                .iter()
+                .map(move |sequence| (action.home_context(), action, sequence))
+        });
+        let scoped = self.scoped.iter().flat_map(|binding| {
+            binding
+                .keys
+                .iter()
+                .map(move |sequence| (binding.context, binding.action, sequence))
+        });
+        home.chain(scoped).collect()
     }
 
-    pub fn matches_any(keys: &[KeySpec], event: KeyEvent) -> bool {
-        keys.iter().copied().any(|k| k.matches(event))
+    /// Looks up the keys pressed so far in `contexts`, innermost first. Bindings that can be
+    /// active together are prefix-free (see [`Self::validate`]), so at most one of "complete"
+    /// and "pending" applies.
+    pub fn match_sequence(&self, events: &[KeyEvent], contexts: &[KeyContext]) -> SequenceMatch {
+        let entries = self.entries();
+        // Auto-repeat matches press bindings too; a `repeat+` binding for the key wins over them.
+        if events
+            .last()
+            .is_some_and(|event| event.kind == KeyEventKind::Repeat)
+        {
+            for &context in contexts {
+                for &(_, action, sequence) in entries.iter().filter(|entry| entry.0 == context) {
+                    let ends_in_repeat = sequence
+                        .keys
+                        .last()
+                        .is_some_and(|key| key.kind == KeyEventKind::Repeat);
+                    if ends_in_repeat && sequence.matches(events) {
+                        return SequenceMatch::Action(action);
+                    }
+                }
+            }
+        }
+
+        let mut pending = false;
+        for &context in contexts {
+            for &(_, action, sequence) in entries.iter().filter(|entry| entry.0 == context) {
+                if sequence.matches(events) {
+                    return SequenceMatch::Action(action);
+                }
+                pending |= sequence.continues(events);
+            }
+        }
+
+        if pending {
+            SequenceMatch::Pending
+        } else {
+            SequenceMatch::NoMatch
+        }
     }
 
     pub fn quit_label(&self) -> String {
@@ -120,7 +654,7 @@ impl KeyBindings {
         // Always advertise `esc` and `ctrl+c` as safe exit paths even if they're not set in config.
         let mut out = key_labels(&self.quit);
         let mut seen = out.iter().cloned().collect::<HashSet<String>>();
-        for label in ["esc", "ctrl+c"] {
+        for label in RESERVED_QUIT_KEYS {
             if seen.insert(label.to_string()) {
                 out.push(label.to_string());
             }
@@ -130,12 +664,21 @@ impl KeyBindings {
     }
 }
 
-pub fn key_labels(keys: &[KeySpec]) -> Vec<String> {
+/// `'list_down'`, or `'list_down' in [keys.global]` when bound outside its home context.
+fn binding_label(context: KeyContext, action: KeyAction) -> String {
+    if context == action.home_context() {
+        format!("'{}'", action.as_str())
+    } else {
+        format!("'{}' in [keys.{}]", action.as_str(), context.as_str())
+    }
+}
+
+pub fn key_labels(keys: &[KeySequence]) -> Vec<String> {
     let mut out = Vec::<String>::new();
     let mut seen = HashSet::<String>::new();
 
-    for &k in keys {
-        let label = key_spec_display(k);
+    for k in keys {
+        let label = key_sequence_display(k);
         if seen.insert(label.clone()) {
             out.push(label);
         }
@@ -144,22 +687,134 @@ pub fn key_labels(keys: &[KeySpec]) -> Vec<String> {
     out
 }
 
+/// Parses a binding: one key spec, or several separated by whitespace for a sequence.
+pub fn parse_key_sequence(value: &str) -> Result<KeySequence> {
+    let keys = value
+        .split_whitespace()
+        .map(parse_key_spec)
+        .collect::<Result<Vec<_>>>()?;
+    if keys.is_empty() {
+        bail!("empty key spec");
+    }
+    Ok(KeySequence { keys })
+}
+
+/// Names for punctuation keys, so specs stay readable and `+` can be bound with modifiers
+/// (`ctrl+plus`). Any other single character can be written as itself.
+const NAMED_CHARS: [(&str, char); 14] = [
+    ("space", ' '),
+    ("plus", '+'),
+    ("minus", '-'),
+    ("equal", '='),
+    ("comma", ','),
+    ("period", '.'),
+    ("slash", '/'),
+    ("backslash", '\\'),
+    ("semicolon", ';'),
+    ("quote", '\''),
+    ("backquote", '`'),
+    ("lbracket", '['),
+    ("rbracket", ']'),
+    ("hash", '#'),
+];
+
+/// Key names [`parse_key_spec`] accepts besides single characters, `f1`-`f24` and
+/// [`NAMED_CHARS`].
+const NAMED_KEYS: [&str; 21] = [
+    "esc",
+    "escape",
+    "enter",
+    "return",
+    "tab",
+    "backtab",
+    "backspace",
+    "insert",
+    "ins",
+    "delete",
+    "del",
+    "home",
+    "end",
+    "pageup",
+    "pgup",
+    "pagedown",
+    "pgdn",
+    "up",
+    "down",
+    "left",
+    "right",
+];
+
+const MODIFIER_NAMES: [&str; 7] = ["ctrl", "control", "alt", "shift", "super", "hyper", "meta"];
+
+/// `value` with misspelled key and modifier names corrected (`ctl+x` -> `ctrl+x`), when every
+/// fix is a likely typo and the result parses. `None` when there is nothing to suggest.
+pub fn suggest_key_spec(value: &str) -> Option<String> {
+    let key_words = NAMED_KEYS
+        .into_iter()
+        .chain(NAMED_CHARS.iter().map(|(name, _)| *name))
+        .collect::<Vec<_>>();
+    let modifier_words = MODIFIER_NAMES
+        .into_iter()
+        .chain(KIND_NAMES.iter().map(|(name, _)| *name))
+        .collect::<Vec<_>>();
+
+    let mut changed = false;
+    let mut keys = Vec::new();
+    for key in value.split_whitespace() {
+        let parts = key.split('+').collect::<Vec<_>>();
+        let mut fixed = Vec::with_capacity(parts.len());
+        for (index, part) in parts.iter().enumerate() {
+            let lower = part.to_ascii_lowercase();
+            let is_key = index + 1 == parts.len();
+            let words = if is_key { &key_words } else { &modifier_words };
+            let known = words.contains(&lower.as_str())
+                || (is_key && function_key_number(&lower).is_some());
+            match did_you_mean(part, words.iter().copied()) {
+                Some(word) if !known && part.chars().count() > 1 => {
+                    changed = true;
+                    fixed.push(word.to_string());
+                }
+                _ => fixed.push(part.to_string()),
+            }
+        }
+        keys.push(fixed.join("+"));
+    }
+    let suggestion = keys.join(" ");
+    (changed && parse_key_sequence(&suggestion).is_ok()).then_some(suggestion)
+}
+
 pub fn parse_key_spec(value: &str) -> Result<KeySpec> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
         bail!("empty key spec");
     }
+    if trimmed == "+" {
+        return Ok(KeySpec::new(KeyCode::Char('+'), KeyModifiers::empty()));
+    }
 
     let mut modifiers = KeyModifiers::empty();
+    let mut kind = KeyEventKind::Press;
     let parts: Vec<&str> = trimmed.split('+').map(|p| p.trim()).collect();
     let key_part = if parts.len() > 1 {
         let key = parts.last().copied().unwrap_or("");
+        if key.is_empty() {
+            bail!(
+                "missing key after '+' in '{}' (use 'plus' for the + key)",
+                trimmed
+            );
+        }
         for m in &parts[..parts.len() - 1] {
             match m.to_ascii_lowercase().as_str() {
                 "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
                 "alt" => modifiers |= KeyModifiers::ALT,
                 "shift" => modifiers |= KeyModifiers::SHIFT,
-                other => bail!("unsupported modifier '{}'", other),
+                "super" => modifiers |= KeyModifiers::SUPER,
+                "hyper" => modifiers |= KeyModifiers::HYPER,
+                "meta" => modifiers |= KeyModifiers::META,
+                other => match KIND_NAMES.iter().find(|(name, _)| *name == other) {
+                    Some(&(_, named)) => kind = named,
+                    None => bail!("unsupported modifier '{}'", other),
+                },
             }
         }
         key
@@ -173,45 +828,99 @@ pub fn parse_key_spec(value: &str) -> Result<KeySpec> {
         "enter" | "return" => KeyCode::Enter,
         "tab" => KeyCode::Tab,
         "backtab" => KeyCode::BackTab,
-        "space" => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        "insert" | "ins" => KeyCode::Insert,
+        "delete" | "del" => KeyCode::Delete,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" | "pgup" => KeyCode::PageUp,
+        "pagedown" | "pgdn" => KeyCode::PageDown,
         "up" => KeyCode::Up,
         "down" => KeyCode::Down,
         "left" => KeyCode::Left,
         "right" => KeyCode::Right,
-        _ => {
-            // Accept a single Unicode scalar as a char key (e.g. "t" or "?").
-            let mut chars = key_part.chars();
-            let ch = chars.next().ok_or_else(|| anyhow!("empty key spec"))?;
-            if chars.next().is_some() {
-                bail!("unsupported multi-character key spec '{}'", key_part);
+        name => {
+            if let Some(&(_, ch)) = NAMED_CHARS.iter().find(|(named, _)| *named == name) {
+                KeyCode::Char(ch)
+            } else if let Some(number) = function_key_number(name) {
+                if !(1..=24).contains(&number) {
+                    bail!("unsupported function key '{}' (use f1-f24)", key_part);
+                }
+                KeyCode::F(number)
+            } else {
+                // Accept a single Unicode scalar as a char key (e.g. "t" or "?").
+                let mut chars = key_part.chars();
+                let ch = chars.next().ok_or_else(|| anyhow!("empty key spec"))?;
+                if chars.next().is_some() {
+                    bail!("unsupported multi-character key spec '{}'", key_part);
+                }
+                KeyCode::Char(ch)
             }
-            KeyCode::Char(ch)
         }
     };
 
-    Ok(KeySpec { code, modifiers })
+    Ok(KeySpec {
+        code,
+        modifiers,
+        kind,
+    }
+    .normalized())
 }
 
+/// `f1` -> 1; `None` for anything that isn't `f` followed by digits.
+fn function_key_number(name: &str) -> Option<u8> {
+    let digits = name.strip_prefix('f')?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(digits.parse().unwrap_or(u8::MAX))
+}
+
+/// Formats a key the way [`parse_key_spec`] reads it back.
 pub fn key_spec_display(spec: KeySpec) -> String {
     let mut out = String::new();
+    if let Some((name, _)) = KIND_NAMES.iter().find(|(_, kind)| *kind == spec.kind) {
+        out.push_str(name);
+        out.push('+');
+    }
     if spec.modifiers.contains(KeyModifiers::CONTROL) {
         out.push_str("ctrl+");
     }
     if spec.modifiers.contains(KeyModifiers::ALT) {
         out.push_str("alt+");
     }
-    if spec.modifiers.contains(KeyModifiers::SHIFT) {
+    // The backtab label below already says "shift".
+    if spec.modifiers.contains(KeyModifiers::SHIFT) && spec.code != KeyCode::BackTab {
         out.push_str("shift+");
     }
+    for (modifier, name) in [
+        (KeyModifiers::SUPER, "super+"),
+        (KeyModifiers::HYPER, "hyper+"),
+        (KeyModifiers::META, "meta+"),
+    ] {
+        if spec.modifiers.contains(modifier) {
+            out.push_str(name);
+        }
+    }
 
     match spec.code {
+        // Only the characters that would break parsing get their names.
         KeyCode::Char(' ') => out.push_str("space"),
+        KeyCode::Char('+') => out.push_str("plus"),
         KeyCode::Char(c) => out.push(c),
         KeyCode::Esc => out.push_str("esc"),
         KeyCode::Enter => out.push_str("enter"),
         KeyCode::Tab => out.push_str("tab"),
-        // User-facing label; config uses "backtab".
+        // User-facing label; config accepts both "shift+tab" and "backtab".
         KeyCode::BackTab => out.push_str("shift+tab"),
+        KeyCode::Backspace => out.push_str("backspace"),
+        KeyCode::Insert => out.push_str("insert"),
+        KeyCode::Delete => out.push_str("delete"),
+        KeyCode::Home => out.push_str("home"),
+        KeyCode::End => out.push_str("end"),
+        KeyCode::PageUp => out.push_str("pageup"),
+        KeyCode::PageDown => out.push_str("pagedown"),
+        KeyCode::F(n) => out.push_str(&format!("f{}", n)),
         KeyCode::Up => out.push_str("up"),
         KeyCode::Down => out.push_str("down"),
         KeyCode::Left => out.push_str("left"),
@@ -222,29 +931,43 @@ pub fn key_spec_display(spec: KeySpec) -> String {
     out
 }
 
-pub fn key_list_display(keys: &[KeySpec]) -> String {
+pub fn key_sequence_display(sequence: &KeySequence) -> String {
+    sequence
+        .keys
+        .iter()
+        .map(|key| key_spec_display(*key))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Label for a partially typed sequence, e.g. `ctrl+x` while waiting for `ctrl+s`.
+pub fn key_events_display(events: &[KeyEvent]) -> String {
+    events
+        .iter()
+        .map(|event| key_spec_display(KeySpec::from_event(*event)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn key_list_display(keys: &[KeySequence]) -> String {
     key_labels(keys).join("/")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crossterm::event::KeyEventState;
+    use proptest::prelude::*;
 
     #[test]
     fn parse_single_chars() {
         assert_eq!(
             parse_key_spec("t").unwrap(),
-            KeySpec {
-                code: KeyCode::Char('t'),
-                modifiers: KeyModifiers::empty()
-            }
+            KeySpec::new(KeyCode::Char('t'), KeyModifiers::empty())
         );
         assert_eq!(
             parse_key_spec("?").unwrap(),
-            KeySpec {
-                code: KeyCode::Char('?'),
-                modifiers: KeyModifiers::empty()
-            }
+            KeySpec::new(KeyCode::Char('?'), KeyModifiers::empty())
         );
     }
 
@@ -252,17 +975,11 @@ mod tests {
     fn parse_special_keys_and_modifiers() {
         assert_eq!(
             parse_key_spec("esc").unwrap(),
-            KeySpec {
-                code: KeyCode::Esc,
-                modifiers: KeyModifiers::empty()
-            }
+            KeySpec::new(KeyCode::Esc, KeyModifiers::empty())
         );
         assert_eq!(
             parse_key_spec("ctrl+c").unwrap(),
-            KeySpec {
-                code: KeyCode::Char('c'),
-                modifiers: KeyModifiers::CONTROL
-            }
+            KeySpec::new(KeyCode::Char('c'), KeyModifiers::CONTROL)
         );
     }
 
@@ -271,12 +988,27 @@ mod tests {
         KeyBindings::default().validate().unwrap();
     }
 
+    #[test]
+    fn every_preset_is_valid() {
+        for preset in KeymapPreset::ALL {
+            KeyBindings::preset(preset)
+                .validate()
+                .unwrap_or_else(|err| panic!("preset {}: {}", preset.as_str(), err));
+        }
+
+        let vim = KeyBindings::preset(KeymapPreset::Vim);
+        assert_eq!(key_list_display(&vim.list_down), "j/down");
+        assert_eq!(key_list_display(&vim.prev_panel), "g T/shift+tab");
+        let emacs = KeyBindings::preset(KeymapPreset::Emacs);
+        assert_eq!(key_list_display(&emacs.list_up), "ctrl+p/up");
+    }
+
     #[test]
     fn key_labels_are_deduped_and_stable() {
         let keys = vec![
-            parse_key_spec("x").unwrap(),
-            parse_key_spec("x").unwrap(),
-            parse_key_spec("backtab").unwrap(),
+            parse_key_sequence("x").unwrap(),
+            parse_key_sequence("x").unwrap(),
+            parse_key_sequence("backtab").unwrap(),
         ];
 
         assert_eq!(
@@ -285,4 +1017,477 @@ mod tests {
         );
         assert_eq!(key_list_display(&keys), "x/shift+tab");
     }
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn parse_sequences_and_display_them() {
+        let sequence = parse_key_sequence("ctrl+x  ctrl+s").unwrap();
+        assert_eq!(
+            sequence.keys,
+            vec![
+                parse_key_spec("ctrl+x").unwrap(),
+                parse_key_spec("ctrl+s").unwrap()
+            ]
+        );
+        assert_eq!(key_sequence_display(&sequence), "ctrl+x ctrl+s");
+        assert!(parse_key_sequence("  ").is_err());
+        assert!(parse_key_sequence("g bogus+g").is_err());
+    }
+
+    const GLOBAL: &[KeyContext] = &[KeyContext::Global];
+
+    #[test]
+    fn match_sequence_waits_for_the_rest_of_a_binding() {
+        let keymap = KeyBindings {
+            cycle_theme: vec![parse_key_sequence("g t").unwrap()],
+            ..KeyBindings::default()
+        };
+        let g = press(KeyCode::Char('g'), KeyModifiers::empty());
+        let t = press(KeyCode::Char('t'), KeyModifiers::empty());
+
+        assert_eq!(keymap.match_sequence(&[g], GLOBAL), SequenceMatch::Pending);
+        assert_eq!(
+            keymap.match_sequence(&[g, t], GLOBAL),
+            SequenceMatch::Action(KeyAction::CycleTheme)
+        );
+        assert_eq!(keymap.match_sequence(&[t], GLOBAL), SequenceMatch::NoMatch);
+        assert_eq!(
+            keymap.match_sequence(&[press(KeyCode::Char('?'), KeyModifiers::empty())], GLOBAL),
+            SequenceMatch::Action(KeyAction::ToggleHelp)
+        );
+    }
+
+    #[test]
+    fn match_sequence_resolves_innermost_context_first() {
+        let keymap = KeyBindings {
+            list_down: vec![parse_key_sequence("j").unwrap()],
+            scoped: vec![
+                ScopedBinding {
+                    context: KeyContext::Overview,
+                    action: KeyAction::CycleTheme,
+                    keys: vec![parse_key_sequence("j").unwrap()],
+                },
+                ScopedBinding {
+                    context: KeyContext::Help,
+                    action: KeyAction::ToggleHelp,
+                    keys: vec![parse_key_sequence("x").unwrap()],
+                },
+            ],
+            ..KeyBindings::default()
+        };
+        keymap.validate().expect("sibling contexts may share keys");
+        let j = [press(KeyCode::Char('j'), KeyModifiers::empty())];
+        let x = [press(KeyCode::Char('x'), KeyModifiers::empty())];
+
+        let overview = [KeyContext::Overview, KeyContext::Global];
+        let list = [KeyContext::List, KeyContext::Global];
+        let help = [KeyContext::Help, KeyContext::List, KeyContext::Global];
+        assert_eq!(
+            keymap.match_sequence(&j, &overview),
+            SequenceMatch::Action(KeyAction::CycleTheme)
+        );
+        assert_eq!(
+            keymap.match_sequence(&j, &list),
+            SequenceMatch::Action(KeyAction::ListDown)
+        );
+        assert_eq!(keymap.match_sequence(&j, GLOBAL), SequenceMatch::NoMatch);
+        assert_eq!(keymap.match_sequence(&x, &list), SequenceMatch::NoMatch);
+        assert_eq!(
+            keymap.match_sequence(&x, &help),
+            SequenceMatch::Action(KeyAction::ToggleHelp)
+        );
+
+        // The help overlay sits over both panels, so it conflicts with either.
+        let keymap = KeyBindings {
+            scoped: vec![ScopedBinding {
+                context: KeyContext::Help,
+                action: KeyAction::CycleTheme,
+                keys: vec![parse_key_sequence("up").unwrap()],
+            }],
+            ..KeyBindings::default()
+        };
+        let error = keymap.validate().expect_err("help conflicts with list");
+        assert_eq!(
+            error.to_string(),
+            "key binding 'up' for 'list_up' conflicts with 'cycle_theme' in [keys.help]"
+        );
+    }
+
+    #[test]
+    fn validate_rejects_prefix_conflicts() {
+        let keymap = KeyBindings {
+            cycle_theme: vec![parse_key_sequence("g").unwrap()],
+            toggle_help: vec![parse_key_sequence("g g").unwrap()],
+            ..KeyBindings::default()
+        };
+        let error = keymap.validate().expect_err("prefix conflict must fail");
+        assert_eq!(
+            error.to_string(),
+            "key binding 'g' for 'cycle_theme' is a prefix of 'g g' for 'toggle_help'"
+        );
+
+        let keymap = KeyBindings {
+            quit: vec![parse_key_sequence("g esc").unwrap()],
+            ..KeyBindings::default()
+        };
+        let error = keymap
+            .validate()
+            .expect_err("esc inside a sequence must fail");
+        assert!(error.to_string().contains("reserved for quitting"));
+    }
+
+    #[test]
+    fn parse_named_keys() {
+        for (spec, code) in [
+            ("f1", KeyCode::F(1)),
+            ("F24", KeyCode::F(24)),
+            ("home", KeyCode::Home),
+            ("end", KeyCode::End),
+            ("pageup", KeyCode::PageUp),
+            ("pgdn", KeyCode::PageDown),
+            ("insert", KeyCode::Insert),
+            ("del", KeyCode::Delete),
+            ("backspace", KeyCode::Backspace),
+            ("plus", KeyCode::Char('+')),
+            ("+", KeyCode::Char('+')),
+            ("minus", KeyCode::Char('-')),
+            ("comma", KeyCode::Char(',')),
+            ("f", KeyCode::Char('f')),
+        ] {
+            assert_eq!(parse_key_spec(spec).unwrap().code, code, "spec {}", spec);
+        }
+
+        assert_eq!(
+            parse_key_spec("ctrl+plus").unwrap(),
+            KeySpec::new(KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            key_spec_display(parse_key_spec("ctrl+plus").unwrap()),
+            "ctrl+plus"
+        );
+        assert_eq!(
+            parse_key_spec("shift+tab").unwrap(),
+            parse_key_spec("backtab").unwrap()
+        );
+        assert!(parse_key_spec("f25").is_err());
+        assert!(parse_key_spec("f0").is_err());
+        assert!(parse_key_spec("ctrl++").is_err());
+    }
+
+    fn key_code_strategy() -> impl Strategy<Value = KeyCode> {
+        prop_oneof![
+            any::<char>()
+                .prop_filter("printable", |c| !c.is_whitespace() && !c.is_control())
+                .prop_map(KeyCode::Char),
+            Just(KeyCode::Char(' ')),
+            (1u8..=24).prop_map(KeyCode::F),
+            prop::sample::select(vec![
+                KeyCode::Esc,
+                KeyCode::Enter,
+                KeyCode::Tab,
+                KeyCode::BackTab,
+                KeyCode::Backspace,
+                KeyCode::Insert,
+                KeyCode::Delete,
+                KeyCode::Home,
+                KeyCode::End,
+                KeyCode::PageUp,
+                KeyCode::PageDown,
+                KeyCode::Up,
+                KeyCode::Down,
+                KeyCode::Left,
+                KeyCode::Right,
+            ]),
+        ]
+    }
+
+    fn key_spec_strategy() -> impl Strategy<Value = KeySpec> {
+        let kind = prop::sample::select(vec![
+            KeyEventKind::Press,
+            KeyEventKind::Repeat,
+            KeyEventKind::Release,
+        ]);
+        (key_code_strategy(), 0u8..64, kind).prop_map(|(code, bits, kind)| {
+            let mut modifiers = KeyModifiers::empty();
+            modifiers.set(KeyModifiers::CONTROL, bits & 1 != 0);
+            modifiers.set(KeyModifiers::ALT, bits & 2 != 0);
+            modifiers.set(KeyModifiers::SHIFT, bits & 4 != 0);
+            modifiers.set(KeyModifiers::SUPER, bits & 8 != 0);
+            modifiers.set(KeyModifiers::HYPER, bits & 16 != 0);
+            modifiers.set(KeyModifiers::META, bits & 32 != 0);
+            KeySpec {
+                code,
+                modifiers,
+                kind,
+            }
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn key_spec_display_round_trips(spec in key_spec_strategy()) {
+            // Display keeps whatever it is given; parsing yields the canonical form.
+            let label = key_spec_display(spec);
+            prop_assert_eq!(parse_key_spec(&label).unwrap(), spec.normalized(), "label {}", label);
+        }
+
+        #[test]
+        fn key_sequence_display_round_trips(specs in prop::collection::vec(key_spec_strategy(), 1..4)) {
+            let sequence = KeySequence {
+                keys: specs.into_iter().map(KeySpec::normalized).collect(),
+            };
+            let label = key_sequence_display(&sequence);
+            prop_assert_eq!(parse_key_sequence(&label).unwrap(), sequence, "label {}", label);
+        }
+    }
+
+    #[test]
+    fn matches_events_as_common_terminals_report_them() {
+        let event = |code, modifiers, state| {
+            KeyEvent::new_with_kind_and_state(code, modifiers, KeyEventKind::Press, state)
+        };
+        let none = KeyModifiers::empty();
+        let shift = KeyModifiers::SHIFT;
+        let ctrl = KeyModifiers::CONTROL;
+        let no_state = KeyEventState::empty();
+
+        // (terminal behavior, binding, reported event, expected match)
+        let cases = [
+            (
+                "xterm ?",
+                "?",
+                event(KeyCode::Char('?'), none, no_state),
+                true,
+            ),
+            (
+                "windows console ?",
+                "?",
+                event(KeyCode::Char('?'), shift, no_state),
+                true,
+            ),
+            (
+                "binding shift+?",
+                "shift+?",
+                event(KeyCode::Char('?'), none, no_state),
+                true,
+            ),
+            (
+                "xterm T",
+                "T",
+                event(KeyCode::Char('T'), shift, no_state),
+                true,
+            ),
+            (
+                "binding shift+t",
+                "shift+t",
+                event(KeyCode::Char('T'), shift, no_state),
+                true,
+            ),
+            (
+                "kitty shift+t",
+                "shift+t",
+                event(KeyCode::Char('t'), shift, no_state),
+                true,
+            ),
+            (
+                "caps lock T",
+                "T",
+                event(KeyCode::Char('T'), none, KeyEventState::CAPS_LOCK),
+                true,
+            ),
+            (
+                "lowercase vs shifted",
+                "t",
+                event(KeyCode::Char('T'), shift, no_state),
+                false,
+            ),
+            (
+                "kitty num lock",
+                "t",
+                event(KeyCode::Char('t'), none, KeyEventState::NUM_LOCK),
+                true,
+            ),
+            (
+                "kitty super+t",
+                "super+t",
+                event(KeyCode::Char('t'), KeyModifiers::SUPER, no_state),
+                true,
+            ),
+            (
+                "super still required",
+                "t",
+                event(KeyCode::Char('t'), KeyModifiers::SUPER, no_state),
+                false,
+            ),
+            (
+                "kitty ctrl+i",
+                "ctrl+i",
+                event(KeyCode::Char('i'), ctrl, no_state),
+                true,
+            ),
+            (
+                "kitty tab is not ctrl+i",
+                "ctrl+i",
+                event(KeyCode::Tab, none, no_state),
+                false,
+            ),
+            (
+                "kitty ctrl+shift+t",
+                "ctrl+T",
+                event(KeyCode::Char('t'), ctrl | shift, no_state),
+                true,
+            ),
+            (
+                "ctrl still required",
+                "ctrl+t",
+                event(KeyCode::Char('t'), none, no_state),
+                false,
+            ),
+            (
+                "xterm shift+tab",
+                "backtab",
+                event(KeyCode::BackTab, shift, no_state),
+                true,
+            ),
+            (
+                "kitty shift+tab",
+                "shift+tab",
+                event(KeyCode::Tab, shift, no_state),
+                true,
+            ),
+            (
+                "plain tab",
+                "tab",
+                event(KeyCode::Tab, none, no_state),
+                true,
+            ),
+            (
+                "shift+arrow keeps shift",
+                "shift+up",
+                event(KeyCode::Up, shift, no_state),
+                true,
+            ),
+            (
+                "arrow without shift",
+                "shift+up",
+                event(KeyCode::Up, none, no_state),
+                false,
+            ),
+            (
+                "alt+x",
+                "alt+x",
+                event(KeyCode::Char('x'), KeyModifiers::ALT, no_state),
+                true,
+            ),
+        ];
+
+        for (terminal, binding, event, expected) in cases {
+            let spec = parse_key_spec(binding).unwrap();
+            assert_eq!(spec.matches(event), expected, "{}: '{}'", terminal, binding);
+        }
+    }
+
+    #[test]
+    fn event_kinds_and_kitty_modifiers_round_trip_and_match() {
+        let spec = parse_key_spec("release+ctrl+super+space").unwrap();
+        assert_eq!(spec.kind, KeyEventKind::Release);
+        assert_eq!(spec.modifiers, KeyModifiers::CONTROL | KeyModifiers::SUPER);
+        assert_eq!(key_spec_display(spec), "release+ctrl+super+space");
+        assert_eq!(
+            key_spec_display(parse_key_spec("Meta+Hyper+x").unwrap()),
+            "hyper+meta+x"
+        );
+
+        let event = |kind| KeyEvent::new_with_kind(KeyCode::Char('j'), KeyModifiers::empty(), kind);
+        let press = parse_key_spec("j").unwrap();
+        let repeat = parse_key_spec("repeat+j").unwrap();
+        let release = parse_key_spec("release+j").unwrap();
+        assert!(press.matches(event(KeyEventKind::Press)));
+        assert!(press.matches(event(KeyEventKind::Repeat)));
+        assert!(!press.matches(event(KeyEventKind::Release)));
+        assert!(!repeat.matches(event(KeyEventKind::Press)));
+        assert!(release.matches(event(KeyEventKind::Release)));
+
+        // `repeat+j` takes over auto-repeat from `j`; a plain press still runs `j`.
+        let keymap = KeyBindings {
+            list_down: vec![parse_key_sequence("j").unwrap()],
+            scoped: vec![ScopedBinding {
+                context: KeyContext::List,
+                action: KeyAction::CycleTheme,
+                keys: vec![parse_key_sequence("repeat+j").unwrap()],
+            }],
+            ..KeyBindings::default()
+        };
+        keymap.validate().unwrap();
+        let contexts = [KeyContext::List, KeyContext::Global];
+        assert_eq!(
+            keymap.match_sequence(&[event(KeyEventKind::Press)], &contexts),
+            SequenceMatch::Action(KeyAction::ListDown)
+        );
+        assert_eq!(
+            keymap.match_sequence(&[event(KeyEventKind::Repeat)], &contexts),
+            SequenceMatch::Action(KeyAction::CycleTheme)
+        );
+
+        let reserved = KeyBindings {
+            toggle_help: vec![parse_key_sequence("release+esc").unwrap()],
+            ..KeyBindings::default()
+        };
+        assert!(reserved.validate().is_err());
+    }
+
+    #[test]
+    fn problems_lists_every_conflict_with_the_bindings_involved() {
+        let keymap = KeyBindings {
+            cycle_theme: vec![parse_key_sequence("g").unwrap()],
+            toggle_help: vec![parse_key_sequence("g g").unwrap()],
+            toggle_color: vec![parse_key_sequence("q").unwrap()],
+            ..KeyBindings::default()
+        };
+        let problems = keymap.problems();
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert_eq!(
+            problems[0].bindings,
+            [
+                (KeyContext::Global, KeyAction::CycleTheme),
+                (KeyContext::Global, KeyAction::ToggleHelp)
+            ]
+        );
+        assert_eq!(
+            problems[1].message,
+            "duplicate key binding 'q' used for multiple actions"
+        );
+        assert_eq!(
+            keymap.validate().unwrap_err().to_string(),
+            problems[0].message
+        );
+    }
+
+    #[test]
+    fn shifted_spellings_are_duplicates() {
+        let keymap = KeyBindings {
+            cycle_theme: vec![parse_key_sequence("T").unwrap()],
+            toggle_color: vec![parse_key_sequence("shift+t").unwrap()],
+            ..KeyBindings::default()
+        };
+        let error = keymap.validate().expect_err("same key twice must fail");
+        assert_eq!(
+            error.to_string(),
+            "duplicate key binding 'T' used for multiple actions"
+        );
+    }
+
+    #[test]
+    fn misspelled_key_names_get_corrected_suggestions() {
+        assert_eq!(suggest_key_spec("ctl+x"), Some("ctrl+x".to_string()));
+        assert_eq!(suggest_key_spec("g pgaeup"), Some("g pageup".to_string()));
+        assert_eq!(
+            suggest_key_spec("shft+tab escap"),
+            Some("shift+tab escape".to_string())
+        );
+        assert_eq!(suggest_key_spec("ctrl+xy"), None);
+    }
 }
@@ -0,0 +1,425 @@
+//! Ergonomics checks for key bindings that are valid but likely to cause trouble.
+//!
+//! [`KeyBindings::validate`] rejects keymaps that can't work; this module flags ones that work
+//! in the demo but may not in the user's terminal, or are awkward to use. Nothing here is an
+//! error: `config lint` reports the findings and only fails with `--deny`.
+
+use crate::config::{ConfigLocation, ResolvedKeys};
+use crate::keys::{
+    key_sequence_display, key_spec_display, KeyAction, KeyContext, KeySequence, KeySpec,
+};
+use crossterm::event::{KeyCode, KeyEventKind, KeyModifiers};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    pub severity: Severity,
+    /// Stable rule name: `terminal-shortcut`, `unreportable-key`, `split-pair` or
+    /// `modifier-heavy`.
+    pub rule: &'static str,
+    pub context: KeyContext,
+    pub action: KeyAction,
+    /// The binding the finding is about; `None` when it concerns all of the action's bindings.
+    pub key: Option<String>,
+    pub message: String,
+    /// The config entry that set the binding; `None` for preset bindings.
+    pub location: Option<ConfigLocation>,
+}
+
+/// Shortcuts that terminals, shells or multiplexers commonly take for themselves.
+const TERMINAL_SHORTCUTS: [(char, &str); 6] = [
+    (
+        's',
+        "is XON/XOFF flow control; terminals that keep it enabled freeze output instead",
+    ),
+    ('q', "is XON/XOFF flow control (resume output)"),
+    (
+        'z',
+        "suspends the program in shells and wrappers that keep job control",
+    ),
+    ('\\', "sends SIGQUIT when the terminal isn't in raw mode"),
+    ('b', "is the default tmux prefix"),
+    ('a', "is the default GNU screen prefix"),
+];
+
+/// Actions that move in opposite directions and should sit on related keys.
+const PAIRS: [(KeyAction, KeyAction); 2] = [
+    (KeyAction::NextPanel, KeyAction::PrevPanel),
+    (KeyAction::ListDown, KeyAction::ListUp),
+];
+
+/// Character pairs that belong together by convention even though they aren't adjacent.
+const MNEMONIC_PAIRS: [(char, char); 6] = [
+    ('n', 'p'),
+    ('h', 'l'),
+    ('+', '-'),
+    ('<', '>'),
+    ('[', ']'),
+    ('(', ')'),
+];
+
+const QWERTY_ROWS: [&str; 4] = ["1234567890-=", "qwertyuiop[]", "asdfghjkl;'", "zxcvbnm,./"];
+
+/// Runs every check over the home bindings and `[keys.<context>]` extras.
+pub fn lint_keys(resolved: &ResolvedKeys) -> Vec<LintFinding> {
+    let keymap = &resolved.bindings;
+    let location = |context, action| {
+        resolved
+            .entry(context, action)
+            .map(|entry| entry.location.clone())
+    };
+
+    let mut bindings = KeyAction::ALL
+        .into_iter()
+        .map(|action| (action.home_context(), action, keymap.keys(action)))
+        .collect::<Vec<_>>();
+    bindings.extend(
+        keymap
+            .scoped
+            .iter()
+            .map(|binding| (binding.context, binding.action, binding.keys.as_slice())),
+    );
+
+    let mut out = Vec::new();
+    for &(context, action, sequences) in &bindings {
+        for sequence in sequences {
+            let label = key_sequence_display(sequence);
+            for &spec in sequence.keys() {
+                let mut issues = Vec::new();
+                if let Some(reason) = terminal_shortcut(spec) {
+                    issues.push((Severity::Warning, "terminal-shortcut", reason.to_string()));
+                }
+                if let Some((severity, reason)) = reporting_issue(spec) {
+                    issues.push((severity, "unreportable-key", reason));
+                }
+                for (severity, rule, reason) in issues {
+                    out.push(LintFinding {
+                        severity,
+                        rule,
+                        context,
+                        action,
+                        key: Some(label.clone()),
+                        message: format!(
+                            "'{}' for '{}': {} {}",
+                            label,
+                            action.as_str(),
+                            key_spec_display(spec),
+                            reason
+                        ),
+                        location: location(context, action),
+                    });
+                }
+            }
+        }
+
+        if !sequences.is_empty() && sequences.iter().all(is_modifier_heavy) {
+            out.push(LintFinding {
+                severity: Severity::Info,
+                rule: "modifier-heavy",
+                context,
+                action,
+                key: None,
+                message: format!(
+                    "'{}' is only bound to keys that need several modifiers at once; add a \
+                     simpler alternative",
+                    action.as_str()
+                ),
+                location: location(context, action),
+            });
+        }
+    }
+
+    for (forward, back) in PAIRS {
+        let (ahead, behind) = (keymap.keys(forward), keymap.keys(back));
+        let paired = ahead
+            .iter()
+            .any(|a| behind.iter().any(|b| sequences_paired(a, b)));
+        if !paired {
+            out.push(LintFinding {
+                severity: Severity::Warning,
+                rule: "split-pair",
+                context: forward.home_context(),
+                action: forward,
+                key: None,
+                message: format!(
+                    "'{}' and '{}' have no bindings on related keys (such as j/k, up/down or \
+                     tab/shift+tab), which makes the pair hard to remember",
+                    forward.as_str(),
+                    back.as_str()
+                ),
+                location: location(forward.home_context(), forward)
+                    .or_else(|| location(back.home_context(), back)),
+            });
+        }
+    }
+
+    // One finding per key, even when a sequence repeats it (`ctrl+b ctrl+b`).
+    out.dedup_by(|a, b| {
+        a.rule == b.rule
+            && a.context == b.context
+            && a.action == b.action
+            && a.key == b.key
+            && a.message == b.message
+    });
+    out
+}
+
+fn terminal_shortcut(spec: KeySpec) -> Option<&'static str> {
+    let KeyCode::Char(c) = spec.code else {
+        return None;
+    };
+    if spec.modifiers != KeyModifiers::CONTROL {
+        return None;
+    }
+    TERMINAL_SHORTCUTS
+        .iter()
+        .find(|(key, _)| *key == c)
+        .map(|(_, reason)| *reason)
+}
+
+/// Keys that only some terminals can send.
+fn reporting_issue(spec: KeySpec) -> Option<(Severity, String)> {
+    const KITTY_ONLY: &str = "is only reported by terminals with the kitty keyboard protocol";
+
+    if spec.kind != KeyEventKind::Press
+        || spec
+            .modifiers
+            .intersects(KeyModifiers::SUPER | KeyModifiers::HYPER | KeyModifiers::META)
+    {
+        return Some((Severity::Warning, KITTY_ONLY.to_string()));
+    }
+    if spec.modifiers.contains(KeyModifiers::CONTROL) {
+        if let KeyCode::Char(c) = spec.code {
+            let legacy = match c {
+                'h' => Some("backspace"),
+                'i' => Some("tab"),
+                'm' => Some("enter"),
+                '[' => Some("esc"),
+                _ => None,
+            };
+            if let Some(legacy) = legacy {
+                return Some((
+                    Severity::Warning,
+                    format!(
+                        "arrives as {} in terminals without the kitty keyboard protocol",
+                        legacy
+                    ),
+                ));
+            }
+            if c.is_uppercase() {
+                return Some((
+                    Severity::Warning,
+                    "can't be told apart from the lowercase key in most terminals".to_string(),
+                ));
+            }
+            if !c.is_ascii_lowercase() && c != ' ' {
+                return Some((
+                    Severity::Warning,
+                    "has no distinct code in most terminals".to_string(),
+                ));
+            }
+        }
+    }
+    if let KeyCode::F(n) = spec.code {
+        if n > 12 {
+            return Some((
+                Severity::Info,
+                "is missing from most keyboards and some terminals".to_string(),
+            ));
+        }
+    }
+    if spec.modifiers.contains(KeyModifiers::ALT) {
+        return Some((
+            Severity::Info,
+            "needs the Option key set to act as Meta in macOS terminals".to_string(),
+        ));
+    }
+    None
+}
+
+fn is_modifier_heavy(sequence: &KeySequence) -> bool {
+    sequence
+        .keys()
+        .iter()
+        .any(|spec| spec.modifiers.bits().count_ones() >= 2)
+}
+
+/// Two bindings belong together when they share everything but a last key that is related.
+fn sequences_paired(a: &KeySequence, b: &KeySequence) -> bool {
+    let (a, b) = (a.keys(), b.keys());
+    match (a.split_last(), b.split_last()) {
+        (Some((last_a, rest_a)), Some((last_b, rest_b))) => {
+            rest_a == rest_b && keys_paired(*last_a, *last_b)
+        }
+        _ => false,
+    }
+}
+
+fn keys_paired(a: KeySpec, b: KeySpec) -> bool {
+    let without_shift = |spec: KeySpec| spec.modifiers - KeyModifiers::SHIFT;
+    if without_shift(a) != without_shift(b) {
+        return false;
+    }
+    use KeyCode::*;
+    match (a.code, b.code) {
+        (Char(x), Char(y)) => {
+            let (x, y) = (x.to_ascii_lowercase(), y.to_ascii_lowercase());
+            x == y
+                || MNEMONIC_PAIRS
+                    .iter()
+                    .any(|&pair| pair == (x, y) || pair == (y, x))
+                || qwerty_distance(x, y).is_some_and(|distance| distance <= 2)
+        }
+        (F(x), F(y)) => x.abs_diff(y) == 1,
+        (x, y) => [
+            (Up, Down),
+            (Left, Right),
+            (PageUp, PageDown),
+            (Home, End),
+            (Tab, BackTab),
+        ]
+        .iter()
+        .any(|&pair| pair == (x, y) || pair == (y, x)),
+    }
+}
+
+/// Rows plus columns between two keys on a US QWERTY layout.
+fn qwerty_distance(a: char, b: char) -> Option<usize> {
+    let position = |c: char| {
+        QWERTY_ROWS
+            .iter()
+            .enumerate()
+            .find_map(|(row, keys)| keys.find(c).map(|col| (row, col)))
+    };
+    let ((row_a, col_a), (row_b, col_b)) = (position(a)?, position(b)?);
+    Some(row_a.abs_diff(row_b) + col_a.abs_diff(col_b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::KeymapPreset;
+    use crate::keys::{parse_key_sequence, KeyBindings};
+
+    fn resolved(keymap: KeyBindings) -> ResolvedKeys {
+        ResolvedKeys {
+            bindings: keymap,
+            preset: KeymapPreset::Default,
+            preset_location: None,
+            sequence_timeout_location: None,
+            from_config: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    fn rules(findings: &[LintFinding]) -> Vec<(&'static str, KeyAction, Option<&str>)> {
+        findings
+            .iter()
+            .map(|f| (f.rule, f.action, f.key.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn presets_are_clean() {
+        for preset in KeymapPreset::ALL {
+            let findings = lint_keys(&resolved(KeyBindings::preset(preset)));
+            assert_eq!(rules(&findings), [], "preset {}", preset.as_str());
+        }
+    }
+
+    #[test]
+    fn flags_risky_but_valid_bindings() {
+        let seq = |spec: &str| vec![parse_key_sequence(spec).unwrap()];
+        let keymap = KeyBindings {
+            quit: seq("ctrl+q"),
+            toggle_help: seq("ctrl+i"),
+            toggle_color: seq("release+c"),
+            toggle_reduced_motion: seq("ctrl+alt+r"),
+            next_panel: seq("m"),
+            prev_panel: seq("x"),
+            ..KeyBindings::default()
+        };
+        keymap.validate().unwrap();
+
+        let findings = lint_keys(&resolved(keymap));
+        assert_eq!(
+            rules(&findings),
+            [
+                (
+                    "unreportable-key",
+                    KeyAction::ToggleColor,
+                    Some("release+c")
+                ),
+                (
+                    "unreportable-key",
+                    KeyAction::ToggleReducedMotion,
+                    Some("ctrl+alt+r")
+                ),
+                ("modifier-heavy", KeyAction::ToggleReducedMotion, None),
+                ("unreportable-key", KeyAction::ToggleHelp, Some("ctrl+i")),
+                ("terminal-shortcut", KeyAction::Quit, Some("ctrl+q")),
+                ("split-pair", KeyAction::NextPanel, None),
+            ]
+        );
+        let severities = findings.iter().map(|f| f.severity).collect::<Vec<_>>();
+        assert_eq!(
+            severities,
+            [
+                Severity::Warning,
+                Severity::Info,
+                Severity::Info,
+                Severity::Warning,
+                Severity::Warning,
+                Severity::Warning
+            ]
+        );
+        assert_eq!(
+            findings[3].message,
+            "'ctrl+i' for 'toggle_help': ctrl+i arrives as tab in terminals without the kitty \
+             keyboard protocol"
+        );
+
+        let keymap = KeyBindings {
+            toggle_help: seq("ctrl+h"),
+            ..KeyBindings::default()
+        };
+        let findings = lint_keys(&resolved(keymap));
+        assert_eq!(
+            rules(&findings),
+            [("unreportable-key", KeyAction::ToggleHelp, Some("ctrl+h"))]
+        );
+        assert!(findings[0].message.contains("arrives as backspace"));
+    }
+
+    #[test]
+    fn related_keys_pair_up() {
+        let paired = |a: &str, b: &str| {
+            sequences_paired(
+                &parse_key_sequence(a).unwrap(),
+                &parse_key_sequence(b).unwrap(),
+            )
+        };
+        assert!(paired("j", "k"));
+        assert!(paired("g t", "g T"));
+        assert!(paired("ctrl+n", "ctrl+p"));
+        assert!(paired("pagedown", "pageup"));
+        assert!(paired("f5", "f6"));
+        assert!(!paired("m", "x"));
+        assert!(!paired("ctrl+j", "k"));
+        assert!(!paired("g j", "h k"));
+    }
+}
@@ -1,17 +1,24 @@
 mod app;
 mod cli;
+mod color;
 mod config;
+mod diagnostics;
+mod interop;
+mod keydocs;
 mod keys;
+mod lint;
 mod terminal;
 mod theme;
 mod ui;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use app::App;
 use app::DemoPanel;
 use clap::Parser;
 use cli::{Cli, Commands};
 use crossterm::event::{self, Event};
+use diagnostics::ConfigErrors;
+use keys::{KeyAction, KeyContext};
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 use serde::Serialize;
@@ -21,32 +28,65 @@ use std::time::{Duration, Instant};
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    match cli.command {
+    let result = match cli.command {
         Commands::Demo(args) => run_demo(args),
         Commands::Themes(args) => print_themes(args),
         Commands::Keys(args) => print_keys(args),
         Commands::Config(args) => run_config(args),
+    };
+    result.map_err(report_config_errors)
+}
+
+/// Prints config problems in full, as `config validate` does, and leaves a one-line error to
+/// exit with.
+fn report_config_errors(err: anyhow::Error) -> anyhow::Error {
+    let Some(errors) = err.downcast_ref::<ConfigErrors>() else {
+        return err;
+    };
+    eprintln!("{}", errors);
+    let summary = format!("found {} config error(s)", errors.0.len());
+    if err.chain().count() > 1 {
+        anyhow::anyhow!("{}: {}", err, summary)
+    } else {
+        anyhow::anyhow!(summary)
     }
 }
 
+/// An error on one line for the demo's status bar; config problems show the first one and
+/// where it is.
+fn status_error(err: &anyhow::Error) -> String {
+    match err.downcast_ref::<ConfigErrors>() {
+        Some(errors) => errors.summary(),
+        None => format!("{:#}", err),
+    }
+}
+
+/// How often the interactive demo checks the config and theme files for edits.
+const RELOAD_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 fn run_demo(args: cli::DemoArgs) -> Result<()> {
-    let resolved = config::resolve_demo_runtime(&args)?;
+    let resolved = config::resolve_demo_runtime(&args.settings, !args.no_tty)?;
 
     if args.no_tty {
         let width = args.width.unwrap_or(80).clamp(20, 240);
         let height = args.height.unwrap_or(24).clamp(10, 120);
 
         let app = App::new(
-            resolved.settings.theme,
+            resolved.themes,
+            &resolved.settings.theme,
             resolved.settings.no_color,
             resolved.settings.high_contrast,
             resolved.settings.reduced_motion,
-            resolved.keys,
+            resolved.keys.bindings,
             DemoPanel::Overview,
-        );
+        )
+        .with_color_depth(resolved.settings.color_depth)
+        .with_simulation(args.simulate);
+        // A simulation only shows in color, so it switches the preview to ANSI output.
+        let ansi = args.simulate.is_some() && !resolved.settings.no_color;
         print!(
             "{}",
-            ui::render_static_preview(&app, width, height, resolved.settings.ascii)?
+            ui::render_static_preview(&app, width, height, resolved.settings.ascii, ansi)?
         );
         return Ok(());
     }
@@ -63,15 +103,20 @@ fn run_demo(args: cli::DemoArgs) -> Result<()> {
     let backend = CrosstermBackend::new(guard.stdout());
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new_with_mouse(
-        resolved.settings.theme,
+    let mut app = App::new(
+        resolved.themes,
+        &resolved.settings.theme,
         resolved.settings.no_color,
         resolved.settings.high_contrast,
         resolved.settings.reduced_motion,
-        resolved.keys,
-        resolved.settings.mouse,
+        resolved.keys.bindings,
         DemoPanel::Overview,
-    );
+    )
+    .with_mouse(resolved.settings.mouse)
+    .with_color_depth(resolved.settings.color_depth)
+    .with_simulation(args.simulate);
+    let mut watcher = config::ConfigWatcher::new(&args.settings.config);
+    let mut last_reload_check = Instant::now();
     let mut last_tick = Instant::now();
     let tick_rate = if app.reduced_motion {
         Duration::from_millis(500)
@@ -85,7 +130,7 @@ fn run_demo(args: cli::DemoArgs) -> Result<()> {
         let timeout = tick_rate.saturating_sub(last_tick.elapsed());
         if event::poll(timeout)? {
             match event::read()? {
-                Event::Key(key) => app.handle_key(key),
+                Event::Key(key) => app.handle_key(key, Instant::now()),
                 Event::Mouse(mouse) => {
                     let area = terminal.size()?;
                     app.handle_mouse(mouse, area.into());
@@ -94,11 +139,34 @@ fn run_demo(args: cli::DemoArgs) -> Result<()> {
             }
         }
 
+        app.expire_pending_keys(Instant::now());
+
+        if let Some(edits) = app.take_save_request() {
+            match save_key_bindings(&args.settings.config, &edits) {
+                Ok(path) => {
+                    watcher.acknowledge_write(&path);
+                    app.report_saved(&path, Instant::now());
+                }
+                Err(err) => app.report_save_error(status_error(&err)),
+            }
+        }
+
         if last_tick.elapsed() >= tick_rate {
             app.tick();
             last_tick = Instant::now();
         }
 
+        if last_reload_check.elapsed() >= RELOAD_POLL_INTERVAL {
+            last_reload_check = Instant::now();
+            if watcher.poll_changed() {
+                match config::reload_config(&args.settings.config, args.settings.keymap_preset) {
+                    Ok(reloaded) => app.reload(reloaded.themes, reloaded.keys, Instant::now()),
+                    Err(err) => app.report_reload_error(status_error(&err)),
+                }
+            }
+            app.expire_status(Instant::now());
+        }
+
         if app.should_quit {
             break;
         }
@@ -112,7 +180,36 @@ fn run_config(args: cli::ConfigArgs) -> Result<()> {
     match args.command {
         cli::ConfigCommands::Init(init) => config_init(init),
         cli::ConfigCommands::Validate(validate) => config_validate(validate),
+        cli::ConfigCommands::Lint(lint) => config_lint(lint),
+        cli::ConfigCommands::Migrate(migrate) => config_migrate(migrate),
+        cli::ConfigCommands::Show(show) => config_show(show),
+    }
+}
+
+/// Writes key bindings edited in the Keys panel into the highest-precedence writable config file,
+/// creating it if needed. The merged config is validated before the file is replaced.
+fn save_key_bindings(
+    explicit: &[std::path::PathBuf],
+    edits: &[(KeyContext, KeyAction, Vec<keys::KeySequence>)],
+) -> Result<std::path::PathBuf> {
+    let path = config::writable_config_path(explicit)?;
+    let contents = if path.exists() {
+        std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read config file: {}", path.display()))?
+    } else {
+        String::new()
+    };
+
+    let updated = config::update_keys_toml(&contents, edits)
+        .with_context(|| format!("cannot update {}", path.display()))?;
+    config::validate_config_contents(&updated, &path, explicit)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
     }
+    std::fs::write(&path, updated)
+        .with_context(|| format!("failed to write config file: {}", path.display()))?;
+    Ok(path)
 }
 
 fn config_init(args: cli::ConfigInitArgs) -> Result<()> {
@@ -142,43 +239,406 @@ fn config_init(args: cli::ConfigInitArgs) -> Result<()> {
 }
 
 fn config_validate(args: cli::ConfigValidateArgs) -> Result<()> {
-    let path = match args.config {
-        Some(path) => path,
-        None => {
-            let path = config::default_config_path().ok_or_else(|| {
-                anyhow::anyhow!("cannot determine default config path (HOME not set)")
-            })?;
-            if !path.exists() {
-                anyhow::bail!(
-                    "config file not found at {} (try `cli-tui-starter config init`)",
-                    path.display()
-                );
-            }
-            path
-        }
-    };
+    let (layers, diagnostics) = config::diagnose_config(&args.config)?;
+    if layers.is_empty() && diagnostics.is_empty() {
+        let path = config::default_config_path().ok_or_else(|| {
+            anyhow::anyhow!("cannot determine default config path (HOME not set)")
+        })?;
+        anyhow::bail!(
+            "config file not found at {} (try `cli-tui-starter config init`)",
+            path.display()
+        );
+    }
 
-    config::validate_config_file(&path)?;
     match args.format {
         cli::OutputFormat::Text => {
-            println!("Config OK: {}", path.display());
+            if !diagnostics.is_empty() {
+                let rendered = diagnostics
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>();
+                eprintln!("{}", rendered.join("\n"));
+            } else {
+                let mut out = String::new();
+                for layer in &layers {
+                    out.push_str(&format!(
+                        "Config OK: {} ({})\n",
+                        layer.source,
+                        layer.kind.as_str()
+                    ));
+                }
+                if layers.len() > 1 {
+                    out.push_str(&format!("Merged config OK ({} layers)\n", layers.len()));
+                }
+                for layer in &layers {
+                    if let Some(version) = layer.version.filter(|v| *v < config::CONFIG_VERSION) {
+                        out.push_str(&format!(
+                            "note: {} uses config version {} (current is {}); upgrade it with \
+                             `cli-tui-starter config migrate --config {} --write`\n",
+                            layer.source,
+                            version,
+                            config::CONFIG_VERSION,
+                            layer.source
+                        ));
+                    }
+                }
+                print!("{}", out);
+            }
         }
         cli::OutputFormat::Json => {
             #[derive(Serialize)]
             struct ConfigValidateJson {
                 ok: bool,
+                /// The highest-precedence layer.
+                path: Option<String>,
+                layers: Vec<LayerJson>,
+                diagnostics: Vec<DiagnosticJson>,
+            }
+            #[derive(Serialize)]
+            struct LayerJson {
+                kind: &'static str,
                 path: String,
+                version: Option<u32>,
+            }
+            #[derive(Serialize)]
+            struct DiagnosticJson {
+                path: Option<String>,
+                line: Option<usize>,
+                column: Option<usize>,
+                message: String,
+                suggestion: Option<String>,
+                help: Option<String>,
             }
             let payload = ConfigValidateJson {
-                ok: true,
-                path: path.display().to_string(),
+                ok: diagnostics.is_empty(),
+                path: layers.last().map(|top| top.source.to_string()),
+                layers: layers
+                    .iter()
+                    .map(|layer| LayerJson {
+                        kind: layer.kind.as_str(),
+                        path: layer.source.to_string(),
+                        version: layer.version,
+                    })
+                    .collect(),
+                diagnostics: diagnostics
+                    .iter()
+                    .map(|diagnostic| DiagnosticJson {
+                        path: diagnostic.source.as_ref().map(ToString::to_string),
+                        line: diagnostic.span.as_ref().map(|span| span.line),
+                        column: diagnostic.span.as_ref().map(|span| span.column),
+                        message: diagnostic.message.clone(),
+                        suggestion: diagnostic.suggestion.clone(),
+                        help: diagnostic.help.clone(),
+                    })
+                    .collect(),
+            };
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+        }
+    }
+    if !diagnostics.is_empty() {
+        anyhow::bail!("found {} config error(s)", diagnostics.len());
+    }
+    Ok(())
+}
+
+fn config_lint(args: cli::ConfigLintArgs) -> Result<()> {
+    let resolved = config::resolve_key_bindings(&args.config, args.keymap_preset)?;
+    let findings = lint::lint_keys(&resolved);
+    let count = |severity| findings.iter().filter(|f| f.severity == severity).count();
+    let (warnings, infos) = (count(lint::Severity::Warning), count(lint::Severity::Info));
+    let denied_from = args.deny.map(|deny| match deny {
+        cli::LintDeny::Warnings => lint::Severity::Warning,
+        cli::LintDeny::Info => lint::Severity::Info,
+    });
+    let denied = match denied_from {
+        Some(level) => findings.iter().filter(|f| f.severity >= level).count(),
+        None => 0,
+    };
+
+    match args.format {
+        cli::OutputFormat::Text => {
+            let mut out = String::new();
+            for finding in &findings {
+                out.push_str(&format!(
+                    "{}[{}]: {}{}\n",
+                    finding.severity.as_str(),
+                    finding.rule,
+                    finding.message,
+                    context_marker(finding.context)
+                ));
+                if let Some(location) = &finding.location {
+                    out.push_str(&format!("  --> {}\n", location));
+                }
+            }
+            out.push_str(&format!("{} warning(s), {} info\n", warnings, infos));
+            print!("{}", out);
+        }
+        cli::OutputFormat::Json => {
+            #[derive(Serialize)]
+            struct LintJson {
+                ok: bool,
+                warnings: usize,
+                infos: usize,
+                findings: Vec<FindingJson>,
+            }
+
+            #[derive(Serialize)]
+            struct FindingJson {
+                severity: &'static str,
+                rule: &'static str,
+                context: &'static str,
+                action: &'static str,
+                key: Option<String>,
+                message: String,
+                path: Option<String>,
+                line: Option<usize>,
+            }
+
+            let payload = LintJson {
+                ok: denied == 0,
+                warnings,
+                infos,
+                findings: findings
+                    .iter()
+                    .map(|finding| FindingJson {
+                        severity: finding.severity.as_str(),
+                        rule: finding.rule,
+                        context: finding.context.as_str(),
+                        action: finding.action.as_str(),
+                        key: finding.key.clone(),
+                        message: finding.message.clone(),
+                        path: finding.location.as_ref().map(|l| l.source.to_string()),
+                        line: finding.location.as_ref().map(|l| l.line),
+                    })
+                    .collect(),
             };
             println!("{}", serde_json::to_string_pretty(&payload)?);
         }
     }
+
+    if denied > 0 {
+        anyhow::bail!("{} lint finding(s) denied by --deny", denied);
+    }
+    Ok(())
+}
+
+fn config_migrate(args: cli::ConfigMigrateArgs) -> Result<()> {
+    let path = match args.config {
+        Some(path) => path,
+        None => config::writable_config_path(&[])?,
+    };
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read config file: {}", path.display()))?;
+    let (from, migrated) = config::migrate_config_toml(&contents)
+        .with_context(|| format!("cannot migrate {}", path.display()))?;
+
+    if !args.write {
+        print!("{}", migrated);
+        return Ok(());
+    }
+    if from == config::CONFIG_VERSION {
+        println!(
+            "{} is already at config version {}",
+            path.display(),
+            config::CONFIG_VERSION
+        );
+        return Ok(());
+    }
+    config::validate_config_contents(&migrated, &path, std::slice::from_ref(&path))
+        .with_context(|| format!("migrated config for {} is invalid", path.display()))?;
+    std::fs::write(&path, migrated)
+        .with_context(|| format!("failed to write config file: {}", path.display()))?;
+    println!(
+        "Migrated {} from config version {} to {}",
+        path.display(),
+        from,
+        config::CONFIG_VERSION
+    );
+    Ok(())
+}
+
+/// One effective value for `config show`, in its config table (`demo`, `keys` or
+/// `keys.<context>`).
+struct ShownValue {
+    table: String,
+    key: &'static str,
+    value: toml::Value,
+    origin: config::Origin,
+}
+
+fn config_show(args: cli::ConfigShowArgs) -> Result<()> {
+    // A print command never queries the terminal, so `theme = "auto"` falls back to COLORFGBG.
+    let runtime = config::resolve_demo_runtime(&args.settings, false)?;
+    let values = shown_values(&runtime, args.settings.keymap_preset.is_some());
+
+    match args.format {
+        cli::ShowFormat::Toml => print!("{}", render_show_toml(&values, args.origin)),
+        cli::ShowFormat::Json => {
+            let mut root = serde_json::Map::new();
+            root.insert("version".to_string(), config::CONFIG_VERSION.into());
+            for shown in &values {
+                let mut table = &mut root;
+                for part in shown.table.split('.') {
+                    table = table
+                        .entry(part)
+                        .or_insert_with(|| serde_json::Value::Object(Default::default()))
+                        .as_object_mut()
+                        .expect("config tables are objects");
+                }
+                table.insert(shown.key.to_string(), serde_json::to_value(&shown.value)?);
+            }
+            if args.origin {
+                let origins = values
+                    .iter()
+                    .map(|shown| {
+                        let name = format!("{}.{}", shown.table, shown.key);
+                        (name, serde_json::Value::from(shown.origin.to_string()))
+                    })
+                    .collect();
+                root.insert("origins".to_string(), serde_json::Value::Object(origins));
+            }
+            println!("{}", serde_json::to_string_pretty(&root)?);
+        }
+    }
     Ok(())
 }
 
+fn shown_values(runtime: &config::DemoRuntime, preset_from_cli: bool) -> Vec<ShownValue> {
+    let settings = &runtime.settings;
+    let demo_origin = |key: &str| {
+        runtime
+            .origins
+            .get(key)
+            .cloned()
+            .unwrap_or(config::Origin::Default)
+    };
+    let mut demo: Vec<(&'static str, toml::Value)> = vec![("theme", settings.theme.clone().into())];
+    if let Some(background) = settings.background {
+        demo.push(("background", background.as_str().into()));
+    }
+    demo.extend([
+        ("no_color", settings.no_color.into()),
+        ("high_contrast", settings.high_contrast.into()),
+        ("reduced_motion", settings.reduced_motion.into()),
+        ("ascii", settings.ascii.into()),
+        ("mouse", settings.mouse.into()),
+        ("color_depth", settings.color_depth.as_str().into()),
+    ]);
+    let mut out = demo
+        .into_iter()
+        .map(|(key, value)| ShownValue {
+            table: "demo".to_string(),
+            key,
+            value,
+            origin: demo_origin(key),
+        })
+        .collect::<Vec<_>>();
+
+    let resolved = &runtime.keys;
+    let bindings = &resolved.bindings;
+    let keys_value = |keys: &[keys::KeySequence]| match keys::key_labels(keys).as_slice() {
+        [single] => toml::Value::from(single.as_str()),
+        labels => toml::Value::from(labels.to_vec()),
+    };
+    let entry_origin = |context, action| {
+        resolved
+            .entry(context, action)
+            .map(|entry| config::Origin::Config(entry.location.clone()))
+    };
+
+    out.push(ShownValue {
+        table: "keys".to_string(),
+        key: "preset",
+        value: resolved.preset.as_str().into(),
+        origin: match &resolved.preset_location {
+            _ if preset_from_cli => config::Origin::Cli("--keymap-preset"),
+            Some(location) => config::Origin::Config(location.clone()),
+            None => config::Origin::Default,
+        },
+    });
+    out.push(ShownValue {
+        table: "keys".to_string(),
+        key: "sequence_timeout_ms",
+        value: toml::Value::Integer(bindings.sequence_timeout.as_millis() as i64),
+        origin: resolved
+            .sequence_timeout_location
+            .clone()
+            .map_or(config::Origin::Default, config::Origin::Config),
+    });
+    let defaults = keys::KeyBindings::default();
+    for action in KeyAction::ALL {
+        let origin = entry_origin(action.home_context(), action).unwrap_or_else(|| {
+            if bindings.keys(action) == defaults.keys(action) {
+                config::Origin::Default
+            } else {
+                config::Origin::Preset(resolved.preset)
+            }
+        });
+        // Only global actions sit in `[keys]` itself since config version 2.
+        let table = match action.home_context() {
+            KeyContext::Global => "keys".to_string(),
+            home => format!("keys.{}", home.as_str()),
+        };
+        out.push(ShownValue {
+            table,
+            key: action.as_str(),
+            value: keys_value(bindings.keys(action)),
+            origin,
+        });
+    }
+    for binding in &bindings.scoped {
+        out.push(ShownValue {
+            table: format!("keys.{}", binding.context.as_str()),
+            key: binding.action.as_str(),
+            value: keys_value(&binding.keys),
+            origin: entry_origin(binding.context, binding.action)
+                .unwrap_or(config::Origin::Default),
+        });
+    }
+
+    // One run per table, so the TOML output has a single header for each.
+    let mut tables: Vec<String> = Vec::new();
+    for shown in &out {
+        if !tables.contains(&shown.table) {
+            tables.push(shown.table.clone());
+        }
+    }
+    out.sort_by_key(|shown| tables.iter().position(|table| *table == shown.table));
+    out
+}
+
+fn render_show_toml(values: &[ShownValue], origin: bool) -> String {
+    let lines = values
+        .iter()
+        .map(|shown| format!("{} = {}", shown.key, shown.value))
+        .collect::<Vec<_>>();
+    let width = lines.iter().map(String::len).max().unwrap_or(0);
+
+    let mut out = format!("version = {}\n\n", config::CONFIG_VERSION);
+    let mut table = None;
+    for (shown, line) in values.iter().zip(&lines) {
+        if table != Some(&shown.table) {
+            if table.is_some() {
+                out.push('\n');
+            }
+            out.push_str(&format!("[{}]\n", shown.table));
+            table = Some(&shown.table);
+        }
+        if origin {
+            out.push_str(&format!(
+                "{:width$}  # {}\n",
+                line,
+                shown.origin,
+                width = width
+            ));
+        } else {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
 #[derive(Serialize)]
 struct ThemesJson<'a> {
     themes: Vec<ThemeInfo<'a>>,
@@ -188,27 +648,42 @@ struct ThemesJson<'a> {
 struct ThemeInfo<'a> {
     name: &'a str,
     description: &'a str,
+    builtin: bool,
 }
 
 fn print_themes(args: cli::ThemesArgs) -> Result<()> {
+    match args.command {
+        Some(cli::ThemesCommands::Check(check)) => return check_themes(check),
+        Some(cli::ThemesCommands::Preview(preview)) => return preview_themes(preview),
+        Some(cli::ThemesCommands::Export(export)) => return export_theme(export),
+        Some(cli::ThemesCommands::Import(import)) => return import_theme(import),
+        None => {}
+    }
+
+    let list = config::resolve_themes(&args.config)?;
+
     match args.format {
         cli::OutputFormat::Text => {
             let mut out = String::new();
             out.push_str("Available themes:\n");
-            for theme in theme::themes() {
-                out.push_str(&format!("- {}: {}\n", theme.name, theme.description));
+            for theme in &list {
+                let origin = if theme.is_builtin() { "" } else { " (config)" };
+                out.push_str(&format!(
+                    "- {}: {}{}\n",
+                    theme.name, theme.description, origin
+                ));
             }
             print!("{}", out);
             Ok(())
         }
         cli::OutputFormat::Json => {
-            let list = theme::themes();
             let payload = ThemesJson {
                 themes: list
                     .iter()
                     .map(|t| ThemeInfo {
-                        name: t.name,
-                        description: t.description,
+                        name: &t.name,
+                        description: &t.description,
+                        builtin: t.is_builtin(),
                     })
                     .collect(),
             };
@@ -218,56 +693,288 @@ fn print_themes(args: cli::ThemesArgs) -> Result<()> {
     }
 }
 
-fn print_keys(args: cli::KeysArgs) -> Result<()> {
-    let keymap = config::resolve_key_bindings(args.config.as_deref())?;
+fn preview_themes(args: cli::ThemesPreviewArgs) -> Result<()> {
+    let mut list = config::resolve_themes(&args.config)?;
+    if let Some(name) = &args.name {
+        let selected = theme::find_theme(&list, name).cloned().ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown theme '{}'. valid themes: {}",
+                name,
+                theme::theme_names(&list)
+            )
+        })?;
+        list = vec![selected];
+    }
+
+    let ansi = !args.no_color && !config::env_disables_color_current();
+    let depth = args
+        .color_depth
+        .and_then(cli::ColorDepthArg::depth)
+        .unwrap_or_else(config::env_color_depth_current);
+    let list: Vec<_> = list
+        .into_iter()
+        .map(|theme| theme.with_color_depth(depth))
+        .collect();
+
+    let width = args.width.clamp(20, 240);
+    print!(
+        "{}",
+        ui::render_theme_previews(&list, width, args.ascii, ansi)?
+    );
+    Ok(())
+}
+
+fn export_theme(args: cli::ThemesExportArgs) -> Result<()> {
+    let list = config::resolve_themes(&args.config)?;
+    let theme = theme::find_theme(&list, &args.name).ok_or_else(|| {
+        anyhow::anyhow!(
+            "unknown theme '{}'. valid themes: {}",
+            args.name,
+            theme::theme_names(&list)
+        )
+    })?;
+    print!("{}", interop::export_theme(theme, args.to));
+    Ok(())
+}
+
+fn import_theme(args: cli::ThemesImportArgs) -> Result<()> {
+    let contents = std::fs::read_to_string(&args.path)
+        .with_context(|| format!("failed to read base16 scheme: {}", args.path.display()))?;
+    let scheme = interop::parse_base16_yaml(&contents)
+        .with_context(|| format!("invalid base16 scheme in {}", args.path.display()))?;
+    let name = args.name.clone().unwrap_or_else(|| scheme.slug());
+
+    if !args.write {
+        print!("{}", scheme.to_theme_toml(&name, true)?);
+        return Ok(());
+    }
+
+    let config_path = config::writable_config_path(&args.config)?;
+    let defined = || -> Result<bool> {
+        Ok(theme::find_theme(&config::resolve_themes(&args.config)?, &name).is_some())
+    };
+
+    // Project files don't carry a `themes/` directory, so the table goes into the file itself.
+    let in_project = config::config_layers(&args.config)
+        .iter()
+        .any(|layer| layer.path == config_path && layer.kind == config::LayerKind::Project);
+    if in_project {
+        if defined()? {
+            anyhow::bail!(
+                "theme '{}' is already defined (use `--name` to pick another)",
+                name
+            );
+        }
+        let mut contents = std::fs::read_to_string(&config_path)
+            .with_context(|| format!("failed to read config file: {}", config_path.display()))?;
+        if !contents.is_empty() {
+            contents.push_str(if contents.ends_with('\n') {
+                "\n"
+            } else {
+                "\n\n"
+            });
+        }
+        contents.push_str(&scheme.to_theme_toml(&name, true)?);
+        config::validate_config_contents(&contents, &config_path, &args.config)?;
+        std::fs::write(&config_path, contents)
+            .with_context(|| format!("failed to write config file: {}", config_path.display()))?;
+        println!("Wrote theme '{}': {}", name, config_path.display());
+        return Ok(());
+    }
+
+    let body = scheme.to_theme_toml(&name, false)?;
+    let dir = config_path
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("invalid config path: {}", config_path.display()))?
+        .join("themes");
+    let path = dir.join(format!("{}.toml", name));
+
+    if path.exists() {
+        if !args.force {
+            anyhow::bail!(
+                "theme file already exists at {} (use `--force` to overwrite)",
+                path.display()
+            );
+        }
+    } else if defined()? {
+        anyhow::bail!(
+            "theme '{}' is already defined (use `--name` to pick another)",
+            name
+        );
+    }
+
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(&path, body)?;
+    println!("Wrote theme '{}': {}", name, path.display());
+    Ok(())
+}
+
+fn check_themes(args: cli::ThemesCheckArgs) -> Result<()> {
+    let mut list = config::resolve_themes(&args.config)?;
+    if let Some(name) = &args.name {
+        let selected = theme::find_theme(&list, name).cloned().ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown theme '{}'. valid themes: {}",
+                name,
+                theme::theme_names(&list)
+            )
+        })?;
+        list = vec![selected];
+    }
+
+    let checks = theme::contrast_audit(&list);
+    let failed = checks.iter().filter(|c| !c.passes_aa()).count();
 
     match args.format {
         cli::OutputFormat::Text => {
-            let mut out = String::new();
-            out.push_str("Key bindings:\n");
-            out.push_str(&format!(
-                "- {}: cycle theme\n",
-                keys::key_list_display(&keymap.cycle_theme)
-            ));
-            out.push_str(&format!(
-                "- {}: next panel\n",
-                keys::key_list_display(&keymap.next_panel)
-            ));
-            out.push_str(&format!(
-                "- {}: previous panel\n",
-                keys::key_list_display(&keymap.prev_panel)
-            ));
-            out.push_str(&format!(
-                "- {}: list up\n",
-                keys::key_list_display(&keymap.list_up)
-            ));
-            out.push_str(&format!(
-                "- {}: list down\n",
-                keys::key_list_display(&keymap.list_down)
-            ));
-            out.push_str(&format!(
-                "- {}: toggle high contrast\n",
-                keys::key_list_display(&keymap.toggle_high_contrast)
-            ));
-            out.push_str(&format!(
-                "- {}: toggle color\n",
-                keys::key_list_display(&keymap.toggle_color)
-            ));
+            let name_width = list
+                .iter()
+                .map(|t| t.name.len())
+                .max()
+                .unwrap_or(0)
+                .max("THEME".len());
+            let mut out = format!(
+                "{:<name_width$}  {:<13}  {:<7}  {:>7}  AA\n",
+                "THEME", "VARIANT", "SLOT", "RATIO"
+            );
+            for check in &checks {
+                let ratio = match check.ratio {
+                    Some(ratio) => format!("{:.2}:1", ratio),
+                    None => "n/a".to_string(),
+                };
+                let verdict = match check.ratio {
+                    None => "skip",
+                    Some(_) if check.passes_aa() => "pass",
+                    Some(_) => "FAIL",
+                };
+                out.push_str(&format!(
+                    "{:<name_width$}  {:<13}  {:<7}  {:>7}  {}\n",
+                    check.theme, check.variant, check.slot, ratio, verdict
+                ));
+            }
             out.push_str(&format!(
-                "- {}: toggle reduced motion\n",
-                keys::key_list_display(&keymap.toggle_reduced_motion)
+                "{} checks, {} failed (WCAG AA requires {}:1)\n",
+                checks.len(),
+                failed,
+                theme::WCAG_AA_RATIO
             ));
+            print!("{}", out);
+        }
+        cli::OutputFormat::Json => {
+            #[derive(Serialize)]
+            struct ContrastJson {
+                ok: bool,
+                threshold: f64,
+                checks: Vec<ContrastCheckJson>,
+            }
+
+            #[derive(Serialize)]
+            struct ContrastCheckJson {
+                theme: String,
+                variant: &'static str,
+                slot: &'static str,
+                fg: Option<String>,
+                bg: Option<String>,
+                ratio: Option<f64>,
+                aa: bool,
+            }
+
+            let payload = ContrastJson {
+                ok: failed == 0,
+                threshold: theme::WCAG_AA_RATIO,
+                checks: checks
+                    .iter()
+                    .map(|check| ContrastCheckJson {
+                        theme: check.theme.clone(),
+                        variant: check.variant,
+                        slot: check.slot,
+                        fg: color::to_rgb(check.fg).map(color::hex),
+                        bg: color::to_rgb(check.bg).map(color::hex),
+                        // Two decimals keep the JSON stable and readable.
+                        ratio: check.ratio.map(|r| (r * 100.0).round() / 100.0),
+                        aa: check.passes_aa(),
+                    })
+                    .collect(),
+            };
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!(
+            "{} contrast check(s) below WCAG AA ({}:1)",
+            failed,
+            theme::WCAG_AA_RATIO
+        );
+    }
+    Ok(())
+}
+
+fn print_keys(args: cli::KeysArgs) -> Result<()> {
+    let resolved = config::resolve_key_bindings(&args.config, args.keymap_preset)?;
+    let keymap = &resolved.bindings;
+    let format = match args.format {
+        cli::KeysFormat::Text => cli::OutputFormat::Text,
+        cli::KeysFormat::Json => cli::OutputFormat::Json,
+        _ if args.explain => {
+            anyhow::bail!("`keys --explain` supports only `--format text` and `--format json`")
+        }
+        card => {
+            print!("{}", keydocs::render(card, keymap, resolved.preset));
+            return Ok(());
+        }
+    };
+    if args.explain {
+        return print_keys_explain(&resolved, args.keymap_preset.is_some(), format);
+    }
+    let labels = |action: KeyAction| {
+        if action == KeyAction::Quit {
+            keymap.quit_labels()
+        } else {
+            keys::key_labels(keymap.keys(action))
+        }
+    };
+
+    match format {
+        cli::OutputFormat::Text => {
+            let mut out = String::new();
             out.push_str(&format!(
-                "- {}: toggle help\n",
-                keys::key_list_display(&keymap.toggle_help)
+                "Key bindings (preset: {}):\n",
+                resolved.preset.as_str()
             ));
-            out.push_str(&format!("- {}: quit\n", keymap.quit_label()));
+            for action in KeyAction::ALL {
+                // Entries without a marker come from the preset.
+                let origin = if resolved.from_config.contains(&action) {
+                    " (config)"
+                } else {
+                    ""
+                };
+                out.push_str(&format!(
+                    "- {}: {}{}{}\n",
+                    labels(action).join("/"),
+                    action.description(),
+                    context_marker(action.home_context()),
+                    origin
+                ));
+            }
+            // `[keys.<context>]` extras always come from the config file.
+            for binding in &keymap.scoped {
+                out.push_str(&format!(
+                    "- {}: {}{} (config)\n",
+                    keys::key_list_display(&binding.keys),
+                    binding.action.description(),
+                    context_marker(binding.context)
+                ));
+            }
             print!("{}", out);
             Ok(())
         }
         cli::OutputFormat::Json => {
             #[derive(Serialize)]
             struct KeysJson {
+                preset: &'static str,
+                /// Actions whose bindings come from `[keys]` rather than the preset.
+                from_config: Vec<&'static str>,
                 cycle_theme: Vec<String>,
                 next_panel: Vec<String>,
                 prev_panel: Vec<String>,
@@ -278,22 +985,259 @@ fn print_keys(args: cli::KeysArgs) -> Result<()> {
                 toggle_reduced_motion: Vec<String>,
                 toggle_help: Vec<String>,
                 quit: Vec<String>,
+                /// Bindings from `[keys.<context>]` outside the action's home context.
+                scoped: Vec<ScopedJson>,
+            }
+
+            #[derive(Serialize)]
+            struct ScopedJson {
+                context: &'static str,
+                action: &'static str,
+                keys: Vec<String>,
             }
 
             let payload = KeysJson {
-                cycle_theme: keys::key_labels(&keymap.cycle_theme),
-                next_panel: keys::key_labels(&keymap.next_panel),
-                prev_panel: keys::key_labels(&keymap.prev_panel),
-                list_up: keys::key_labels(&keymap.list_up),
-                list_down: keys::key_labels(&keymap.list_down),
-                toggle_high_contrast: keys::key_labels(&keymap.toggle_high_contrast),
-                toggle_color: keys::key_labels(&keymap.toggle_color),
-                toggle_reduced_motion: keys::key_labels(&keymap.toggle_reduced_motion),
-                toggle_help: keys::key_labels(&keymap.toggle_help),
-                quit: keymap.quit_labels(),
+                preset: resolved.preset.as_str(),
+                from_config: resolved
+                    .from_config
+                    .iter()
+                    .map(|action| action.as_str())
+                    .collect(),
+                cycle_theme: labels(KeyAction::CycleTheme),
+                next_panel: labels(KeyAction::NextPanel),
+                prev_panel: labels(KeyAction::PrevPanel),
+                list_up: labels(KeyAction::ListUp),
+                list_down: labels(KeyAction::ListDown),
+                toggle_high_contrast: labels(KeyAction::ToggleHighContrast),
+                toggle_color: labels(KeyAction::ToggleColor),
+                toggle_reduced_motion: labels(KeyAction::ToggleReducedMotion),
+                toggle_help: labels(KeyAction::ToggleHelp),
+                quit: labels(KeyAction::Quit),
+                scoped: keymap
+                    .scoped
+                    .iter()
+                    .map(|binding| ScopedJson {
+                        context: binding.context.as_str(),
+                        action: binding.action.as_str(),
+                        keys: keys::key_labels(&binding.keys),
+                    })
+                    .collect(),
             };
             println!("{}", serde_json::to_string_pretty(&payload)?);
             Ok(())
         }
     }
 }
+
+/// Where an effective key binding comes from, for `keys --explain`.
+enum KeySource<'a> {
+    /// The built-in default keymap.
+    Default,
+    /// The selected preset, where it differs from the default keymap.
+    Preset,
+    Config(&'a config::KeyEntry),
+}
+
+impl KeySource<'_> {
+    fn kind(&self) -> &'static str {
+        match self {
+            KeySource::Default => "default",
+            KeySource::Preset => "preset",
+            KeySource::Config(_) => "config",
+        }
+    }
+}
+
+struct ExplainedKey<'a> {
+    context: KeyContext,
+    action: KeyAction,
+    key: String,
+    source: KeySource<'a>,
+}
+
+fn explain_keys(resolved: &config::ResolvedKeys) -> Vec<ExplainedKey<'_>> {
+    let defaults = keys::KeyBindings::default();
+    let mut out = Vec::new();
+    for action in KeyAction::ALL {
+        let context = action.home_context();
+        let entry = resolved.entry(context, action);
+        let default_labels = keys::key_labels(defaults.keys(action));
+        for key in keys::key_labels(resolved.bindings.keys(action)) {
+            let source = match entry {
+                Some(entry) => KeySource::Config(entry),
+                None if default_labels.contains(&key) => KeySource::Default,
+                None => KeySource::Preset,
+            };
+            out.push(ExplainedKey {
+                context,
+                action,
+                key,
+                source,
+            });
+        }
+    }
+    for binding in &resolved.bindings.scoped {
+        // Scoped bindings only exist because of a `[keys.<context>]` entry.
+        let Some(entry) = resolved.entry(binding.context, binding.action) else {
+            continue;
+        };
+        for key in keys::key_labels(&binding.keys) {
+            out.push(ExplainedKey {
+                context: binding.context,
+                action: binding.action,
+                key,
+                source: KeySource::Config(entry),
+            });
+        }
+    }
+    out
+}
+
+fn print_keys_explain(
+    resolved: &config::ResolvedKeys,
+    preset_from_cli: bool,
+    format: cli::OutputFormat,
+) -> Result<()> {
+    let explained = explain_keys(resolved);
+    let preset_source = if preset_from_cli {
+        "cli"
+    } else if resolved.preset_location.is_some() {
+        "config"
+    } else {
+        "default"
+    };
+
+    match format {
+        cli::OutputFormat::Text => {
+            let preset_origin = match &resolved.preset_location {
+                _ if preset_from_cli => "from --keymap-preset".to_string(),
+                Some(location) => format!("from {}", location),
+                None => "built in".to_string(),
+            };
+            let width = explained
+                .iter()
+                .map(|row| row.key.len())
+                .chain(keys::RESERVED_QUIT_KEYS.map(str::len))
+                .max()
+                .unwrap_or(0);
+
+            let mut out = format!(
+                "Key bindings (preset: {}, {}):\n",
+                resolved.preset.as_str(),
+                preset_origin
+            );
+            let mut current = None;
+            for row in &explained {
+                if current != Some((row.context, row.action)) {
+                    current = Some((row.context, row.action));
+                    out.push_str(&format!(
+                        "- {}{}:\n",
+                        row.action.description(),
+                        context_marker(row.context)
+                    ));
+                }
+                let source = match row.source {
+                    KeySource::Default => "default".to_string(),
+                    KeySource::Preset => format!("preset {}", resolved.preset.as_str()),
+                    KeySource::Config(entry) => format!("{} [{}]", entry.location, entry.table),
+                };
+                out.push_str(&format!("    {:<width$}  {}\n", row.key, source));
+            }
+            out.push_str("Reserved keys (always quit, not configurable):\n");
+            for key in keys::RESERVED_QUIT_KEYS {
+                let note = if key == "esc" {
+                    "quit; cancels a pending key sequence first"
+                } else {
+                    "quit"
+                };
+                out.push_str(&format!("    {:<width$}  {}\n", key, note));
+            }
+            print!("{}", out);
+            Ok(())
+        }
+        cli::OutputFormat::Json => {
+            #[derive(Serialize)]
+            struct ExplainJson {
+                preset: &'static str,
+                /// `cli`, `config` or `default`.
+                preset_source: &'static str,
+                preset_location: Option<LocationJson>,
+                bindings: Vec<BindingJson>,
+                reserved: Vec<ReservedJson>,
+            }
+
+            #[derive(Serialize)]
+            struct BindingJson {
+                action: &'static str,
+                context: &'static str,
+                key: String,
+                /// `default`, `preset` or `config`.
+                source: &'static str,
+                location: Option<LocationJson>,
+            }
+
+            #[derive(Serialize)]
+            struct LocationJson {
+                path: String,
+                line: usize,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                table: Option<String>,
+            }
+
+            #[derive(Serialize)]
+            struct ReservedJson {
+                key: &'static str,
+                action: &'static str,
+            }
+
+            let payload = ExplainJson {
+                preset: resolved.preset.as_str(),
+                preset_source,
+                preset_location: resolved
+                    .preset_location
+                    .as_ref()
+                    .filter(|_| !preset_from_cli)
+                    .map(|location| LocationJson {
+                        path: location.source.to_string(),
+                        line: location.line,
+                        table: None,
+                    }),
+                bindings: explained
+                    .iter()
+                    .map(|row| BindingJson {
+                        action: row.action.as_str(),
+                        context: row.context.as_str(),
+                        key: row.key.clone(),
+                        source: row.source.kind(),
+                        location: match row.source {
+                            KeySource::Config(entry) => Some(LocationJson {
+                                path: entry.location.source.to_string(),
+                                line: entry.location.line,
+                                table: Some(entry.table.clone()),
+                            }),
+                            _ => None,
+                        },
+                    })
+                    .collect(),
+                reserved: keys::RESERVED_QUIT_KEYS
+                    .iter()
+                    .map(|&key| ReservedJson {
+                        key,
+                        action: KeyAction::Quit.as_str(),
+                    })
+                    .collect(),
+            };
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+            Ok(())
+        }
+    }
+}
+
+/// ` [list]` for bindings that only apply in one context; global ones need no marker.
+fn context_marker(context: KeyContext) -> String {
+    if context == KeyContext::Global {
+        String::new()
+    } else {
+        format!(" [{}]", context.as_str())
+    }
+}
@@ -1,17 +1,33 @@
+use crate::cli::Background;
+use crate::color::relative_luminance;
 use anyhow::Result;
 use crossterm::cursor::Show;
-use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::event::{
+    DisableMouseCapture, EnableMouseCapture, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+    PushKeyboardEnhancementFlags,
+};
 use crossterm::execute;
 use crossterm::terminal::{
-    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+    disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen,
+    LeaveAlternateScreen,
 };
-use std::io::{self, Stdout};
+use std::io::{self, IsTerminal, Stdout, Write};
+use std::time::{Duration, Instant};
 
 pub struct TerminalGuard {
     stdout: Stdout,
     mouse_enabled: bool,
+    keyboard_enhanced: bool,
 }
 
+/// Kitty keyboard protocol features the demo opts into: unambiguous keys (`ctrl+i` vs `tab`,
+/// `ctrl+m` vs `enter`, a bare `esc`), repeat/release events and super/hyper/meta, and the
+/// shifted character alongside the base key so `?` still arrives as `?`.
+const KEYBOARD_ENHANCEMENTS: KeyboardEnhancementFlags =
+    KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+        .union(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+        .union(KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS);
+
 impl TerminalGuard {
     pub fn enter(mouse_enabled: bool) -> Result<Self> {
         enable_raw_mode()?;
@@ -28,9 +44,13 @@ impl TerminalGuard {
                 return Err(err.into());
             }
         }
+        // Terminals without the protocol (or that don't answer the query) keep legacy input.
+        let keyboard_enhanced = matches!(supports_keyboard_enhancement(), Ok(true))
+            && execute!(stdout, PushKeyboardEnhancementFlags(KEYBOARD_ENHANCEMENTS)).is_ok();
         Ok(Self {
             stdout,
             mouse_enabled,
+            keyboard_enhanced,
         })
     }
 
@@ -42,10 +62,14 @@ impl TerminalGuard {
 impl Drop for TerminalGuard {
     fn drop(&mut self) {
         // Best-effort restore for happy path and error paths. Ordering is intentional:
-        // 1) show cursor (ratatui may hide it during draws)
-        // 2) disable mouse capture (when enabled)
-        // 3) leave alternate screen
-        // 4) disable raw mode
+        // 1) pop keyboard enhancement flags (when pushed) so the shell gets legacy input back
+        // 2) show cursor (ratatui may hide it during draws)
+        // 3) disable mouse capture (when enabled)
+        // 4) leave alternate screen
+        // 5) disable raw mode
+        if self.keyboard_enhanced {
+            let _ = execute!(self.stdout, PopKeyboardEnhancementFlags);
+        }
         if self.mouse_enabled {
             let _ = execute!(self.stdout, Show, DisableMouseCapture, LeaveAlternateScreen);
         } else {
@@ -54,3 +78,182 @@ impl Drop for TerminalGuard {
         let _ = disable_raw_mode();
     }
 }
+
+/// How long to wait for the terminal to answer a background color query.
+const BACKGROUND_QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+/// How long to keep discarding a slow terminal's replies after giving up on them, so they don't
+/// reach the demo as key presses.
+const BACKGROUND_DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Asks the terminal for its background color (OSC 11) and classifies it as light or dark.
+///
+/// A primary device attributes request (DA1) is sent right after the query; every terminal
+/// answers DA1, so terminals without OSC 11 support end the wait early instead of timing out.
+/// Returns `None` when stdin/stdout are not a TTY, the terminal does not answer, or on non-unix.
+pub fn query_background() -> Option<Background> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return None;
+    }
+
+    enable_raw_mode().ok()?;
+    let response = read_background_response(BACKGROUND_QUERY_TIMEOUT, BACKGROUND_DRAIN_TIMEOUT);
+    let _ = disable_raw_mode();
+
+    let rgb = parse_osc11_response(&response?)?;
+    Some(if relative_luminance(rgb) > 0.5 {
+        Background::Light
+    } else {
+        Background::Dark
+    })
+}
+
+/// Sends the queries and collects what arrives within `timeout`. A reply that is still in flight
+/// then is read and dropped up to the DA1 terminator (for at most `drain`), and anything left is
+/// flushed, so the event loop never sees it.
+#[cfg(unix)]
+fn read_background_response(timeout: Duration, drain: Duration) -> Option<Vec<u8>> {
+    use std::os::fd::AsRawFd;
+
+    let mut stdout = io::stdout();
+    stdout.write_all(b"\x1b]11;?\x1b\\\x1b[c").ok()?;
+    stdout.flush().ok()?;
+
+    let fd = io::stdin().as_raw_fd();
+    let start = Instant::now();
+    let mut response = Vec::new();
+    if !read_until_da1(fd, start + timeout, &mut response) {
+        let mut late = response.clone();
+        read_until_da1(fd, start + timeout + drain, &mut late);
+        // SAFETY: `fd` is stdin, which stays open for the whole process.
+        unsafe { libc::tcflush(fd, libc::TCIFLUSH) };
+    }
+    Some(response)
+}
+
+/// Appends input from `fd` to `response` until the DA1 reply is complete (returns true) or
+/// `deadline` passes.
+#[cfg(unix)]
+fn read_until_da1(fd: libc::c_int, deadline: Instant, response: &mut Vec<u8>) -> bool {
+    let mut buf = [0u8; 64];
+    loop {
+        if contains_da1_reply(response) {
+            return true;
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return false;
+        }
+        let mut pollfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `pollfd` is a valid, initialized struct and we pass a count of one.
+        let ready = unsafe { libc::poll(&mut pollfd, 1, remaining.as_millis() as libc::c_int) };
+        if ready <= 0 {
+            return false;
+        }
+        // SAFETY: `buf` is a valid writable buffer of the given length.
+        let read = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), buf.len()) };
+        if read <= 0 {
+            return false;
+        }
+        response.extend_from_slice(&buf[..read as usize]);
+    }
+}
+
+#[cfg(not(unix))]
+fn read_background_response(_timeout: Duration, _drain: Duration) -> Option<Vec<u8>> {
+    None
+}
+
+/// True once the DA1 reply (`ESC [ ? … c`) has arrived, which terminates the query.
+#[cfg(unix)]
+fn contains_da1_reply(bytes: &[u8]) -> bool {
+    bytes
+        .windows(3)
+        .position(|w| w == b"\x1b[?")
+        .is_some_and(|start| bytes[start..].contains(&b'c'))
+}
+
+/// Extracts the color from an OSC 11 reply such as `ESC ] 11 ; rgb:ffff/ffff/ffff ESC \`.
+/// Components may use 1–4 hex digits; each is scaled to 8 bits.
+fn parse_osc11_response(bytes: &[u8]) -> Option<(u8, u8, u8)> {
+    let text = String::from_utf8_lossy(bytes);
+    let start = text.find("]11;rgb:")? + "]11;rgb:".len();
+    let body = &text[start..];
+    let end = body.find(['\x07', '\x1b']).unwrap_or(body.len());
+
+    let mut parts = body[..end].split('/').map(|part| {
+        if part.is_empty() || part.len() > 4 {
+            return None;
+        }
+        let value = u32::from_str_radix(part, 16).ok()?;
+        let max = (1u32 << (4 * part.len())) - 1;
+        Some(((value * 255 + max / 2) / max) as u8)
+    });
+    let rgb = (parts.next()??, parts.next()??, parts.next()??);
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(rgb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_osc11_response_handles_common_replies() {
+        assert_eq!(
+            parse_osc11_response(b"\x1b]11;rgb:ffff/ffff/ffff\x1b\\\x1b[?62;c"),
+            Some((255, 255, 255))
+        );
+        assert_eq!(
+            parse_osc11_response(b"\x1b]11;rgb:1e1e/1e1e/2e2e\x07"),
+            Some((30, 30, 46))
+        );
+        assert_eq!(
+            parse_osc11_response(b"\x1b]11;rgb:f/8/0\x07"),
+            Some((255, 136, 0))
+        );
+        // Only the DA1 reply: the terminal does not support OSC 11.
+        assert_eq!(parse_osc11_response(b"\x1b[?1;2c"), None);
+        assert_eq!(parse_osc11_response(b"\x1b]11;rgb:zz/00/00\x07"), None);
+        assert_eq!(parse_osc11_response(b"\x1b]11;rgb:00/00\x07"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn contains_da1_reply_waits_for_terminator() {
+        assert!(!contains_da1_reply(b"\x1b]11;rgb:0/0/0\x07\x1b[?6"));
+        assert!(contains_da1_reply(b"\x1b]11;rgb:0/0/0\x07\x1b[?62;c"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_until_da1_consumes_a_reply_split_across_reads() {
+        let mut fds = [0; 2];
+        // SAFETY: `fds` has room for the two descriptors `pipe` writes.
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        let [read_fd, write_fd] = fds;
+        let write = |bytes: &[u8]| {
+            // SAFETY: `bytes` is a valid buffer of the given length.
+            unsafe { libc::write(write_fd, bytes.as_ptr().cast(), bytes.len()) };
+        };
+
+        let deadline = || Instant::now() + Duration::from_millis(50);
+        let mut response = Vec::new();
+        write(b"\x1b]11;rgb:0/0/0\x07\x1b[?6");
+        assert!(!read_until_da1(read_fd, deadline(), &mut response));
+        write(b"2;cq");
+        assert!(read_until_da1(read_fd, deadline(), &mut response));
+        assert_eq!(response, b"\x1b]11;rgb:0/0/0\x07\x1b[?62;cq");
+
+        // SAFETY: both descriptors came from `pipe` above and are closed once.
+        unsafe {
+            libc::close(read_fd);
+            libc::close(write_fd);
+        }
+    }
+}
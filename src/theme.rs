@@ -1,11 +1,18 @@
-use crate::cli::ThemeName;
-use ratatui::style::Color;
+use crate::cli::{Background, CvdSimulation, ThemeName};
+use crate::color::{
+    contrast_ratio, downsample, relative_luminance, simulate_cvd, to_rgb, ColorDepth,
+};
+use anyhow::{anyhow, bail, Result};
+use ratatui::style::{Color, Modifier, Style};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
 pub struct Theme {
-    pub name: &'static str,
-    pub description: &'static str,
+    pub name: String,
+    pub description: String,
     pub palette: ThemePalette,
+    /// Per-slot tweaks layered over the styles derived from `palette`.
+    pub style_overrides: BTreeMap<StyleSlot, StyleOverride>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -18,8 +25,243 @@ pub struct ThemePalette {
     pub danger: Color,
 }
 
+/// The six colors every theme defines, by their config key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteSlot {
+    Fg,
+    Bg,
+    Accent,
+    Muted,
+    Success,
+    Danger,
+}
+
+impl PaletteSlot {
+    pub const ALL: [PaletteSlot; 6] = [
+        PaletteSlot::Fg,
+        PaletteSlot::Bg,
+        PaletteSlot::Accent,
+        PaletteSlot::Muted,
+        PaletteSlot::Success,
+        PaletteSlot::Danger,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PaletteSlot::Fg => "fg",
+            PaletteSlot::Bg => "bg",
+            PaletteSlot::Accent => "accent",
+            PaletteSlot::Muted => "muted",
+            PaletteSlot::Success => "success",
+            PaletteSlot::Danger => "danger",
+        }
+    }
+}
+
+impl ThemePalette {
+    pub fn slot_mut(&mut self, slot: PaletteSlot) -> &mut Color {
+        match slot {
+            PaletteSlot::Fg => &mut self.fg,
+            PaletteSlot::Bg => &mut self.bg,
+            PaletteSlot::Accent => &mut self.accent,
+            PaletteSlot::Muted => &mut self.muted,
+            PaletteSlot::Success => &mut self.success,
+            PaletteSlot::Danger => &mut self.danger,
+        }
+    }
+
+    pub fn map(self, f: impl Fn(Color) -> Color) -> Self {
+        Self {
+            fg: f(self.fg),
+            bg: f(self.bg),
+            accent: f(self.accent),
+            muted: f(self.muted),
+            success: f(self.success),
+            danger: f(self.danger),
+        }
+    }
+}
+
+/// Named UI elements that `ui.rs` styles, so themes can restyle them independently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StyleSlot {
+    Border,
+    BorderFocused,
+    Title,
+    Selection,
+    TabActive,
+    TabInactive,
+    GaugeFill,
+    GaugeEmpty,
+    Warning,
+    Success,
+    Info,
+    HeaderBg,
+    PopupBg,
+}
+
+impl StyleSlot {
+    pub const ALL: [StyleSlot; 13] = [
+        StyleSlot::Border,
+        StyleSlot::BorderFocused,
+        StyleSlot::Title,
+        StyleSlot::Selection,
+        StyleSlot::TabActive,
+        StyleSlot::TabInactive,
+        StyleSlot::GaugeFill,
+        StyleSlot::GaugeEmpty,
+        StyleSlot::Warning,
+        StyleSlot::Success,
+        StyleSlot::Info,
+        StyleSlot::HeaderBg,
+        StyleSlot::PopupBg,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StyleSlot::Border => "border",
+            StyleSlot::BorderFocused => "border_focused",
+            StyleSlot::Title => "title",
+            StyleSlot::Selection => "selection",
+            StyleSlot::TabActive => "tab_active",
+            StyleSlot::TabInactive => "tab_inactive",
+            StyleSlot::GaugeFill => "gauge_fill",
+            StyleSlot::GaugeEmpty => "gauge_empty",
+            StyleSlot::Warning => "warning",
+            StyleSlot::Success => "success",
+            StyleSlot::Info => "info",
+            StyleSlot::HeaderBg => "header_bg",
+            StyleSlot::PopupBg => "popup_bg",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|slot| slot.as_str() == value)
+    }
+}
+
+/// A config override for one style slot. Unset fields keep the palette-derived default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StyleOverride {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underline: Option<bool>,
+}
+
+impl StyleOverride {
+    fn apply(self, mut style: Style) -> Style {
+        if let Some(fg) = self.fg {
+            style = style.fg(fg);
+        }
+        if let Some(bg) = self.bg {
+            style = style.bg(bg);
+        }
+        for (enabled, modifier) in [
+            (self.bold, Modifier::BOLD),
+            (self.italic, Modifier::ITALIC),
+            (self.underline, Modifier::UNDERLINED),
+        ] {
+            style = match enabled {
+                Some(true) => style.add_modifier(modifier),
+                Some(false) => style.remove_modifier(modifier),
+                None => style,
+            };
+        }
+        style
+    }
+
+    fn map_colors(self, f: impl Fn(Color) -> Color) -> Self {
+        Self {
+            fg: self.fg.map(&f),
+            bg: self.bg.map(&f),
+            ..self
+        }
+    }
+}
+
+/// Resolved styles for every [`StyleSlot`]. The gauge reads `gauge_fill.fg` for the filled part
+/// and `gauge_empty.bg` for the track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThemeStyles {
+    pub border: Style,
+    pub border_focused: Style,
+    pub title: Style,
+    pub selection: Style,
+    pub tab_active: Style,
+    pub tab_inactive: Style,
+    pub gauge_fill: Style,
+    pub gauge_empty: Style,
+    pub warning: Style,
+    pub success: Style,
+    pub info: Style,
+    pub header_bg: Style,
+    pub popup_bg: Style,
+}
+
+impl ThemeStyles {
+    /// Defaults derived from the six palette colors.
+    pub fn from_palette(p: ThemePalette) -> Self {
+        let fg = |color: Color| Style::default().fg(color);
+        let base = Style::default().fg(p.fg).bg(p.bg);
+        Self {
+            border: fg(p.muted),
+            border_focused: fg(p.accent),
+            title: fg(p.accent).add_modifier(Modifier::BOLD),
+            selection: fg(p.accent).add_modifier(Modifier::BOLD),
+            tab_active: fg(p.accent).add_modifier(Modifier::BOLD),
+            tab_inactive: fg(p.fg),
+            gauge_fill: fg(p.accent),
+            gauge_empty: Style::default().bg(p.bg),
+            warning: fg(p.danger),
+            success: fg(p.success),
+            info: fg(p.muted),
+            header_bg: base,
+            popup_bg: base,
+        }
+    }
+
+    fn slot_mut(&mut self, slot: StyleSlot) -> &mut Style {
+        match slot {
+            StyleSlot::Border => &mut self.border,
+            StyleSlot::BorderFocused => &mut self.border_focused,
+            StyleSlot::Title => &mut self.title,
+            StyleSlot::Selection => &mut self.selection,
+            StyleSlot::TabActive => &mut self.tab_active,
+            StyleSlot::TabInactive => &mut self.tab_inactive,
+            StyleSlot::GaugeFill => &mut self.gauge_fill,
+            StyleSlot::GaugeEmpty => &mut self.gauge_empty,
+            StyleSlot::Warning => &mut self.warning,
+            StyleSlot::Success => &mut self.success,
+            StyleSlot::Info => &mut self.info,
+            StyleSlot::HeaderBg => &mut self.header_bg,
+            StyleSlot::PopupBg => &mut self.popup_bg,
+        }
+    }
+}
+
 impl Theme {
+    /// Palette-derived styles with this theme's overrides applied.
+    pub fn styles(&self) -> ThemeStyles {
+        let mut styles = ThemeStyles::from_palette(self.palette);
+        for (slot, style_override) in &self.style_overrides {
+            let style = styles.slot_mut(*slot);
+            *style = style_override.apply(*style);
+        }
+        styles
+    }
+
     pub fn with_accessibility(mut self, high_contrast: bool, no_color: bool) -> Self {
+        // Both modes replace the palette wholesale, so override colors would fight it; modifiers
+        // still apply since they don't affect contrast.
+        if high_contrast || no_color {
+            for style_override in self.style_overrides.values_mut() {
+                style_override.fg = None;
+                style_override.bg = None;
+            }
+        }
+
         if no_color {
             self.palette = ThemePalette {
                 fg: Color::Reset,
@@ -32,38 +274,87 @@ impl Theme {
             return self;
         }
 
-        if high_contrast {
+        if high_contrast && self.background() == Background::Light {
+            // Named greens/yellows wash out on white, so use dark 256-color entries instead.
+            self.palette = ThemePalette {
+                fg: Color::Black,
+                bg: Color::White,
+                accent: Color::Indexed(18),
+                muted: Color::Indexed(238),
+                success: Color::Indexed(22),
+                danger: Color::Indexed(88),
+            };
+        } else if high_contrast {
             self.palette = ThemePalette {
                 fg: Color::White,
                 bg: Color::Black,
                 accent: Color::Yellow,
                 muted: Color::Gray,
                 success: Color::Green,
-                danger: Color::Red,
+                // `Red` is only ~3.6:1 on black with xterm defaults; keep high contrast at AA.
+                danger: Color::LightRed,
             };
         }
 
         self
     }
+
+    /// Whether the palette is meant for a light or dark terminal, judged by `bg` luminance.
+    pub fn background(&self) -> Background {
+        match to_rgb(self.palette.bg) {
+            Some(rgb) if relative_luminance(rgb) > 0.5 => Background::Light,
+            _ => Background::Dark,
+        }
+    }
+
+    /// Transforms every palette slot through a color-vision-deficiency simulation.
+    pub fn with_simulation(mut self, simulation: Option<CvdSimulation>) -> Self {
+        if let Some(kind) = simulation {
+            self.palette = self.palette.map(|color| simulate_cvd(color, kind));
+            self.map_override_colors(|color| simulate_cvd(color, kind));
+        }
+        self
+    }
+
+    /// Maps every palette slot down to what the terminal can display.
+    pub fn with_color_depth(mut self, depth: ColorDepth) -> Self {
+        self.palette = self.palette.map(|color| downsample(color, depth));
+        self.map_override_colors(|color| downsample(color, depth));
+        self
+    }
+
+    fn map_override_colors(&mut self, f: impl Fn(Color) -> Color) {
+        for style_override in self.style_overrides.values_mut() {
+            *style_override = style_override.map_colors(&f);
+        }
+    }
+
+    /// Built-in themes are compiled in; everything else comes from config.
+    pub fn is_builtin(&self) -> bool {
+        ThemeName::parse(&self.name).is_some()
+    }
 }
 
 pub fn themes() -> Vec<Theme> {
     vec![
         Theme {
-            name: ThemeName::Aurora.as_str(),
-            description: "Cool blues with a calm accent",
+            name: ThemeName::Aurora.as_str().to_string(),
+            description: "Cool blues with a calm accent".to_string(),
             palette: ThemePalette {
                 fg: Color::White,
                 bg: Color::Black,
-                accent: Color::LightBlue,
+                // xterm's `LightBlue` (#5c5cff) misses WCAG AA on black; 75 is #5fafff and
+                // still downsamples to `LightBlue` in 16-color terminals.
+                accent: Color::Indexed(75),
                 muted: Color::Gray,
                 success: Color::LightGreen,
                 danger: Color::LightRed,
             },
+            style_overrides: BTreeMap::new(),
         },
         Theme {
-            name: ThemeName::Mono.as_str(),
-            description: "Neutral monochrome for maximum focus",
+            name: ThemeName::Mono.as_str().to_string(),
+            description: "Neutral monochrome for maximum focus".to_string(),
             palette: ThemePalette {
                 fg: Color::White,
                 bg: Color::Black,
@@ -72,10 +363,11 @@ pub fn themes() -> Vec<Theme> {
                 success: Color::White,
                 danger: Color::White,
             },
+            style_overrides: BTreeMap::new(),
         },
         Theme {
-            name: ThemeName::Solar.as_str(),
-            description: "Warm highlights with soft contrast",
+            name: ThemeName::Solar.as_str().to_string(),
+            description: "Warm highlights with soft contrast".to_string(),
             palette: ThemePalette {
                 fg: Color::White,
                 bg: Color::Black,
@@ -84,10 +376,245 @@ pub fn themes() -> Vec<Theme> {
                 success: Color::LightGreen,
                 danger: Color::LightRed,
             },
+            style_overrides: BTreeMap::new(),
+        },
+        Theme {
+            name: ThemeName::AuroraLight.as_str().to_string(),
+            description: "Aurora for light terminal backgrounds".to_string(),
+            palette: ThemePalette {
+                fg: Color::Black,
+                bg: Color::White,
+                accent: Color::Indexed(25),
+                muted: Color::Indexed(242),
+                success: Color::Indexed(28),
+                danger: Color::Indexed(124),
+            },
+            style_overrides: BTreeMap::new(),
+        },
+        Theme {
+            name: ThemeName::MonoLight.as_str().to_string(),
+            description: "Mono for light terminal backgrounds".to_string(),
+            palette: ThemePalette {
+                fg: Color::Black,
+                bg: Color::White,
+                accent: Color::Indexed(238),
+                muted: Color::Indexed(242),
+                success: Color::Black,
+                danger: Color::Black,
+            },
+            style_overrides: BTreeMap::new(),
+        },
+        Theme {
+            name: ThemeName::SolarLight.as_str().to_string(),
+            description: "Solar for light terminal backgrounds".to_string(),
+            palette: ThemePalette {
+                fg: Color::Black,
+                bg: Color::White,
+                accent: Color::Indexed(130),
+                muted: Color::Indexed(242),
+                success: Color::Indexed(28),
+                danger: Color::Indexed(124),
+            },
+            style_overrides: BTreeMap::new(),
         },
     ]
 }
 
+/// Theme value that picks aurora or aurora-light based on the terminal background.
+pub const AUTO_THEME: &str = "auto";
+
+/// Suffix pairing a light theme with its dark counterpart (`solar` / `solar-light`).
+const LIGHT_SUFFIX: &str = "-light";
+
+/// Resolves a requested theme (or `auto`) to a concrete theme name, switching to the variant that
+/// matches `background` when one exists. With no background, the theme is used as-is.
+pub fn select_theme(
+    list: &[Theme],
+    requested: &str,
+    background: Option<Background>,
+) -> Result<String> {
+    let requested = requested.trim().to_ascii_lowercase();
+    let requested = if requested == AUTO_THEME {
+        ThemeName::Aurora.as_str().to_string()
+    } else {
+        requested
+    };
+    let theme = find_theme(list, &requested).ok_or_else(|| {
+        anyhow!(
+            "unknown theme '{}'. valid themes: {}, {}",
+            requested,
+            AUTO_THEME,
+            theme_names(list)
+        )
+    })?;
+
+    let base = theme.name.strip_suffix(LIGHT_SUFFIX).unwrap_or(&theme.name);
+    let variant = match background {
+        Some(Background::Light) => format!("{}{}", base, LIGHT_SUFFIX),
+        Some(Background::Dark) => base.to_string(),
+        None => return Ok(theme.name.clone()),
+    };
+    Ok(find_theme(list, &variant)
+        .map(|t| t.name.clone())
+        .unwrap_or_else(|| theme.name.clone()))
+}
+
+/// WCAG AA minimum contrast for normal-size text.
+pub const WCAG_AA_RATIO: f64 = 4.5;
+
+#[derive(Debug, Clone)]
+pub struct ContrastCheck {
+    pub theme: String,
+    /// `normal` or `high-contrast`.
+    pub variant: &'static str,
+    pub slot: &'static str,
+    pub fg: Color,
+    pub bg: Color,
+    /// `None` when a slot uses `reset`, whose color depends on the terminal.
+    pub ratio: Option<f64>,
+}
+
+impl ContrastCheck {
+    pub fn passes_aa(&self) -> bool {
+        self.ratio.is_none_or(|ratio| ratio >= WCAG_AA_RATIO)
+    }
+}
+
+/// Checks every foreground slot against `bg` for each theme and its high-contrast variant.
+pub fn contrast_audit(list: &[Theme]) -> Vec<ContrastCheck> {
+    let mut out = Vec::new();
+    for theme in list {
+        for (variant, high_contrast) in [("normal", false), ("high-contrast", true)] {
+            let palette = theme
+                .clone()
+                .with_accessibility(high_contrast, false)
+                .palette;
+            for (slot, fg) in [
+                ("fg", palette.fg),
+                ("accent", palette.accent),
+                ("muted", palette.muted),
+                ("success", palette.success),
+                ("danger", palette.danger),
+            ] {
+                out.push(ContrastCheck {
+                    theme: theme.name.clone(),
+                    variant,
+                    slot,
+                    fg,
+                    bg: palette.bg,
+                    ratio: contrast_ratio(fg, palette.bg),
+                });
+            }
+        }
+    }
+    out
+}
+
+/// Looks up a theme by name (case-insensitive, surrounding whitespace ignored).
+pub fn find_theme<'a>(list: &'a [Theme], name: &str) -> Option<&'a Theme> {
+    let wanted = name.trim().to_ascii_lowercase();
+    list.iter().find(|theme| theme.name == wanted)
+}
+
+pub fn theme_names(list: &[Theme]) -> String {
+    list.iter()
+        .map(|theme| theme.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Theme names double as CLI values and TOML table keys, so keep them simple.
+pub fn validate_theme_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        bail!("theme name must not be empty");
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        bail!(
+            "invalid theme name '{}' (use lowercase letters, digits, '-' or '_')",
+            name
+        );
+    }
+    if ThemeName::parse(name).is_some() || name == AUTO_THEME {
+        bail!("theme name '{}' is reserved for a built-in theme", name);
+    }
+    Ok(())
+}
+
+/// Parses a palette color from config. Accepts the 16 named ANSI colors (plus `reset`),
+/// `#rrggbb`/`#rgb` truecolor values, and 256-color palette indices like `"208"`.
+pub fn parse_color(value: &str) -> Result<Color> {
+    let trimmed = value.trim();
+    if let Some(hex) = trimmed.strip_prefix('#') {
+        return parse_hex_color(hex)
+            .ok_or_else(|| anyhow::anyhow!("invalid hex color '{}' (expected #rrggbb)", value));
+    }
+    if !trimmed.is_empty() && trimmed.chars().all(|c| c.is_ascii_digit()) {
+        return trimmed
+            .parse::<u8>()
+            .map(Color::Indexed)
+            .map_err(|_| anyhow::anyhow!("invalid color index '{}' (expected 0-255)", value));
+    }
+
+    let normalized: String = value
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, '-' | '_' | ' '))
+        .collect::<String>()
+        .to_ascii_lowercase()
+        .replace("grey", "gray");
+
+    let color = match normalized.as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" => Color::Gray,
+        "darkgray" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => bail!(
+            "unsupported color '{}' (expected a named ANSI color like \"lightblue\", \
+             \"#rrggbb\", or a 0-255 palette index)",
+            value
+        ),
+    };
+
+    Ok(color)
+}
+
+fn parse_hex_color(hex: &str) -> Option<Color> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    match hex.len() {
+        6 => Some(Color::Rgb(
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        )),
+        // Shorthand: `#f80` == `#ff8800`.
+        3 => Some(Color::Rgb(
+            channel(&hex[0..1])? * 17,
+            channel(&hex[1..2])? * 17,
+            channel(&hex[2..3])? * 17,
+        )),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,9 +622,180 @@ mod tests {
     #[test]
     fn themes_have_unique_names() {
         let list = themes();
-        let mut names: Vec<&str> = list.iter().map(|theme| theme.name).collect();
+        let mut names: Vec<&str> = list.iter().map(|theme| theme.name.as_str()).collect();
         names.sort_unstable();
         names.dedup();
         assert_eq!(names.len(), list.len());
     }
+
+    #[test]
+    fn builtin_themes_pass_wcag_aa() {
+        let failures: Vec<_> = contrast_audit(&themes())
+            .into_iter()
+            .filter(|check| !check.passes_aa())
+            .map(|check| format!("{}/{}/{}", check.theme, check.variant, check.slot))
+            .collect();
+        assert!(failures.is_empty(), "failing slots: {:?}", failures);
+    }
+
+    #[test]
+    fn contrast_audit_flags_low_contrast_slots() {
+        let mut theme = themes()[0].clone();
+        theme.palette.muted = Color::Rgb(40, 40, 40);
+
+        let checks = contrast_audit(&[theme]);
+        assert_eq!(checks.len(), 10);
+        let muted = checks
+            .iter()
+            .find(|c| c.variant == "normal" && c.slot == "muted")
+            .unwrap();
+        assert!(!muted.passes_aa());
+        // The high-contrast palette replaces custom colors entirely.
+        assert!(checks
+            .iter()
+            .filter(|c| c.variant == "high-contrast")
+            .all(ContrastCheck::passes_aa));
+    }
+
+    #[test]
+    fn builtin_light_themes_report_light_background() {
+        let list = themes();
+        for theme in &list {
+            let expected = if theme.name.ends_with("-light") {
+                Background::Light
+            } else {
+                Background::Dark
+            };
+            assert_eq!(theme.background(), expected, "{}", theme.name);
+            assert_eq!(
+                theme.clone().with_accessibility(true, false).background(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn select_theme_picks_background_variants() {
+        let list = themes();
+        assert_eq!(select_theme(&list, "auto", None).unwrap(), "aurora");
+        assert_eq!(
+            select_theme(&list, "auto", Some(Background::Light)).unwrap(),
+            "aurora-light"
+        );
+        assert_eq!(
+            select_theme(&list, "solar", Some(Background::Light)).unwrap(),
+            "solar-light"
+        );
+        assert_eq!(
+            select_theme(&list, "solar-light", Some(Background::Dark)).unwrap(),
+            "solar"
+        );
+        assert_eq!(
+            select_theme(&list, "mono-light", None).unwrap(),
+            "mono-light"
+        );
+        assert!(select_theme(&list, "neon", None).is_err());
+
+        // Themes without a light counterpart stay as requested.
+        let mut custom = list.clone();
+        custom.push(Theme {
+            name: "brand".to_string(),
+            description: String::new(),
+            palette: list[0].palette,
+            style_overrides: BTreeMap::new(),
+        });
+        assert_eq!(
+            select_theme(&custom, "brand", Some(Background::Light)).unwrap(),
+            "brand"
+        );
+    }
+
+    #[test]
+    fn styles_derive_from_palette_and_apply_overrides() {
+        let mut theme = themes().remove(0);
+        let derived = theme.styles();
+        assert_eq!(derived.border.fg, Some(theme.palette.muted));
+        assert_eq!(derived.gauge_fill.fg, Some(theme.palette.accent));
+        assert!(derived.title.add_modifier.contains(Modifier::BOLD));
+
+        theme.style_overrides.insert(
+            StyleSlot::Title,
+            StyleOverride {
+                fg: Some(Color::Rgb(255, 0, 255)),
+                bold: Some(false),
+                underline: Some(true),
+                ..StyleOverride::default()
+            },
+        );
+        let styles = theme.styles();
+        assert_eq!(styles.title.fg, Some(Color::Rgb(255, 0, 255)));
+        assert!(!styles.title.add_modifier.contains(Modifier::BOLD));
+        assert!(styles.title.add_modifier.contains(Modifier::UNDERLINED));
+        assert_eq!(styles.border, derived.border);
+
+        let downsampled = theme.clone().with_color_depth(ColorDepth::Ansi256).styles();
+        assert!(matches!(downsampled.title.fg, Some(Color::Indexed(_))));
+
+        // High contrast swaps in its own palette, so override colors give way but modifiers stay.
+        let high_contrast = theme.with_accessibility(true, false).styles();
+        assert_eq!(high_contrast.title.fg, Some(Color::Yellow));
+        assert!(high_contrast
+            .title
+            .add_modifier
+            .contains(Modifier::UNDERLINED));
+    }
+
+    #[test]
+    fn style_slot_names_round_trip() {
+        for slot in StyleSlot::ALL {
+            assert_eq!(StyleSlot::parse(slot.as_str()), Some(slot));
+        }
+        assert_eq!(StyleSlot::parse("borders"), None);
+    }
+
+    #[test]
+    fn find_theme_is_case_insensitive() {
+        let list = themes();
+        assert_eq!(find_theme(&list, " SOLAR ").unwrap().name, "solar");
+        assert!(find_theme(&list, "neon").is_none());
+    }
+
+    #[test]
+    fn parse_color_accepts_named_colors() {
+        assert_eq!(parse_color("LightBlue").unwrap(), Color::LightBlue);
+        assert_eq!(parse_color("dark-grey").unwrap(), Color::DarkGray);
+        assert_eq!(parse_color("dark_gray").unwrap(), Color::DarkGray);
+        assert!(parse_color("chartreuse").is_err());
+    }
+
+    #[test]
+    fn parse_color_accepts_hex_and_indexed_colors() {
+        assert_eq!(parse_color("#ff8800").unwrap(), Color::Rgb(255, 136, 0));
+        assert_eq!(parse_color("#F80").unwrap(), Color::Rgb(255, 136, 0));
+        assert_eq!(parse_color("208").unwrap(), Color::Indexed(208));
+        assert!(parse_color("#ff88").is_err());
+        assert!(parse_color("#gg0000").is_err());
+        assert!(parse_color("256").is_err());
+    }
+
+    #[test]
+    fn with_color_depth_downsamples_every_slot() {
+        let mut theme = themes()[0].clone();
+        theme.palette.accent = Color::Rgb(255, 135, 0);
+        theme.palette.bg = Color::Indexed(236);
+
+        let theme = theme.with_color_depth(ColorDepth::Ansi16);
+        assert_eq!(theme.palette.accent, Color::Yellow);
+        assert_eq!(theme.palette.bg, Color::Black);
+        assert_eq!(theme.palette.fg, Color::White);
+    }
+
+    #[test]
+    fn validate_theme_name_rejects_builtins_and_odd_names() {
+        validate_theme_name("brand-dark").unwrap();
+        assert!(validate_theme_name("aurora").is_err());
+        assert!(validate_theme_name("auto").is_err());
+        assert!(validate_theme_name("Brand").is_err());
+        assert!(validate_theme_name("").is_err());
+    }
 }
@@ -1,5 +1,6 @@
 use crate::app::{App, DemoPanel};
 use crate::keys;
+use crate::theme::{Theme, ThemeStyles};
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span, Text};
@@ -31,21 +32,19 @@ pub fn draw(frame: &mut Frame, app: &App) {
     }
 }
 
-fn draw_header(frame: &mut Frame, area: Rect, app: &App, theme: &crate::theme::Theme) {
-    let base = Style::default().fg(theme.palette.fg).bg(theme.palette.bg);
+fn draw_header(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
+    let styles = theme.styles();
+    let base = styles.header_bg;
 
     let title = Line::from(vec![
-        Span::styled(
-            " CLI TUI Starter ",
-            base.fg(theme.palette.accent).add_modifier(Modifier::BOLD),
-        ),
+        Span::styled(" CLI TUI Starter ", base.patch(styles.title)),
         Span::raw(" "),
-        Span::styled(app.spinner_frame(), base.fg(theme.palette.muted)),
+        Span::styled(app.spinner_frame(), base.patch(styles.info)),
         Span::raw(" "),
-        Span::styled("ready", base.fg(theme.palette.success)),
+        Span::styled("ready", base.patch(styles.success)),
     ]);
 
-    let info_lines = header_info_lines(area, app, base, theme);
+    let info_lines = header_info_lines(area, app, base, &styles);
 
     let mut lines = Vec::with_capacity(1 + info_lines.len());
     lines.push(title);
@@ -56,14 +55,15 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App, theme: &crate::theme::T
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(theme.palette.muted)),
+                .border_style(styles.border),
         )
         .style(base);
 
     frame.render_widget(header, area);
 }
 
-fn draw_body(frame: &mut Frame, area: Rect, app: &App, theme: &crate::theme::Theme) {
+fn draw_body(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
+    let styles = theme.styles();
     let base = Style::default().fg(theme.palette.fg).bg(theme.palette.bg);
 
     let commands = List::new(vec![
@@ -75,17 +75,17 @@ fn draw_body(frame: &mut Frame, area: Rect, app: &App, theme: &crate::theme::The
         Block::default()
             .title(" Commands ")
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(theme.palette.muted)),
+            .border_style(styles.border),
     )
     .style(base);
 
     let showcase_block = Block::default()
         .title(" Showcase ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(theme.palette.muted))
+        .border_style(styles.border)
         .style(base);
 
-    let accessibility = accessibility_panel(area, app, base, theme);
+    let accessibility = accessibility_panel(area, app, base, &styles);
 
     if is_narrow(area) {
         let stack = Layout::default()
@@ -125,8 +125,9 @@ fn draw_showcase_contents(
     block: Block<'_>,
     area: Rect,
     app: &App,
-    theme: &crate::theme::Theme,
+    theme: &Theme,
 ) {
+    let styles = theme.styles();
     let base = Style::default().fg(theme.palette.fg).bg(theme.palette.bg);
     let inner = block.inner(area);
 
@@ -139,29 +140,134 @@ fn draw_showcase_contents(
         .constraints([Constraint::Length(1), Constraint::Min(0)])
         .split(inner);
 
-    let tab_titles = vec![
-        Line::from(Span::raw("Overview")),
-        Line::from(Span::raw("List")),
-    ];
+    let tab_titles = DemoPanel::ALL
+        .iter()
+        .map(|panel| Line::from(Span::raw(panel.title())))
+        .collect::<Vec<_>>();
 
     let tabs = Tabs::new(tab_titles)
         .select(app.panel().index())
-        .highlight_style(base.fg(theme.palette.accent).add_modifier(Modifier::BOLD))
-        .style(base);
+        .highlight_style(base.patch(styles.tab_active))
+        .style(base.patch(styles.tab_inactive));
 
     frame.render_widget(tabs, layout[0]);
 
     match app.panel() {
-        DemoPanel::Overview => draw_showcase_overview(frame, layout[1], app, theme, base),
-        DemoPanel::List => draw_showcase_list(frame, layout[1], app, theme, base),
+        DemoPanel::Overview => draw_showcase_overview(frame, layout[1], app, &styles, base),
+        DemoPanel::List => draw_showcase_list(frame, layout[1], app, &styles, base),
+        DemoPanel::Keys => draw_showcase_keys(frame, layout[1], app, &styles, base),
+    }
+}
+
+fn draw_showcase_keys(frame: &mut Frame, area: Rect, app: &App, styles: &ThemeStyles, base: Style) {
+    if area.width <= 2 || area.height <= 2 {
+        return;
+    }
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(area);
+
+    let editor = app.key_editor();
+    let hint = Paragraph::new(Line::from(Span::styled(
+        "up/down | enter set | del remove | ctrl+s save",
+        base.patch(styles.info),
+    )))
+    .style(base);
+    frame.render_widget(hint, layout[0]);
+
+    // One line per binding; the selected action also gets an add slot after its bindings.
+    let mut lines = Vec::new();
+    let mut selected_line = 0;
+    for (context, action) in editor.rows() {
+        let bindings = editor.draft().context_keys(context, action);
+        let is_selected_row = (context, action) == editor.selected_row();
+        let name = if context == action.home_context() {
+            action.description().to_string()
+        } else {
+            format!("{} in {}", action.description(), context.as_str())
+        };
+        let marker = if editor.is_edited(context, action) {
+            " *"
+        } else {
+            ""
+        };
+        let slots = bindings.len() + usize::from(is_selected_row);
+        for slot in 0..slots.max(1) {
+            let is_selected = is_selected_row && slot == editor.selected_binding();
+            let keys = match (is_selected, editor.captured(), bindings.get(slot)) {
+                (true, Some([]), _) => "press keys...".to_string(),
+                (true, Some(typed), _) => format!(
+                    "{} ...",
+                    typed
+                        .iter()
+                        .map(|&key| keys::key_spec_display(key))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                ),
+                (_, _, Some(sequence)) => keys::key_sequence_display(sequence),
+                (_, _, None) if is_selected_row => "+ add".to_string(),
+                (_, _, None) => "(none)".to_string(),
+            };
+            let prefix = if is_selected { "> " } else { "  " };
+            let (name, marker) = if slot == 0 {
+                (name.as_str(), marker)
+            } else {
+                ("", "")
+            };
+            let label = format!("{prefix}{name:<22}{keys}{marker}");
+            if is_selected {
+                selected_line = lines.len();
+                lines.push(ListItem::new(Line::from(Span::styled(
+                    label,
+                    base.patch(styles.selection),
+                ))));
+            } else {
+                lines.push(ListItem::new(Line::from(Span::raw(label))));
+            }
+        }
     }
+
+    let rows_area = layout[1];
+    if rows_area.height > 0 {
+        let viewport = rows_area.height as usize;
+        let start = (selected_line + 1).saturating_sub(viewport);
+        let visible = lines
+            .into_iter()
+            .skip(start)
+            .take(viewport)
+            .collect::<Vec<_>>();
+        frame.render_widget(List::new(visible).style(base), rows_area);
+    }
+
+    // Conflicts show up as soon as a key is captured, before anything is saved.
+    let status = match (editor.error(), editor.is_capturing()) {
+        (Some(error), _) => Span::styled(error.to_string(), base.patch(styles.warning)),
+        (None, true) => Span::styled(
+            format!(
+                "Keys for '{}' (pause to finish, esc cancels)",
+                editor.selected_action().description()
+            ),
+            base.patch(styles.info),
+        ),
+        (None, false) if editor.has_edits() => {
+            Span::styled("* = unsaved (ctrl+s saves)", base.patch(styles.info))
+        }
+        (None, false) => Span::raw(""),
+    };
+    frame.render_widget(Paragraph::new(Line::from(status)).style(base), layout[2]);
 }
 
 fn draw_showcase_overview(
     frame: &mut Frame,
     area: Rect,
     app: &App,
-    theme: &crate::theme::Theme,
+    styles: &ThemeStyles,
     base: Style,
 ) {
     if area.width <= 2 || area.height <= 2 {
@@ -192,12 +298,12 @@ fn draw_showcase_overview(
 
     let mut theme_info_lines = vec![Line::from(Span::styled(
         app.current_theme_name(),
-        base.fg(theme.palette.accent).add_modifier(Modifier::BOLD),
+        base.patch(styles.title),
     ))];
     if theme_lines >= 2 {
         theme_info_lines.push(Line::from(Span::styled(
             app.current_theme_description(),
-            base.fg(theme.palette.muted),
+            base.patch(styles.info),
         )));
     }
     if theme_lines >= 3 {
@@ -214,7 +320,7 @@ fn draw_showcase_overview(
 
     let gauge = Gauge::default()
         .ratio(progress_ratio)
-        .gauge_style(base.fg(theme.palette.accent))
+        .gauge_style(gauge_style(base, styles))
         .label(format!("{progress_percent}%"));
 
     let mut rows = vec![
@@ -240,7 +346,7 @@ fn draw_showcase_overview(
     )
     .header(
         Row::new(vec![Cell::from("Action"), Cell::from("Key")])
-            .style(base.fg(theme.palette.muted).add_modifier(Modifier::BOLD)),
+            .style(base.patch(styles.info).add_modifier(Modifier::BOLD)),
     )
     .column_spacing(1)
     .style(base);
@@ -250,13 +356,7 @@ fn draw_showcase_overview(
     frame.render_widget(table, layout[2]);
 }
 
-fn draw_showcase_list(
-    frame: &mut Frame,
-    area: Rect,
-    app: &App,
-    theme: &crate::theme::Theme,
-    base: Style,
-) {
+fn draw_showcase_list(frame: &mut Frame, area: Rect, app: &App, styles: &ThemeStyles, base: Style) {
     if area.width <= 2 || area.height <= 2 {
         return;
     }
@@ -269,7 +369,7 @@ fn draw_showcase_list(
     let hint = Paragraph::new(Text::from(vec![
         Line::from(Span::styled(
             "Scrolling list demo",
-            base.fg(theme.palette.accent).add_modifier(Modifier::BOLD),
+            base.patch(styles.title),
         )),
         Line::from(Span::styled(
             format!(
@@ -278,7 +378,7 @@ fn draw_showcase_list(
                 keys::key_list_display(&app.keymap.list_down),
                 keys::key_list_display(&app.keymap.next_panel)
             ),
-            base.fg(theme.palette.muted),
+            base.patch(styles.info),
         )),
     ]))
     .wrap(Wrap { trim: true })
@@ -307,10 +407,7 @@ fn draw_showcase_list(
             let prefix = if is_selected { "> " } else { "  " };
             let label = format!("{prefix}Item {:02}", i + 1);
             let line = if is_selected {
-                Line::from(Span::styled(
-                    label,
-                    base.fg(theme.palette.accent).add_modifier(Modifier::BOLD),
-                ))
+                Line::from(Span::styled(label, base.patch(styles.selection)))
             } else {
                 Line::from(Span::raw(label))
             };
@@ -326,14 +423,14 @@ fn accessibility_panel(
     area: Rect,
     app: &App,
     base: Style,
-    theme: &crate::theme::Theme,
+    styles: &ThemeStyles,
 ) -> Paragraph<'static> {
     // In narrow/short layouts, prefer a compact one-liner (help overlay contains the full list).
     if area.width < 90 || area.height < 20 {
         let compact = Line::from(vec![
             Span::styled(
                 "Keys: ",
-                base.fg(theme.palette.muted).add_modifier(Modifier::BOLD),
+                base.patch(styles.info).add_modifier(Modifier::BOLD),
             ),
             Span::raw(format!(
                 "{} contrast | {} color | {} motion | {} help | {} quit",
@@ -351,7 +448,7 @@ fn accessibility_panel(
                 Block::default()
                     .title(" Accessibility ")
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(theme.palette.muted)),
+                    .border_style(styles.border),
             )
             .style(base);
     }
@@ -362,7 +459,7 @@ fn accessibility_panel(
                 "{}: high contrast",
                 keys::key_list_display(&app.keymap.toggle_high_contrast)
             ),
-            base.fg(theme.palette.accent),
+            base.patch(styles.title),
         )),
         Line::from(format!(
             "{}: toggle color",
@@ -382,47 +479,67 @@ fn accessibility_panel(
         Block::default()
             .title(" Accessibility ")
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(theme.palette.muted)),
+            .border_style(styles.border),
     )
     .style(base)
 }
 
-fn draw_footer(frame: &mut Frame, area: Rect, app: &App, theme: &crate::theme::Theme) {
+fn draw_footer(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
+    let styles = theme.styles();
     let base = Style::default().fg(theme.palette.fg).bg(theme.palette.bg);
 
-    let footer = Paragraph::new(Text::from(Line::from(vec![
-        Span::styled(
-            format!(
-                "Press {} for help.",
-                keys::key_list_display(&app.keymap.toggle_help)
-            ),
-            base.fg(theme.palette.muted),
-        ),
-        Span::raw(" "),
-        Span::styled(
-            format!("Use {} to exit.", app.keymap.quit_label()),
-            base.fg(theme.palette.danger),
-        ),
-    ])))
-    .alignment(Alignment::Center)
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(theme.palette.muted)),
-    )
-    .style(base);
+    // A half-typed key sequence, then a pending status (e.g. a config reload result), temporarily
+    // replace the key hints.
+    let line = if let Some(label) = app.pending_keys_label() {
+        Line::from(Span::styled(
+            format!("{} … (esc to cancel)", label),
+            base.patch(styles.info),
+        ))
+    } else {
+        match app.status() {
+            Some(status) => Line::from(Span::styled(
+                status.text.clone(),
+                base.patch(if status.is_error {
+                    styles.warning
+                } else {
+                    styles.info
+                }),
+            )),
+            None => Line::from(vec![
+                Span::styled(
+                    format!(
+                        "Press {} for help.",
+                        keys::key_list_display(&app.keymap.toggle_help)
+                    ),
+                    base.patch(styles.info),
+                ),
+                Span::raw(" "),
+                Span::styled(
+                    format!("Use {} to exit.", app.keymap.quit_label()),
+                    base.patch(styles.warning),
+                ),
+            ]),
+        }
+    };
+
+    let footer = Paragraph::new(Text::from(line))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(styles.border),
+        )
+        .style(base);
 
     frame.render_widget(footer, area);
 }
 
-fn draw_help(frame: &mut Frame, area: Rect, app: &App, theme: &crate::theme::Theme) {
-    let base = Style::default().fg(theme.palette.fg).bg(theme.palette.bg);
+fn draw_help(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
+    let styles = theme.styles();
+    let base = styles.popup_bg;
     let popup_area = centered_popup_rect(area);
     let mut help_lines = vec![
-        Line::from(Span::styled(
-            "Keys",
-            base.fg(theme.palette.accent).add_modifier(Modifier::BOLD),
-        )),
+        Line::from(Span::styled("Keys", base.patch(styles.title))),
         Line::from(format!(
             "{}: cycle theme",
             keys::key_list_display(&app.keymap.cycle_theme)
@@ -454,13 +571,14 @@ fn draw_help(frame: &mut Frame, area: Rect, app: &App, theme: &crate::theme::The
             keys::key_list_display(&app.keymap.toggle_help)
         )),
         Line::from(format!("{}: quit", app.keymap.quit_label())),
+        Line::from("Keys panel: enter rebinds the selected action, ctrl+s saves"),
     ];
 
     if app.mouse_enabled {
         help_lines.push(Line::from(""));
         help_lines.push(Line::from(Span::styled(
             "Mouse (opt-in)",
-            base.fg(theme.palette.accent).add_modifier(Modifier::BOLD),
+            base.patch(styles.title),
         )));
         help_lines.push(Line::from("- Scroll wheel: move list selection"));
         help_lines.push(Line::from("- Left click tabs: switch panel"));
@@ -469,7 +587,7 @@ fn draw_help(frame: &mut Frame, area: Rect, app: &App, theme: &crate::theme::The
     help_lines.push(Line::from(""));
     help_lines.push(Line::from(Span::styled(
         "Accessibility",
-        base.fg(theme.palette.accent).add_modifier(Modifier::BOLD),
+        base.patch(styles.title),
     )));
     help_lines.push(Line::from("- No-color mode for screen readers"));
     help_lines.push(Line::from("- High-contrast palette"));
@@ -481,7 +599,7 @@ fn draw_help(frame: &mut Frame, area: Rect, app: &App, theme: &crate::theme::The
             Block::default()
                 .title(" Help ")
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(theme.palette.accent)),
+                .border_style(styles.border_focused),
         )
         .style(base);
 
@@ -489,6 +607,12 @@ fn draw_help(frame: &mut Frame, area: Rect, app: &App, theme: &crate::theme::The
     frame.render_widget(help_text, popup_area);
 }
 
+/// The gauge draws its fill with `gauge_fill.fg` over a track colored by `gauge_empty.bg`.
+fn gauge_style(base: Style, styles: &ThemeStyles) -> Style {
+    base.patch(styles.gauge_fill)
+        .bg(styles.gauge_empty.bg.or(base.bg).unwrap_or_default())
+}
+
 fn on_off(value: bool) -> &'static str {
     if value {
         "on"
@@ -505,38 +629,35 @@ fn header_info_lines(
     area: Rect,
     app: &App,
     base: Style,
-    theme: &crate::theme::Theme,
+    styles: &ThemeStyles,
 ) -> Vec<Line<'static>> {
-    let theme_label = Span::styled(
-        format!("Theme: {}", app.current_theme_name()),
-        base.fg(theme.palette.fg),
-    );
+    let theme_label = Span::styled(format!("Theme: {}", app.current_theme_name()), base);
 
     let high_contrast = Span::styled(
         format!("High contrast: {}", on_off(app.high_contrast)),
-        base.fg(theme.palette.muted),
+        base.patch(styles.info),
     );
 
     let no_color = Span::styled(
         format!("No color: {}", on_off(app.no_color)),
-        base.fg(theme.palette.muted),
+        base.patch(styles.info),
     );
 
     let reduced_motion = Span::styled(
         format!("Reduced motion: {}", on_off(app.reduced_motion)),
-        base.fg(theme.palette.muted),
+        base.patch(styles.info),
     );
 
-    if area.width < 70 {
-        vec![Line::from(vec![
+    let mut spans = if area.width < 70 {
+        vec![
             theme_label,
             Span::raw(" | "),
             no_color,
             Span::raw(" | "),
             reduced_motion,
-        ])]
+        ]
     } else {
-        vec![Line::from(vec![
+        vec![
             theme_label,
             Span::raw(" | "),
             high_contrast,
@@ -544,8 +665,20 @@ fn header_info_lines(
             no_color,
             Span::raw(" | "),
             reduced_motion,
-        ])]
+        ]
+    };
+
+    // Make it obvious that colors are being transformed so screenshots aren't mistaken for the
+    // real palette.
+    if let Some(simulation) = app.simulation {
+        spans.push(Span::raw(" | "));
+        spans.push(Span::styled(
+            format!("Simulating: {}", simulation.as_str()),
+            base.patch(styles.warning),
+        ));
     }
+
+    vec![Line::from(spans)]
 }
 
 fn centered_popup_rect(area: Rect) -> Rect {
@@ -570,11 +703,14 @@ fn centered_popup_rect(area: Rect) -> Rect {
     }
 }
 
+/// Renders one frame of the demo as text. With `ansi`, cell colors are emitted as SGR escape
+/// codes (used for `--simulate`, which is pointless without color).
 pub fn render_static_preview(
     app: &App,
     width: u16,
     height: u16,
     ascii: bool,
+    ansi: bool,
 ) -> anyhow::Result<String> {
     use ratatui::backend::TestBackend;
     use ratatui::Terminal;
@@ -583,9 +719,156 @@ pub fn render_static_preview(
     let mut terminal = Terminal::new(backend)?;
     terminal.draw(|frame| draw(frame, app))?;
 
-    let buffer = terminal.backend().buffer();
-    let mut lines = Vec::<String>::with_capacity(height as usize);
+    Ok(buffer_to_text(terminal.backend().buffer(), ascii, ansi))
+}
+
+/// Height of one `themes preview` card, including its border.
+const PREVIEW_CARD_HEIGHT: u16 = 11;
+/// Cards narrower than this wrap the swatch labels, so fewer cards go on each row instead.
+const PREVIEW_CARD_MIN_WIDTH: u16 = 38;
+
+/// Renders one card per theme (palette swatches plus a sample gauge, table and list), laid out in
+/// as many columns as fit in `width`. With `ansi`, cell colors are emitted as SGR escape codes.
+pub fn render_theme_previews(
+    themes: &[Theme],
+    width: u16,
+    ascii: bool,
+    ansi: bool,
+) -> anyhow::Result<String> {
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    let columns = (width / PREVIEW_CARD_MIN_WIDTH).clamp(1, themes.len().max(1) as u16);
+    let rows = themes.len().div_ceil(columns as usize) as u16;
+    let height = rows * PREVIEW_CARD_HEIGHT;
+    if height == 0 {
+        return Ok(String::new());
+    }
+
+    let backend = TestBackend::new(width, height);
+    let mut terminal = Terminal::new(backend)?;
+    terminal.draw(|frame| {
+        let card_width = width / columns;
+        for (index, theme) in themes.iter().enumerate() {
+            let area = Rect::new(
+                (index as u16 % columns) * card_width,
+                (index as u16 / columns) * PREVIEW_CARD_HEIGHT,
+                card_width,
+                PREVIEW_CARD_HEIGHT,
+            );
+            draw_theme_card(frame, area, theme);
+        }
+    })?;
+
+    Ok(buffer_to_text(terminal.backend().buffer(), ascii, ansi))
+}
+
+fn draw_theme_card(frame: &mut Frame, area: Rect, theme: &Theme) {
+    let styles = theme.styles();
+    let p = theme.palette;
+    let base = Style::default().fg(p.fg).bg(p.bg);
+
+    let block = Block::default()
+        .title(Span::styled(
+            format!(" {} ", theme.name),
+            base.patch(styles.title),
+        ))
+        .borders(Borders::ALL)
+        .border_style(styles.border)
+        .style(base);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    if inner.width <= 2 || inner.height < PREVIEW_CARD_HEIGHT - 2 {
+        return;
+    }
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Min(0),
+        ])
+        .split(inner);
+
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            theme.description.clone(),
+            base.patch(styles.info),
+        )),
+        layout[0],
+    );
+
+    let swatch = |name: &'static str, color| {
+        vec![
+            Span::styled("██", base.fg(color)),
+            Span::styled(format!(" {:<9}", name), base),
+        ]
+    };
+    let swatches = Text::from(vec![
+        Line::from(
+            [
+                swatch("fg", p.fg),
+                swatch("bg", p.bg),
+                swatch("accent", p.accent),
+            ]
+            .concat(),
+        ),
+        Line::from(
+            [
+                swatch("muted", p.muted),
+                swatch("success", p.success),
+                swatch("danger", p.danger),
+            ]
+            .concat(),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(swatches).style(base), layout[1]);
+
+    frame.render_widget(
+        Gauge::default()
+            .ratio(0.62)
+            .gauge_style(gauge_style(base, &styles))
+            .label("62%"),
+        layout[2],
+    );
+
+    let table = Table::new(
+        vec![
+            Row::new(vec![
+                Cell::from("build"),
+                Cell::from(Span::styled("ok", base.fg(p.success))),
+            ]),
+            Row::new(vec![
+                Cell::from("deploy"),
+                Cell::from(Span::styled("failed", base.fg(p.danger))),
+            ]),
+        ],
+        [Constraint::Percentage(55), Constraint::Percentage(45)],
+    )
+    .header(
+        Row::new(vec![Cell::from("Task"), Cell::from("Status")])
+            .style(base.patch(styles.info).add_modifier(Modifier::BOLD)),
+    )
+    .column_spacing(1)
+    .style(base);
+    frame.render_widget(table, layout[3]);
+
+    let list = List::new(vec![
+        ListItem::new(Line::from(Span::styled(
+            "> Item 01",
+            base.patch(styles.selection),
+        ))),
+        ListItem::new("  Item 02"),
+    ])
+    .style(base);
+    frame.render_widget(list, layout[4]);
+}
 
+/// Serializes a rendered buffer line by line, trimming trailing blank cells.
+fn buffer_to_text(buffer: &ratatui::buffer::Buffer, ascii: bool, ansi: bool) -> String {
     fn ascii_symbol(sym: &str) -> &str {
         // Keep this small and conservative: only map the glyphs we render today.
         // Unknown non-ASCII symbols become '?' to keep output machine-safe.
@@ -608,32 +891,109 @@ pub fn render_static_preview(
         }
     }
 
-    for y in 0..height {
+    let area = buffer.area;
+    let mut lines = Vec::<String>::with_capacity(area.height as usize);
+    for y in 0..area.height {
+        // A blank cell still matters in ANSI mode when it paints a background.
+        let visible = |x: u16| {
+            let cell = &buffer[(x, y)];
+            cell.symbol() != " " || (ansi && cell.bg != ratatui::style::Color::Reset)
+        };
+        let end = (0..area.width)
+            .rev()
+            .find(|&x| visible(x))
+            .map_or(0, |x| x + 1);
+
         let mut line = String::new();
-        for x in 0..width {
-            let sym = buffer[(x, y)].symbol();
+        let mut current: Option<String> = None;
+        for x in 0..end {
+            let cell = &buffer[(x, y)];
+            if ansi {
+                let sgr = sgr_sequence(cell.fg, cell.bg, cell.modifier);
+                if current.as_ref() != Some(&sgr) {
+                    line.push_str(&sgr);
+                    current = Some(sgr);
+                }
+            }
+            let sym = cell.symbol();
             if ascii {
                 line.push_str(ascii_symbol(sym));
             } else {
                 line.push_str(sym);
             }
         }
-        lines.push(line.trim_end_matches(' ').to_string());
+        if current.is_some() {
+            line.push_str("\x1b[0m");
+        }
+        lines.push(line);
     }
 
     while lines.last().is_some_and(|l| l.is_empty()) {
         lines.pop();
     }
 
-    Ok(lines.join("\n") + "\n")
+    lines.join("\n") + "\n"
+}
+
+/// A full SGR sequence (reset, modifiers, colors) for one cell style.
+fn sgr_sequence(
+    fg: ratatui::style::Color,
+    bg: ratatui::style::Color,
+    modifier: Modifier,
+) -> String {
+    use ratatui::style::Color;
+
+    fn color_code(color: Color, background: bool) -> Option<String> {
+        let offset = if background { 10 } else { 0 };
+        let named = |code: u8| Some((code + offset).to_string());
+        match color {
+            Color::Reset => None,
+            Color::Black => named(30),
+            Color::Red => named(31),
+            Color::Green => named(32),
+            Color::Yellow => named(33),
+            Color::Blue => named(34),
+            Color::Magenta => named(35),
+            Color::Cyan => named(36),
+            Color::Gray => named(37),
+            Color::DarkGray => named(90),
+            Color::LightRed => named(91),
+            Color::LightGreen => named(92),
+            Color::LightYellow => named(93),
+            Color::LightBlue => named(94),
+            Color::LightMagenta => named(95),
+            Color::LightCyan => named(96),
+            Color::White => named(97),
+            Color::Indexed(i) => Some(format!("{};5;{}", 38 + offset, i)),
+            Color::Rgb(r, g, b) => Some(format!("{};2;{};{};{}", 38 + offset, r, g, b)),
+        }
+    }
+
+    let mut codes = vec!["0".to_string()];
+    for (flag, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+    ] {
+        if modifier.contains(flag) {
+            codes.push(code.to_string());
+        }
+    }
+    codes.extend(color_code(fg, false));
+    codes.extend(color_code(bg, true));
+    format!("\x1b[{}m", codes.join(";"))
 }
 
 #[cfg(test)]
 mod tests {
     use crate::app::{App, DemoPanel};
-    use crate::cli::ThemeName;
-    use crate::keys::{parse_key_spec, KeyBindings};
+    use crate::cli::{CvdSimulation, KeymapPreset};
+    use crate::keys::{parse_key_sequence, KeyBindings};
+    use crate::theme::{themes, StyleOverride, StyleSlot};
     use ratatui::backend::TestBackend;
+    use ratatui::style::Color;
     use ratatui::Terminal;
 
     fn render_lines(width: u16, height: u16, app: &App) -> Vec<String> {
@@ -660,7 +1020,8 @@ mod tests {
     #[test]
     fn narrow_layout_stacks_sections_vertically() {
         let app = App::new(
-            ThemeName::Aurora,
+            themes(),
+            "aurora",
             true,  // no_color for stable rendering
             false, // high_contrast
             true,  // reduced_motion
@@ -680,7 +1041,8 @@ mod tests {
     #[test]
     fn wide_layout_places_commands_left_and_panels_right() {
         let app = App::new(
-            ThemeName::Aurora,
+            themes(),
+            "aurora",
             true,
             false,
             true,
@@ -700,14 +1062,15 @@ mod tests {
     #[test]
     fn help_panel_uses_active_keymap_labels() {
         let keymap = KeyBindings {
-            cycle_theme: vec![parse_key_spec("n").unwrap()],
-            toggle_help: vec![parse_key_spec("!").unwrap()],
+            cycle_theme: vec![parse_key_sequence("n").unwrap()],
+            toggle_help: vec![parse_key_sequence("!").unwrap()],
             ..KeyBindings::default()
         };
         keymap.validate().unwrap();
 
         let mut app = App::new(
-            ThemeName::Aurora,
+            themes(),
+            "aurora",
             true,
             false,
             true,
@@ -723,10 +1086,28 @@ mod tests {
         assert!(merged.contains("!: toggle help"));
     }
 
+    #[test]
+    fn header_shows_active_cvd_simulation() {
+        let app = App::new(
+            themes(),
+            "aurora",
+            false,
+            false,
+            true,
+            KeyBindings::default(),
+            DemoPanel::Overview,
+        )
+        .with_simulation(Some(CvdSimulation::Deuteranopia));
+
+        let lines = render_lines(120, 24, &app);
+        assert!(lines.join("\n").contains("Simulating: deuteranopia"));
+    }
+
     #[test]
     fn list_panel_renders_selection_and_hint() {
         let app = App::new(
-            ThemeName::Aurora,
+            themes(),
+            "aurora",
             true,
             false,
             true,
@@ -740,4 +1121,152 @@ mod tests {
         assert!(merged.contains("Scrolling list demo"));
         assert!(merged.contains("> Item 01"));
     }
+
+    #[test]
+    fn keys_panel_lists_every_binding_of_the_selected_action() {
+        let app = App::new(
+            themes(),
+            "aurora",
+            false,
+            false,
+            true,
+            KeyBindings::preset(KeymapPreset::Vim),
+            DemoPanel::Keys,
+        );
+
+        let lines = render_lines(100, 30, &app);
+        let merged = lines.join("\n");
+        assert!(merged.contains("> cycle theme"));
+        assert!(merged.contains("+ add"));
+        let next = lines
+            .iter()
+            .position(|line| line.contains("next panel"))
+            .expect("next panel row");
+        assert!(lines[next].contains("g t"));
+        assert!(lines[next + 1].contains("tab"));
+        assert!(!lines[next + 1].contains("next panel"));
+    }
+
+    #[test]
+    fn borders_popup_and_status_read_theme_style_slots() {
+        let mut list = themes();
+        list[0].style_overrides.insert(
+            StyleSlot::Border,
+            StyleOverride {
+                fg: Some(Color::Magenta),
+                ..StyleOverride::default()
+            },
+        );
+        list[0].style_overrides.insert(
+            StyleSlot::Success,
+            StyleOverride {
+                fg: Some(Color::Yellow),
+                ..StyleOverride::default()
+            },
+        );
+        list[0].style_overrides.insert(
+            StyleSlot::BorderFocused,
+            StyleOverride {
+                fg: Some(Color::LightGreen),
+                ..StyleOverride::default()
+            },
+        );
+        let mut app = App::new(
+            list,
+            "aurora",
+            false,
+            false,
+            true,
+            KeyBindings::default(),
+            DemoPanel::Overview,
+        );
+
+        let backend = TestBackend::new(120, 24);
+        let mut terminal = Terminal::new(backend).expect("terminal");
+        terminal.draw(|f| super::draw(f, &app)).expect("draw");
+        let buf = terminal.backend().buffer();
+        assert_eq!(buf[(0, 0)].fg, Color::Magenta);
+        let ready = (1..115)
+            .find(|&x| (0..5).all(|i| buf[(x + i, 1)].symbol() == &"ready"[i as usize..][..1]))
+            .expect("ready status");
+        assert_eq!(buf[(ready, 1)].fg, Color::Yellow);
+
+        app.show_help = true;
+        terminal.draw(|f| super::draw(f, &app)).expect("draw");
+        let buf = terminal.backend().buffer();
+        let corner = (0..24)
+            .flat_map(|y| (1..116).map(move |x| (x, y)))
+            .find(|&(x, y)| {
+                (0..6).all(|i| buf[(x + i, y)].symbol() == &" Help "[i as usize..][..1])
+            })
+            .map(|(x, y)| (x - 1, y))
+            .expect("help popup title");
+        assert_eq!(buf[corner].fg, Color::LightGreen);
+    }
+
+    #[test]
+    fn footer_shows_reload_status() {
+        let mut app = App::new(
+            themes(),
+            "aurora",
+            true,
+            false,
+            true,
+            KeyBindings::default(),
+            DemoPanel::Overview,
+        );
+        app.report_reload_error("invalid config TOML".to_string());
+
+        let lines = render_lines(120, 24, &app);
+        let footer = &lines[lines.len() - 2];
+        assert!(footer.contains("Config error: invalid config TOML"));
+        assert!(!footer.contains("for help"));
+    }
+
+    #[test]
+    fn theme_previews_lay_out_cards_side_by_side() {
+        let list = themes();
+        let text = super::render_theme_previews(&list[..3], 80, true, false).expect("render");
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 2 * super::PREVIEW_CARD_HEIGHT as usize);
+        assert!(lines[0].starts_with("+ aurora -"));
+        assert!(lines[0].contains("++ mono -"));
+        assert!(lines[11].starts_with("+ solar -"));
+        assert!(text.contains("## muted"));
+        assert!(!text.contains('\x1b'));
+    }
+
+    #[test]
+    fn theme_previews_emit_ansi_colors() {
+        let mut list = themes();
+        list.truncate(1);
+        list[0].palette.accent = Color::Rgb(1, 2, 3);
+        let text = super::render_theme_previews(&list, 40, false, true).expect("render");
+
+        assert!(text.contains("\x1b[0;38;2;1;2;3;40m██"));
+        assert!(text.lines().all(|line| line.ends_with("\x1b[0m")));
+    }
+
+    #[test]
+    fn sgr_sequence_covers_modifiers_and_color_kinds() {
+        use ratatui::style::Modifier;
+
+        assert_eq!(
+            super::sgr_sequence(Color::Reset, Color::Reset, Modifier::empty()),
+            "\x1b[0m"
+        );
+        assert_eq!(
+            super::sgr_sequence(Color::LightRed, Color::Black, Modifier::BOLD),
+            "\x1b[0;1;91;40m"
+        );
+        assert_eq!(
+            super::sgr_sequence(
+                Color::Indexed(208),
+                Color::Rgb(10, 20, 30),
+                Modifier::ITALIC | Modifier::UNDERLINED
+            ),
+            "\x1b[0;3;4;38;5;208;48;2;10;20;30m"
+        );
+    }
 }
@@ -1,4 +1,5 @@
 use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::PredicateBooleanExt;
 use predicates::str::contains;
 use std::fs;
 use std::path::PathBuf;
@@ -111,7 +112,8 @@ fn demo_help_includes_config_and_override_flags() {
         .stdout(contains("--motion"))
         .stdout(contains("--mouse"))
         .stdout(contains("--no-mouse"))
-        .stdout(contains("--normal-contrast"));
+        .stdout(contains("--normal-contrast"))
+        .stdout(contains("--color-depth"));
 }
 
 fn unique_temp_dir() -> PathBuf {
@@ -150,6 +152,614 @@ fn keys_reflect_config_overrides_via_xdg_config_home() {
         .stdout(contains("- x/esc/ctrl+c: quit"));
 }
 
+#[test]
+fn keys_mark_preset_and_config_bindings() {
+    let root = unique_temp_dir();
+    fs::create_dir_all(&root).expect("create dir");
+    let path = root.join("config.toml");
+    fs::write(
+        &path,
+        r#"
+        [keys]
+        preset = "emacs"
+        cycle_theme = "n"
+
+        [keys.overview]
+        toggle_color = "j"
+        "#,
+    )
+    .expect("write config");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["keys", "--config"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(contains("Key bindings (preset: emacs):"))
+        .stdout(contains("- n: cycle theme (config)\n"))
+        .stdout(contains("- ctrl+n/down: list down [list]\n"))
+        .stdout(contains("- j: toggle color [overview] (config)\n"));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args([
+        "keys",
+        "--keymap-preset",
+        "vim",
+        "--format",
+        "json",
+        "--config",
+    ])
+    .arg(&path)
+    .assert()
+    .success()
+    .stdout(contains("\"preset\": \"vim\""))
+    .stdout(contains("\"from_config\": [\n    \"cycle_theme\"\n  ]"))
+    .stdout(contains("\"g t\""));
+}
+
+#[test]
+fn keys_explain_traces_bindings_to_their_source() {
+    let root = unique_temp_dir();
+    fs::create_dir_all(&root).expect("create dir");
+    let path = root.join("config.toml");
+    fs::write(
+        &path,
+        "[keys]\npreset = \"vim\"\ncycle_theme = \"n\"\n\n[keys.overview]\ntoggle_color = \"x\"\n",
+    )
+    .expect("write config");
+    let location = |line: usize| format!("{}:{}", path.display(), line);
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["keys", "--explain", "--config"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(contains(format!(
+            "Key bindings (preset: vim, from {}):",
+            location(2)
+        )))
+        .stdout(contains(format!("    n          {} [keys]\n", location(3))))
+        .stdout(contains(
+            "    g t        preset vim\n    tab        default\n",
+        ))
+        .stdout(contains(format!(
+            "- toggle color [overview]:\n    x          {} [keys.overview]\n",
+            location(6)
+        )))
+        .stdout(contains(
+            "Reserved keys (always quit, not configurable):\n    esc",
+        ));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args([
+        "keys",
+        "--explain",
+        "--keymap-preset",
+        "emacs",
+        "--format",
+        "json",
+        "--config",
+    ])
+    .arg(&path)
+    .assert()
+    .success()
+    .stdout(contains("\"preset_source\": \"cli\""))
+    .stdout(contains(
+        "\"key\": \"ctrl+x o\",\n      \"source\": \"preset\"",
+    ))
+    .stdout(contains("\"line\": 3,\n        \"table\": \"keys\""))
+    .stdout(contains("\"key\": \"ctrl+c\""));
+}
+
+#[test]
+fn keys_render_reference_cards() {
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["keys", "--keymap-preset", "vim", "--format", "markdown"])
+        .assert()
+        .success()
+        .stdout(contains("## Navigation\n"))
+        .stdout(contains("| `j`, `down` | list down | list |\n"))
+        .stdout(contains("## Reserved\n"));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["keys", "--format", "csv"])
+        .assert()
+        .success()
+        .stdout(contains("category,action,description,context,key\n"))
+        .stdout(contains("Display,cycle_theme,cycle theme,global,t\n"));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["keys", "--explain", "--format", "man"])
+        .assert()
+        .failure()
+        .stderr(contains(
+            "supports only `--format text` and `--format json`",
+        ));
+}
+
+#[test]
+fn keys_accept_sequences_and_reject_prefix_conflicts() {
+    let root = unique_temp_dir();
+    fs::create_dir_all(&root).expect("create dir");
+    let path = root.join("config.toml");
+    fs::write(
+        &path,
+        r#"
+        [keys]
+        cycle_theme = ["t", "g t"]
+        quit = ["q", "ctrl+x ctrl+q"]
+        "#,
+    )
+    .expect("write config");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["keys", "--config"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(contains("- t/g t: cycle theme"));
+
+    fs::write(
+        &path,
+        r#"
+        [keys]
+        cycle_theme = "g"
+        toggle_help = "g g"
+        "#,
+    )
+    .expect("write config");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["config", "validate", "--config"])
+        .arg(&path)
+        .assert()
+        .failure()
+        .stderr(contains(
+            "key binding 'g' for 'cycle_theme' is a prefix of 'g g' for 'toggle_help'",
+        ));
+}
+
+fn brand_theme_config() -> &'static str {
+    r#"
+    [themes.brand]
+    description = "Brand palette"
+    fg = "white"
+    bg = "black"
+    accent = "lightmagenta"
+    muted = "gray"
+    success = "lightgreen"
+    danger = "lightred"
+    "#
+}
+
+#[test]
+fn themes_include_config_defined_themes() {
+    let root = unique_temp_dir();
+    let config_dir = root.join("cli-tui-starter");
+    fs::create_dir_all(config_dir.join("themes")).expect("create themes dir");
+    fs::write(config_dir.join("config.toml"), brand_theme_config()).expect("write config");
+    fs::write(
+        config_dir.join("themes").join("ocean.toml"),
+        r#"
+        fg = "white"
+        bg = "black"
+        accent = "cyan"
+        muted = "gray"
+        success = "green"
+        danger = "red"
+        "#,
+    )
+    .expect("write theme file");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.arg("themes")
+        .env("XDG_CONFIG_HOME", &root)
+        .assert()
+        .success()
+        .stdout(contains("- aurora:"))
+        .stdout(contains("- brand: Brand palette (config)"))
+        .stdout(contains("- ocean: Custom theme (config)"));
+}
+
+#[test]
+fn demo_no_tty_accepts_config_defined_theme() {
+    let root = unique_temp_dir();
+    fs::create_dir_all(&root).expect("create dir");
+    let path = root.join("config.toml");
+    fs::write(&path, brand_theme_config()).expect("write config");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["demo", "--no-tty", "--theme", "brand", "--config"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(contains("Theme: brand"));
+}
+
+#[test]
+fn config_lint_warns_and_denies_on_request() {
+    let root = unique_temp_dir();
+    fs::create_dir_all(&root).expect("create dir");
+    let path = root.join("config.toml");
+    fs::write(&path, "[keys]\nquit = \"ctrl+z\"\n").expect("write config");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["config", "lint", "--config"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(contains("warning[terminal-shortcut]: 'ctrl+z' for 'quit'"))
+        .stdout(contains(format!("  --> {}:2\n", path.display())))
+        .stdout(contains("1 warning(s), 0 info\n"));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args([
+        "config", "lint", "--deny", "warnings", "--format", "json", "--config",
+    ])
+    .arg(&path)
+    .assert()
+    .failure()
+    .stdout(contains("\"ok\": false"))
+    .stdout(contains("\"rule\": \"terminal-shortcut\""))
+    .stderr(contains("1 lint finding(s) denied by --deny"));
+
+    fs::write(&path, "[keys]\npreset = \"vim\"\n").expect("write config");
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["config", "lint", "--deny", "info", "--config"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout("0 warning(s), 0 info\n");
+}
+
+#[test]
+fn config_validate_reports_theme_inheritance_cycles() {
+    let root = unique_temp_dir();
+    fs::create_dir_all(&root).expect("create dir");
+    let path = root.join("config.toml");
+    fs::write(
+        &path,
+        r#"
+        [themes.dawn]
+        extends = "dusk"
+
+        [themes.dusk]
+        extends = "dawn"
+        "#,
+    )
+    .expect("write config");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["config", "validate", "--config"])
+        .arg(&path)
+        .assert()
+        .failure()
+        .stderr(contains(
+            "error: theme inheritance cycle: dawn -> dusk -> dawn\n",
+        ))
+        .stderr(contains("config.toml:3:19\n"));
+
+    fs::write(
+        &path,
+        r#"
+        [demo]
+        theme = "dusk"
+
+        [themes.dusk]
+        extends = "solar"
+        accent = "lightmagenta"
+        "#,
+    )
+    .expect("write config");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["themes", "--config"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(contains("- dusk: Based on solar (config)"));
+}
+
+#[test]
+fn demo_no_tty_accepts_truecolor_theme_with_color_depth() {
+    let root = unique_temp_dir();
+    fs::create_dir_all(&root).expect("create dir");
+    let path = root.join("config.toml");
+    fs::write(
+        &path,
+        r##"
+        [demo]
+        theme = "ember"
+        color_depth = "16"
+
+        [themes.ember]
+        fg = "#f5f5f5"
+        bg = "236"
+        accent = "#ff8700"
+        muted = "245"
+        success = "#5fd75f"
+        danger = "#ff5f5f"
+        "##,
+    )
+    .expect("write config");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["demo", "--no-tty", "--color-depth", "256", "--config"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(contains("Theme: ember"));
+}
+
+#[test]
+fn themes_check_passes_for_builtin_themes() {
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["themes", "check"])
+        .env("XDG_CONFIG_HOME", unique_temp_dir())
+        .assert()
+        .success()
+        .stdout(contains("high-contrast"))
+        .stdout(contains("0 failed"));
+
+    // Options before the subcommand belong to the theme list; `check` takes its own.
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["themes", "--format", "json", "check"])
+        .env("XDG_CONFIG_HOME", unique_temp_dir())
+        .assert()
+        .failure()
+        .stderr(contains("cannot be used with '--format <FORMAT>'"));
+}
+
+#[test]
+fn themes_preview_renders_cards_with_optional_color() {
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args([
+        "themes",
+        "preview",
+        "solar",
+        "--ascii",
+        "--color-depth",
+        "256",
+    ])
+    .env("XDG_CONFIG_HOME", unique_temp_dir())
+    .env_remove("NO_COLOR")
+    .assert()
+    .success()
+    .stdout(contains(" solar "))
+    .stdout(contains(" success "))
+    .stdout(contains("\x1b[0;"));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["themes", "preview", "--ascii"])
+        .env("XDG_CONFIG_HOME", unique_temp_dir())
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(contains("+ aurora-light -"))
+        .stdout(contains("\x1b[").not());
+}
+
+#[test]
+fn themes_export_writes_terminal_formats() {
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["themes", "export", "aurora", "--to", "kitty"])
+        .env("XDG_CONFIG_HOME", unique_temp_dir())
+        .assert()
+        .success()
+        .stdout(contains("background #000000"))
+        .stdout(contains("color15 #ffffff"));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["themes", "export", "neon", "--to", "alacritty"])
+        .env("XDG_CONFIG_HOME", unique_temp_dir())
+        .assert()
+        .failure()
+        .stderr(contains("unknown theme 'neon'"));
+}
+
+#[test]
+fn themes_import_writes_base16_scheme_to_themes_dir() {
+    let root = unique_temp_dir();
+    fs::create_dir_all(&root).expect("create temp dir");
+    let scheme = root.join("tomorrow.yaml");
+    fs::write(
+        &scheme,
+        r#"scheme: "Tomorrow Night"
+author: "Chris Kempson"
+base00: "1d1f21"
+base03: "969896"
+base05: "c5c8c6"
+base08: "cc6666"
+base0B: "b5bd68"
+base0D: "81a2be"
+"#,
+    )
+    .expect("write scheme");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["themes", "import"])
+        .arg(&scheme)
+        .env("XDG_CONFIG_HOME", &root)
+        .assert()
+        .success()
+        .stdout(contains("[themes.tomorrow-night]"))
+        .stdout(contains("accent = \"#81a2be\""));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["themes", "import", "--write"])
+        .arg(&scheme)
+        .env("XDG_CONFIG_HOME", &root)
+        .assert()
+        .success()
+        .stdout(contains("Wrote theme 'tomorrow-night'"));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.arg("themes")
+        .env("XDG_CONFIG_HOME", &root)
+        .assert()
+        .success()
+        .stdout(contains(
+            "tomorrow-night: Tomorrow Night (base16, Chris Kempson) (config)",
+        ));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["themes", "import", "--write"])
+        .arg(&scheme)
+        .env("XDG_CONFIG_HOME", &root)
+        .assert()
+        .failure()
+        .stderr(contains("--force"));
+}
+
+#[test]
+fn themes_import_writes_where_the_keys_panel_saves() {
+    let root = unique_temp_dir();
+    let project = root.join("project");
+    fs::create_dir_all(&project).expect("create dirs");
+    let scheme = root.join("ocean.yaml");
+    fs::write(
+        &scheme,
+        "scheme: \"Ocean\"\nbase00: \"2b303b\"\nbase03: \"65737e\"\nbase05: \"c0c5ce\"\nbase08: \"bf616a\"\nbase0B: \"a3be8c\"\nbase0D: \"8fa1b3\"\n",
+    )
+    .expect("write scheme");
+
+    // The last --config wins, as for the Keys panel.
+    let first = root.join("first").join("config.toml");
+    let second = root.join("second").join("config.toml");
+    for path in [&first, &second] {
+        fs::create_dir_all(path.parent().expect("parent")).expect("create dir");
+        fs::write(path, "").expect("write config");
+    }
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["themes", "import", "--write", "--config"])
+        .arg(&first)
+        .arg("--config")
+        .arg(&second)
+        .arg(&scheme)
+        .env("XDG_CONFIG_HOME", &root)
+        .assert()
+        .success();
+    assert!(root.join("second/themes/ocean.toml").is_file());
+    assert!(!root.join("first/themes").exists());
+
+    // A project file has no themes/ directory, so the table is added to it.
+    let project_file = project.join(".cli-tui-starter.toml");
+    fs::write(&project_file, "[demo]\nmouse = true\n").expect("write project config");
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["themes", "import", "--write"])
+        .arg(&scheme)
+        .current_dir(&project)
+        .env("XDG_CONFIG_HOME", root.join("xdg"))
+        .assert()
+        .success();
+    let contents = fs::read_to_string(&project_file).expect("read project config");
+    assert!(
+        contents.starts_with("[demo]\nmouse = true\n\n[themes.ocean]\n"),
+        "{}",
+        contents
+    );
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.arg("themes")
+        .current_dir(&project)
+        .env("XDG_CONFIG_HOME", root.join("xdg"))
+        .assert()
+        .success()
+        .stdout(contains("ocean: Ocean (base16)"));
+}
+
+#[test]
+fn themes_check_fails_on_low_contrast_theme() {
+    let root = unique_temp_dir();
+    fs::create_dir_all(&root).expect("create dir");
+    let path = root.join("config.toml");
+    fs::write(
+        &path,
+        r##"
+        [themes.murky]
+        fg = "white"
+        bg = "black"
+        accent = "blue"
+        muted = "#333333"
+        success = "green"
+        danger = "lightred"
+        "##,
+    )
+    .expect("write config");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["themes", "check", "murky", "--format", "json", "--config"])
+        .arg(&path)
+        .assert()
+        .failure()
+        .stdout(contains("\"ok\": false"))
+        .stdout(contains("\"slot\": \"muted\""))
+        .stderr(contains("below WCAG AA"));
+}
+
+#[test]
+fn demo_no_tty_accepts_cvd_simulation() {
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args([
+        "demo",
+        "--no-tty",
+        "--width",
+        "120",
+        "--simulate",
+        "protanopia",
+    ])
+    .env_remove("NO_COLOR")
+    .assert()
+    .success()
+    .stdout(contains("Simulating: protanopia"))
+    .stdout(contains("\x1b[0;"));
+}
+
+#[test]
+fn demo_no_tty_auto_theme_follows_background() {
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args([
+        "demo",
+        "--no-tty",
+        "--width",
+        "120",
+        "--theme",
+        "auto",
+        "--background",
+        "light",
+    ])
+    .env("XDG_CONFIG_HOME", unique_temp_dir())
+    .assert()
+    .success()
+    .stdout(contains("Theme: aurora-light"));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["demo", "--no-tty", "--width", "120", "--theme", "auto"])
+        .env("XDG_CONFIG_HOME", unique_temp_dir())
+        .env("COLORFGBG", "0;15")
+        .assert()
+        .success()
+        .stdout(contains("Theme: aurora-light"));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["demo", "--no-tty", "--width", "120", "--theme", "auto"])
+        .env("XDG_CONFIG_HOME", unique_temp_dir())
+        .env_remove("COLORFGBG")
+        .assert()
+        .success()
+        .stdout(contains("Theme: aurora |"));
+}
+
+#[test]
+fn demo_rejects_unknown_theme() {
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["demo", "--no-tty", "--theme", "neon"])
+        .env("XDG_CONFIG_HOME", unique_temp_dir())
+        .assert()
+        .failure()
+        .stderr(contains("unknown theme 'neon'"))
+        .stderr(contains("aurora, mono, solar"));
+}
+
 #[test]
 fn config_init_writes_starter_config_to_default_xdg_path() {
     let root = unique_temp_dir();
@@ -246,6 +856,289 @@ fn config_validate_fails_when_default_missing() {
         .stderr(contains("config init"));
 }
 
+#[test]
+fn every_command_reports_config_errors_with_location_and_suggestion() {
+    let root = unique_temp_dir();
+    fs::create_dir_all(&root).expect("create dir");
+    let path = root.join("bad.toml");
+    fs::write(&path, "[demo]\nno_colour = true\n").expect("write config");
+
+    for args in [&["demo", "--no-tty"][..], &["keys"], &["config", "show"]] {
+        let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+        cmd.args(args)
+            .arg("--config")
+            .arg(&path)
+            .env("XDG_CONFIG_HOME", &root)
+            .assert()
+            .failure()
+            .stderr(contains(format!(
+                "error: unknown key 'no_colour' in [demo]\n --> {}:2:1\n",
+                path.display()
+            )))
+            .stderr(contains("= help: did you mean 'no_color'?\n"))
+            .stderr(contains("found 1 config error(s)"));
+    }
+}
+
+#[test]
+fn config_files_named_like_env_variables_keep_their_locations() {
+    let root = unique_temp_dir();
+    fs::create_dir_all(&root).expect("create dir");
+    let path = root.join("CLI_TUI_STARTER_ci.toml");
+    fs::write(&path, "[demo]\nmose = true\n").expect("write config");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["config", "validate", "--config"])
+        .arg(&path)
+        .env("XDG_CONFIG_HOME", &root)
+        .assert()
+        .failure()
+        .stderr(contains(format!(
+            " --> {}:2:1\n  |\n2 | mose = true\n  | ^^^^\n",
+            path.display()
+        )));
+}
+
+#[test]
+fn config_validate_reports_every_error_with_location_and_suggestion() {
+    let root = unique_temp_dir();
+    fs::create_dir_all(&root).expect("create dir");
+    let path = root.join("config.toml");
+    fs::write(
+        &path,
+        "[demo]\ntheme = \"solr\"\nmose = true\n\n[keys]\nquit = \"ctl+x\"\n",
+    )
+    .expect("write config");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["config", "validate", "--config"])
+        .arg(&path)
+        .env("XDG_CONFIG_HOME", &root)
+        .assert()
+        .failure()
+        .stderr(contains(format!(
+            "error: unknown theme 'solr'\n --> {}:2:9\n  |\n2 | theme = \"solr\"\n  |         ^^^^^^\n  = help: did you mean 'solar'?\n",
+            path.display()
+        )))
+        .stderr(contains("did you mean 'mouse'?"))
+        .stderr(contains("did you mean 'ctrl+x'?"))
+        .stderr(contains("found 3 config error(s)"));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["config", "validate", "--format", "json", "--config"])
+        .arg(&path)
+        .env("XDG_CONFIG_HOME", &root)
+        .assert()
+        .failure()
+        .stdout(contains("\"ok\": false"))
+        .stdout(contains("\"line\": 3,\n      \"column\": 1,"))
+        .stdout(contains("\"message\": \"unknown key 'mose' in [demo]\""))
+        .stdout(contains("\"suggestion\": \"mouse\""));
+}
+
+#[test]
+fn config_migrate_upgrades_old_files_in_place() {
+    let root = unique_temp_dir();
+    fs::create_dir_all(&root).expect("create dir");
+    let path = root.join("config.toml");
+    fs::write(
+        &path,
+        "# team config\n[keys]\nquit = \"x\" # quick exit\n# vi-style scrolling\nlist_down = \"j\"\n",
+    )
+    .expect("write config");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["config", "validate", "--config"])
+        .arg(&path)
+        .env("XDG_CONFIG_HOME", &root)
+        .assert()
+        .success()
+        .stdout(contains(format!(
+            "note: {} uses config version 1 (current is 2)",
+            path.display()
+        )));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["config", "migrate", "--write", "--config"])
+        .arg(&path)
+        .env("XDG_CONFIG_HOME", &root)
+        .assert()
+        .success()
+        .stdout(contains("from config version 1 to 2"));
+    let migrated = fs::read_to_string(&path).expect("read config");
+    assert_eq!(
+        migrated,
+        "# team config\nversion = 2\n\n[keys]\nquit = \"x\" # quick exit\n\n[keys.list]\n# vi-style scrolling\nlist_down = \"j\"\n"
+    );
+
+    fs::write(&path, "version = 9\n[keys]\nscroll = \"j\"\n").expect("write config");
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["keys", "--config"])
+        .arg(&path)
+        .env("XDG_CONFIG_HOME", &root)
+        .assert()
+        .failure()
+        .stderr(contains(
+            "error: config version 9 is newer than this cli-tui-starter supports (up to 2)\n",
+        ))
+        .stderr(contains("found 1 config error(s)"));
+}
+
+#[test]
+fn config_layers_merge_user_project_and_explicit_files() {
+    let root = unique_temp_dir();
+    let user_dir = root.join("xdg").join("cli-tui-starter");
+    let project = root.join("project");
+    let nested = project.join("src");
+    fs::create_dir_all(&user_dir).expect("create user dir");
+    fs::create_dir_all(&nested).expect("create project dir");
+    fs::write(
+        user_dir.join("config.toml"),
+        "[keys]\ncycle_theme = \"n\"\nquit = \"x\"\n",
+    )
+    .expect("write user config");
+    fs::write(
+        project.join(".cli-tui-starter.toml"),
+        "[keys]\ncycle_theme = \"m\"\n",
+    )
+    .expect("write project config");
+    let first = root.join("first.toml");
+    let second = root.join("second.toml");
+    fs::write(&first, "[keys]\ntoggle_help = \"f1\"\n").expect("write first");
+    fs::write(&second, "[keys]\ntoggle_help = \"f2\"\n").expect("write second");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["keys", "--config"])
+        .arg(&first)
+        .arg("--config")
+        .arg(&second)
+        .current_dir(&nested)
+        .env("XDG_CONFIG_HOME", root.join("xdg"))
+        .assert()
+        .success()
+        .stdout(contains("- m: cycle theme (config)\n"))
+        .stdout(contains("- x/esc/ctrl+c: quit (config)\n"))
+        .stdout(contains("- f2: toggle help (config)\n"));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["config", "validate", "--config"])
+        .arg(&first)
+        .current_dir(&nested)
+        .env("XDG_CONFIG_HOME", root.join("xdg"))
+        .assert()
+        .success()
+        .stdout(contains(format!(
+            "Config OK: {} (project)\n",
+            project.join(".cli-tui-starter.toml").display()
+        )))
+        .stdout(contains(format!(
+            "Config OK: {} (explicit)\n",
+            first.display()
+        )))
+        .stdout(contains("Merged config OK (3 layers)\n"));
+
+    // Each layer is fine on its own, but together `m` would mean two things.
+    fs::write(&second, "[keys]\ntoggle_color = \"m\"\n").expect("write second");
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["config", "validate", "--config"])
+        .arg(&second)
+        .current_dir(&nested)
+        .env("XDG_CONFIG_HOME", root.join("xdg"))
+        .assert()
+        .failure()
+        .stderr(contains(format!(
+            "error: duplicate key binding 'm' used for multiple actions\n --> {}:2:15\n",
+            project.join(".cli-tui-starter.toml").display()
+        )))
+        .stderr(contains(format!(
+            "= help: the other binding is set at {}:2\n",
+            second.display()
+        )));
+}
+
+#[test]
+fn env_vars_override_config_files_but_not_cli_flags() {
+    let root = unique_temp_dir();
+    let config_dir = root.join("cli-tui-starter");
+    fs::create_dir_all(&config_dir).expect("create config dir");
+    fs::write(
+        config_dir.join("config.toml"),
+        "[demo]\ntheme = \"solar\"\n\n[keys]\nquit = \"x\"\n",
+    )
+    .expect("write config");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.arg("keys")
+        .env("XDG_CONFIG_HOME", &root)
+        .env("CLI_TUI_STARTER_KEYS__QUIT", "[\"z\", \"f10\"]")
+        .assert()
+        .success()
+        .stdout(contains("- z/f10/esc/ctrl+c: quit (config)"));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["demo", "--no-tty", "--theme", "mono"])
+        .env("XDG_CONFIG_HOME", &root)
+        .env("CLI_TUI_STARTER_DEMO__THEME", "aurora")
+        .assert()
+        .success()
+        .stdout(contains("Theme: mono"));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["config", "validate"])
+        .env("XDG_CONFIG_HOME", &root)
+        .env("CLI_TUI_STARTER_DEMO__MOUSE", "sometimes")
+        .assert()
+        .failure()
+        .stderr(contains("invalid value for CLI_TUI_STARTER_DEMO__MOUSE"));
+}
+
+#[test]
+fn config_show_prints_effective_values_with_origins() {
+    let root = unique_temp_dir();
+    let config_dir = root.join("cli-tui-starter");
+    fs::create_dir_all(&config_dir).expect("create config dir");
+    let path = config_dir.join("config.toml");
+    fs::write(
+        &path,
+        "[demo]\ntheme = \"solar\"\nmouse = true\n\n[keys]\npreset = \"vim\"\nquit = \"x\"\n",
+    )
+    .expect("write config");
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["config", "show", "--origin", "--no-mouse"])
+        .env("XDG_CONFIG_HOME", &root)
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(contains("[demo]\ntheme = \"solar\""))
+        .stdout(contains(format!("# {}:2\n", path.display())))
+        .stdout(contains("no_color = true"))
+        .stdout(contains("# env NO_COLOR\n"))
+        .stdout(contains("# --no-mouse\n"))
+        .stdout(contains("# preset vim\n"))
+        .stdout(contains(format!("# {}:7\n", path.display())));
+
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["config", "show", "--format", "json", "--origin"])
+        .env("XDG_CONFIG_HOME", &root)
+        .env_remove("NO_COLOR")
+        .assert()
+        .success()
+        .stdout(contains("\"quit\": \"x\""))
+        .stdout(contains("\"mouse\": true"))
+        .stdout(contains(
+            "\"keys.preset\": \"{}:6\"".replace("{}", &path.display().to_string()),
+        ));
+
+    // Preview-only `demo` options are not settings.
+    let mut cmd = cargo_bin_cmd!("cli-tui-starter");
+    cmd.args(["config", "show", "--width", "100"])
+        .env("XDG_CONFIG_HOME", &root)
+        .assert()
+        .failure()
+        .stderr(contains("unexpected argument '--width'"));
+}
+
 fn dummy_config_for_validate() -> &'static str {
     r#"
     [demo]